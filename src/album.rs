@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// チャプターごとに分割したファイルの出力テンプレート（アルバムフォルダ内に "01 - 曲名.mp3"）
+pub const TRACK_TEMPLATE: &str = "%(title)s/%(section_number)02d - %(section_title)s.%(ext)s";
+
+/// 分割前のファイルの出力テンプレート（動画タイトルのフォルダをアルバムフォルダにする）
+pub const ALBUM_TEMPLATE: &str = "%(title)s/%(title)s.%(ext)s";
+
+/// チャプターごとに分割された曲にトラック番号と曲名のタグを付ける
+///
+/// `source` は分割前のファイルで、同じフォルダにある "NN - 曲名.ext" のファイルを曲として扱います。
+/// アルバム名・アーティストなどは分割前のファイルから引き継がれます。
+/// `keep_source` がfalseなら分割前のファイルを削除します。
+/// タグを付けた曲のパスをトラック番号順に返します。
+pub fn tag_tracks(source: &Path, keep_source: bool) -> Result<Vec<PathBuf>> {
+    let album_dir = source
+        .parent()
+        .ok_or_else(|| YtdlError::Other(t!("アルバムフォルダが不明です: {}", source.display())))?;
+
+    let mut tracks: Vec<(u32, String, PathBuf)> = std::fs::read_dir(album_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path != source)
+        .filter_map(|path| {
+            let (number, title) = parse_track_file_name(path.file_name()?.to_str()?)?;
+            Some((number, title.to_string(), path))
+        })
+        .collect();
+    tracks.sort_by_key(|(number, _, _)| *number);
+
+    if tracks.is_empty() {
+        return Err(YtdlError::Other(
+            t!("チャプターが見つからないため、曲ごとに分割できませんでした").to_string(),
+        ));
+    }
+
+    let total = tracks.len();
+    for (number, title, path) in &tracks {
+        write_track_tags(path, *number, total, title)?;
+    }
+
+    if !keep_source {
+        std::fs::remove_file(source)?;
+    }
+    println!("{}", t!("💿 {}曲に分割しました: {}", total, album_dir.display()));
+
+    Ok(tracks.into_iter().map(|(_, _, path)| path).collect())
+}
+
+/// ffmpegでトラック番号・曲名のタグを書き込む（他のタグ・カバー画像はそのまま）
+fn write_track_tags(path: &Path, number: u32, total: usize, title: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".tagging-{}", file_name));
+
+    let status = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(path)
+        .args(["-map", "0", "-c", "copy"])
+        .arg("-metadata")
+        .arg(format!("track={}/{}", number, total))
+        .arg("-metadata")
+        .arg(format!("title={}", title))
+        .arg(&temp)
+        .status()
+        .map_err(|e| YtdlError::ProcessError(t!("ffmpegの起動失敗: {}", e)))?;
+
+    if !status.success() {
+        let _ = std::fs::remove_file(&temp);
+        return Err(YtdlError::ProcessError(t!(
+            "タグを書き込めませんでした: {}",
+            path.display()
+        )));
+    }
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// 分割したファイル名からトラック番号と曲名を取り出す（例: "03 - Intro.mp3" -> (3, "Intro")）
+fn parse_track_file_name(name: &str) -> Option<(u32, &str)> {
+    let (number, rest) = name.split_once(" - ")?;
+    let number = number.parse().ok()?;
+    let title = rest.rsplit_once('.').map_or(rest, |(title, _)| title);
+    Some((number, title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_track_file_name() {
+        assert_eq!(parse_track_file_name("03 - Intro.mp3"), Some((3, "Intro")));
+        assert_eq!(
+            parse_track_file_name("12 - Side B - Finale.m4a"),
+            Some((12, "Side B - Finale"))
+        );
+        assert_eq!(parse_track_file_name("Live Set.mp3"), None);
+        assert_eq!(parse_track_file_name("Live - Set.mp3"), None);
+    }
+}
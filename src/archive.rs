@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::paths;
+
+/// アーカイブファイルのデフォルトの名前（保存先に作成）
+pub const DEFAULT_FILE_NAME: &str = "downloaded.txt";
+
+/// ダウンロード済みアーカイブ（yt-dlpの `--download-archive`）の1件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// 抽出器の名前（YouTubeなら "youtube"）
+    pub extractor: String,
+    /// 動画ID
+    pub id: String,
+}
+
+/// アーカイブファイルのデフォルトの場所（保存先の "downloaded.txt"）
+pub fn default_path() -> PathBuf {
+    paths::default_output_dir().0.join(DEFAULT_FILE_NAME)
+}
+
+/// 記録されている動画を読み込む（ファイルがなければ空）
+pub fn read_entries(path: &Path) -> Result<Vec<ArchiveEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_entries(&std::fs::read_to_string(path)?))
+}
+
+/// 指定した動画IDの記録を削除（削除した件数を返す）
+pub fn remove_ids(path: &Path, ids: &[String]) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let content = std::fs::read_to_string(path)?;
+    let (kept, removed) = remove_lines(&content, ids);
+    if removed > 0 {
+        std::fs::write(path, kept)?;
+    }
+    Ok(removed)
+}
+
+/// yt-dlpがアーカイブ済みとしてスキップした動画のID
+/// （"[download] 動画ID: タイトル has already been recorded in the archive"）
+pub fn already_recorded(line: &str) -> Option<String> {
+    let reason = line
+        .strip_prefix("[download] ")?
+        .strip_suffix("has already been recorded in the archive")?;
+    let (id, _) = reason.split_once(": ")?;
+    (!id.is_empty() && !id.contains(char::is_whitespace)).then(|| id.to_string())
+}
+
+/// "youtube dQw4w9WgXcQ" の形式の行を読み込む（空行・不正な行は無視）
+fn parse_entries(content: &str) -> Vec<ArchiveEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (extractor, id) = line.trim().split_once(' ')?;
+            Some(ArchiveEntry {
+                extractor: extractor.to_string(),
+                id: id.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 指定したIDの行を除いた内容と、除いた行数
+fn remove_lines(content: &str, ids: &[String]) -> (String, usize) {
+    let mut removed = 0;
+    let mut kept = String::new();
+    for line in content.lines() {
+        let matches = line
+            .trim()
+            .split_once(' ')
+            .is_some_and(|(_, id)| ids.iter().any(|target| target == id.trim()));
+        if matches {
+            removed += 1;
+        } else {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    (kept, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_remove() {
+        let content = "youtube abc123\n\nyoutube def456\nniconico sm9\n";
+        let entries = parse_entries(content);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[2],
+            ArchiveEntry {
+                extractor: "niconico".to_string(),
+                id: "sm9".to_string()
+            }
+        );
+
+        let (kept, removed) = remove_lines(content, &["def456".to_string(), "zzz".to_string()]);
+        assert_eq!(removed, 1);
+        assert_eq!(kept, "youtube abc123\n\nniconico sm9\n");
+    }
+
+    #[test]
+    fn test_already_recorded() {
+        assert_eq!(
+            already_recorded("[download] abc123: Lecture 1 has already been recorded in the archive").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            already_recorded("[download] abc123: has already been recorded in the archive").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(already_recorded("[download] /tmp/a.mp4 has already been downloaded"), None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::t;
+
+/// 断片の同時ダウンロード数の上限
+const MAX_FRAGMENTS: u32 = 16;
+
+/// `--parallel` を指定しない場合の、動画の同時ダウンロード本数の上限
+pub const DEFAULT_MAX_PARALLEL: usize = 4;
+
+/// 前回より速度がこの割合を下回ったら、最後に上げた設定を戻す
+const SLOWDOWN_RATIO: f64 = 0.75;
+
+/// 1回のyt-dlpの結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// 完了（ダウンロードしたサイズと所要時間）
+    Finished { bytes: u64, elapsed: Duration },
+    /// HTTP 429（リクエストが多すぎる）
+    RateLimited,
+    /// その他の失敗
+    Failed,
+}
+
+/// 最後に上げた設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Fragments,
+    Parallel,
+}
+
+#[derive(Debug)]
+struct State {
+    fragments: u32,
+    parallel: usize,
+    /// これまでで最も速い速度（バイト/秒）
+    best_speed: f64,
+    last_step: Option<Step>,
+    /// 速度が上がらなくなった（これ以上は上げない）
+    settled: bool,
+}
+
+/// 断片・動画の同時ダウンロード数の自動調整（`--auto-tune`）
+///
+/// 控えめな設定（どちらも1）から始め、速度が落ちない間は断片、次に動画の同時ダウンロード数を上げます。
+/// 429を受けたら半分に下げ、上げて遅くなった場合は元に戻してそれ以上は上げません。
+#[derive(Debug)]
+pub struct AutoTuner {
+    max_parallel: usize,
+    state: Mutex<State>,
+}
+
+impl AutoTuner {
+    /// `max_parallel` は動画の同時ダウンロード本数の上限
+    pub fn new(max_parallel: usize) -> Self {
+        Self {
+            max_parallel: max_parallel.max(1),
+            state: Mutex::new(State {
+                fragments: 1,
+                parallel: 1,
+                best_speed: 0.0,
+                last_step: None,
+                settled: false,
+            }),
+        }
+    }
+
+    /// 動画の同時ダウンロード本数の上限
+    pub fn max_parallel(&self) -> usize {
+        self.max_parallel
+    }
+
+    /// 現在の断片の同時ダウンロード数
+    pub fn fragments(&self) -> u32 {
+        self.state.lock().map(|state| state.fragments).unwrap_or(1)
+    }
+
+    /// 現在の動画の同時ダウンロード本数
+    pub fn parallel(&self) -> usize {
+        self.state.lock().map(|state| state.parallel).unwrap_or(1)
+    }
+
+    /// 結果を記録して設定を調整（変更した場合はその説明を返す）
+    pub fn record(&self, outcome: Outcome) -> Option<String> {
+        let mut state = self.state.lock().ok()?;
+        let before = (state.fragments, state.parallel);
+        let reason = match outcome {
+            Outcome::RateLimited => {
+                state.fragments = (state.fragments / 2).max(1);
+                state.parallel = (state.parallel / 2).max(1);
+                state.best_speed = 0.0;
+                state.last_step = None;
+                state.settled = true;
+                t!("429（リクエストが多すぎる）を受けたため下げます")
+            }
+            Outcome::Failed => return None,
+            Outcome::Finished { bytes, elapsed } => {
+                if bytes == 0 || elapsed.is_zero() {
+                    return None;
+                }
+                let speed = bytes as f64 / elapsed.as_secs_f64();
+                if speed < state.best_speed * SLOWDOWN_RATIO {
+                    match state.last_step.take() {
+                        Some(Step::Fragments) => state.fragments = (state.fragments / 2).max(1),
+                        Some(Step::Parallel) => state.parallel = state.parallel.saturating_sub(1).max(1),
+                        None => {}
+                    }
+                    state.settled = true;
+                    t!("速度が落ちたため戻します")
+                } else {
+                    state.best_speed = state.best_speed.max(speed);
+                    if state.settled {
+                        return None;
+                    }
+                    if state.fragments < MAX_FRAGMENTS {
+                        state.fragments = (state.fragments * 2).min(MAX_FRAGMENTS);
+                        state.last_step = Some(Step::Fragments);
+                    } else if state.parallel < self.max_parallel {
+                        state.parallel += 1;
+                        state.last_step = Some(Step::Parallel);
+                    }
+                    t!("速度が落ちていないため上げます")
+                }
+            }
+        };
+
+        (before != (state.fragments, state.parallel)).then(|| {
+            t!(
+                "{}（断片: {} → {}、同時ダウンロード: {} → {}本）",
+                reason, before.0, state.fragments, before.1, state.parallel
+            )
+        })
+    }
+}
+
+/// yt-dlpの出力にHTTP 429が含まれるか
+pub fn is_rate_limited(lines: &[String]) -> bool {
+    lines
+        .iter()
+        .any(|line| line.contains("HTTP Error 429") || line.contains("Too Many Requests"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(bytes: u64) -> Outcome {
+        Outcome::Finished {
+            bytes,
+            elapsed: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_ramps_up_fragments_then_parallel() {
+        let tuner = AutoTuner::new(2);
+        for _ in 0..4 {
+            assert!(tuner.record(finished(1000)).is_some());
+        }
+        assert_eq!((tuner.fragments(), tuner.parallel()), (16, 1));
+
+        tuner.record(finished(1000));
+        assert_eq!((tuner.fragments(), tuner.parallel()), (16, 2));
+
+        // 上限に達したら変更しない
+        assert_eq!(tuner.record(finished(1000)), None);
+    }
+
+    #[test]
+    fn test_backs_off() {
+        let tuner = AutoTuner::new(4);
+        tuner.record(finished(1000));
+        tuner.record(finished(1000));
+        assert_eq!(tuner.fragments(), 4);
+
+        // 遅くなったら最後に上げた設定を戻し、それ以上は上げない
+        assert!(tuner.record(finished(100)).is_some());
+        assert_eq!(tuner.fragments(), 2);
+        assert_eq!(tuner.record(finished(1000)), None);
+
+        assert!(tuner.record(Outcome::RateLimited).is_some());
+        assert_eq!((tuner.fragments(), tuner.parallel()), (1, 1));
+        assert_eq!(tuner.record(Outcome::Failed), None);
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        let lines = vec!["ERROR: [youtube] abc: HTTP Error 429: Too Many Requests".to_string()];
+        assert!(is_rate_limited(&lines));
+        assert!(!is_rate_limited(&["ERROR: Video unavailable".to_string()]));
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::notification;
+use crate::shutdown;
+use crate::t;
+use crate::watch_dir::sleep_unless_shutdown;
+use crate::ytdlp_wrapper::YtdlpWrapper;
+
+/// ダウンロードできるようになるまで動画を確認し、できるようになったらすぐにダウンロード（`--watch-until-available`）
+///
+/// プレミア公開前・処理中・地域制限の切り替えなどでまだダウンロードできない動画を `interval` ごとに確認します。
+/// ダウンロードが終わったらデスクトップに通知します。
+/// Ctrl+Cまたは終了要求（SIGTERM）を受け取ったら確認をやめて終了します。
+pub fn watch(wrapper: &YtdlpWrapper, url: &str, interval: Duration) -> Result<()> {
+    println!(
+        "{}",
+        t!("⏳ ダウンロードできるようになるまで{}ごとに確認します（Ctrl+Cで終了）", format_interval(interval))
+    );
+    while let Some(reason) = wrapper.check_available(url)? {
+        println!("{}", t!("⏳ まだダウンロードできません: {}", reason));
+        sleep_unless_shutdown(interval);
+        if shutdown::is_requested() {
+            println!("{}", t!("👋 確認を終了しました"));
+            return Ok(());
+        }
+    }
+
+    println!("{}", t!("🎉 ダウンロードできるようになりました"));
+    let result = wrapper.download_urls(&[url.to_string()]);
+    match &result {
+        Ok(()) => notification::show(t!("ダウンロードが完了しました"), url),
+        Err(e) => notification::show(&t!("ダウンロードに失敗しました: {}", e), url),
+    }
+    result
+}
+
+/// 確認の間隔を "10分" のように表示
+fn format_interval(interval: Duration) -> String {
+    let seconds = interval.as_secs();
+    if seconds >= 3600 && seconds.is_multiple_of(3600) {
+        t!("{}時間", seconds / 3600)
+    } else if seconds >= 60 && seconds.is_multiple_of(60) {
+        t!("{}分", seconds / 60)
+    } else {
+        t!("{}秒", seconds)
+    }
+}
+
@@ -0,0 +1,196 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::error::{Result, YtdlError};
+use crate::pause;
+use crate::t;
+
+/// ダウンロードの中止要求
+///
+/// 中止すると新しいダウンロードを開始せず、実行中のyt-dlp（動画情報の取得を含む）も終了させます。
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+    /// 実行中のyt-dlpのプロセスID（並列ダウンロードでは複数）
+    children: Mutex<Vec<u32>>,
+}
+
+impl CancelToken {
+    /// 中止する
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Ok(children) = self.children.lock() {
+            for &pid in children.iter() {
+                pause::terminate_child(pid);
+            }
+        }
+    }
+
+    /// 中止されたか
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 実行中のyt-dlpを登録（登録前に中止されていた場合はすぐに終了させる）
+    pub(crate) fn add_child(&self, pid: u32) {
+        if let Ok(mut children) = self.children.lock() {
+            children.push(pid);
+        }
+        if self.is_cancelled() {
+            pause::terminate_child(pid);
+        }
+    }
+
+    /// 終了したyt-dlpの登録を解除
+    pub(crate) fn remove_child(&self, pid: u32) {
+        if let Ok(mut children) = self.children.lock() {
+            children.retain(|&child| child != pid);
+        }
+    }
+
+    /// 動画情報の取得などのyt-dlpを実行し、標準出力を1行ずつ `on_line` に渡す
+    ///
+    /// ダウンロードと同じく一時停止・中止の対象として登録します。
+    /// 中止された場合はyt-dlpを終了させ、出力を最後まで読んでから [`YtdlError::Cancelled`] を返します。
+    pub(crate) fn run_probe(&self, cmd: &mut Command, mut on_line: impl FnMut(&str)) -> Result<ProbeOutput> {
+        pause::prepare_child(cmd);
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| YtdlError::ProcessError(t!("プロセス起動失敗: {}", e)))?;
+        pause::register_child(child.id());
+        self.add_child(child.id());
+
+        // stderrは別スレッドで読み取る（パイプが詰まってyt-dlpが停止するのを防ぐ）
+        let stderr_reader = child.stderr.take().map(|mut stderr| {
+            std::thread::spawn(move || {
+                let mut content = Vec::new();
+                let _ = stderr.read_to_end(&mut content);
+                String::from_utf8_lossy(&content).into_owned()
+            })
+        });
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(std::result::Result::ok) {
+                if !self.is_cancelled() {
+                    on_line(&line);
+                }
+            }
+        }
+
+        let status = child.wait();
+        pause::unregister_child(child.id());
+        self.remove_child(child.id());
+        let stderr = stderr_reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        if self.is_cancelled() {
+            return Err(YtdlError::Cancelled(0));
+        }
+        let status = status.map_err(|e| YtdlError::ProcessError(e.to_string()))?;
+        Ok(ProbeOutput { status, stderr })
+    }
+}
+
+/// [`CancelToken::run_probe`] で実行したyt-dlpの結果（標準出力は実行中に渡し済み）
+pub(crate) struct ProbeOutput {
+    pub status: ExitStatus,
+    pub stderr: String,
+}
+
+/// バックグラウンドで実行中のダウンロード
+///
+/// [`YtdlpWrapper::spawn`](crate::YtdlpWrapper::spawn) で作成します。
+pub struct DownloadHandle {
+    thread: JoinHandle<Result<()>>,
+    token: Arc<CancelToken>,
+}
+
+impl DownloadHandle {
+    pub(crate) fn new(thread: JoinHandle<Result<()>>, token: Arc<CancelToken>) -> Self {
+        Self { thread, token }
+    }
+
+    /// ダウンロードを中止（実行中のyt-dlpも終了させる）。完了は [`join`](Self::join) で待つ
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// ダウンロードが終了したか
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// ダウンロードの終了を待つ
+    pub fn join(self) -> Result<()> {
+        self.thread
+            .join()
+            .unwrap_or_else(|_| Err(YtdlError::Other(t!("ダウンロード処理が異常終了しました").to_string())))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// yt-dlpと同じく独自のプロセスグループで起動
+    fn spawn_sleep() -> std::process::Child {
+        let mut cmd = std::process::Command::new("sleep");
+        cmd.arg("30");
+        pause::prepare_child(&mut cmd);
+        cmd.spawn().unwrap()
+    }
+
+    #[test]
+    fn test_cancel_terminates_child() {
+        let token = CancelToken::default();
+        let mut child = spawn_sleep();
+        let mut other = spawn_sleep();
+        token.add_child(child.id());
+        token.add_child(other.id());
+        assert!(!token.is_cancelled());
+
+        token.cancel();
+        assert!(token.is_cancelled());
+        assert!(!child.wait().unwrap().success());
+        assert!(!other.wait().unwrap().success());
+
+        // 中止後に登録されたプロセスもすぐに終了させる
+        let mut child = spawn_sleep();
+        token.add_child(child.id());
+        assert!(!child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_cancel_during_probe() {
+        let token = CancelToken::default();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo '{\"id\": \"a\"}'; sleep 30; echo '{\"id\": \"b\"}'"]);
+
+        let started = std::time::Instant::now();
+        let mut lines = Vec::new();
+        let result = token.run_probe(&mut cmd, |line| {
+            lines.push(line.to_string());
+            token.cancel();
+        });
+        assert!(matches!(result, Err(YtdlError::Cancelled(_))));
+        assert_eq!(lines.len(), 1);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_run_probe_output() {
+        let token = CancelToken::default();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo one; echo two; echo oops >&2; exit 3"]);
+
+        let mut lines = Vec::new();
+        let output = token.run_probe(&mut cmd, |line| lines.push(line.to_string())).unwrap();
+        assert_eq!(lines, ["one", "two"]);
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stderr.trim(), "oops");
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, YtdlError};
+use crate::paths;
+use crate::t;
+
+/// 続きの位置を記録するファイル名（データディレクトリに作成）
+const FILE_NAME: &str = "playlist-checkpoints.json";
+
+/// プレイリストの範囲を分割（1から数える位置、両端を含む）
+pub fn ranges(first: usize, last: usize, size: usize) -> Vec<(usize, usize)> {
+    if size == 0 || first == 0 || first > last {
+        return Vec::new();
+    }
+    (first..=last)
+        .step_by(size)
+        .map(|start| (start, (start + size - 1).min(last)))
+        .collect()
+}
+
+/// 分割してダウンロードしているプレイリストの続きの位置（`--playlist-chunk`）
+///
+/// データディレクトリの "playlist-checkpoints.json" にURLごとの次の位置を保存し、
+/// 中断した場合は次回の実行で、最後まで完了した区切りの次から再開します。
+/// プレイリストの最後まで完了したURLは削除します。
+#[derive(Debug)]
+pub struct Checkpoints {
+    path: PathBuf,
+    entries: BTreeMap<String, usize>,
+}
+
+impl Checkpoints {
+    /// データディレクトリの記録を読み込む
+    pub fn open() -> Result<Self> {
+        Self::load(&paths::data_dir()?.join(FILE_NAME))
+    }
+
+    /// 記録を読み込む（ファイルがなければ空）
+    pub fn load(path: &Path) -> Result<Self> {
+        let entries = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| {
+                YtdlError::Other(t!("続きの位置を読み込めません: {}: {}", path.display(), e))
+            })?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// 次にダウンロードする位置
+    pub fn get(&self, url: &str) -> Option<usize> {
+        self.entries.get(url).copied()
+    }
+
+    /// 次にダウンロードする位置を記録して保存
+    pub fn set(&mut self, url: &str, next: usize) -> Result<()> {
+        self.entries.insert(url.to_string(), next);
+        self.save()
+    }
+
+    /// 最後まで完了したURLの記録を削除して保存
+    pub fn remove(&mut self, url: &str) -> Result<()> {
+        if self.entries.remove(url).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 保存（書きかけのファイルを残さないよう、一時ファイルに書いてから置き換える）
+    fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| YtdlError::Other(t!("続きの位置を保存できません: {}", e)))?;
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(ranges(1, 450, 200), vec![(1, 200), (201, 400), (401, 450)]);
+        assert_eq!(ranges(201, 400, 200), vec![(201, 400)]);
+        assert_eq!(ranges(5, 4, 200), vec![]);
+        assert_eq!(ranges(1, 10, 0), vec![]);
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let path = std::env::temp_dir().join(format!("ytdl-checkpoints-{}.json", std::process::id()));
+        let url = "https://www.youtube.com/playlist?list=PL123";
+        let mut checkpoints = Checkpoints::load(&path).unwrap();
+        checkpoints.set(url, 201).unwrap();
+        assert_eq!(Checkpoints::load(&path).unwrap().get(url), Some(201));
+
+        checkpoints.remove(url).unwrap();
+        assert_eq!(Checkpoints::load(&path).unwrap().get(url), None);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,37 +1,438 @@
-use clap::Parser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::quality::QualityPreset;
+use crate::collision::CollisionPolicy;
+use crate::config::Config;
+use crate::encode::EncodePreset;
+use crate::filename::FilenamePolicy;
+use crate::i18n::{self, Lang};
+use crate::metadata::parse_metadata_lang;
+use crate::progress_parser::{format_bytes, parse_byte_size};
+use crate::progress_style::{ProgressFormat, ProgressTheme};
+use crate::t;
+use crate::youtube_url::PlaylistChoice;
+use crate::quality::{AudioFormat, AudioQuality, Container, QualityProfile, Resolution, VideoCodec};
+use crate::rclone::s3_remote;
+use crate::routing::RouteRule;
+use crate::site::SiteConfig;
 
 /// YouTube動画一括ダウンローダー
 ///
 /// 自分のYouTube動画やプレイリストを一括でダウンロードするCLIツール。
 /// Chrome/Firefox/Edgeのブラウザクッキーを自動検出してプライベート動画にも対応。
+///
+/// 主なオプションは YTDL_* 環境変数でも指定できます（コマンドライン引数が優先）。
+/// サブコマンドを省略した場合は download と同じです（`ytdl <URL>`）。
 #[derive(Parser, Debug)]
 #[command(name = "ytdl")]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct App {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub download: Cli,
+
+    /// 表示する言語（省略時は設定ファイルの lang、なければ環境変数 LANG から決める）
+    ///
+    /// 引数のIDはサブコマンド（ytdl lang）と区別するため display_lang にする。
+    #[arg(id = "display_lang", long = "lang", global = true, value_enum, env = "YTDL_LANG")]
+    pub lang: Option<Lang>,
+}
+
+impl App {
+    /// コマンドラインを解析（明示的に指定されたダウンロードのオプションも返す）
+    pub fn parse_specified() -> (Self, SpecifiedArgs) {
+        let matches = Self::command().get_matches();
+        let app = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        let download = matches
+            .subcommand_matches("download")
+            .or_else(|| matches.subcommand_matches("queue")?.subcommand_matches("run"))
+            .or_else(|| matches.subcommand_matches("watch-dir"))
+            .unwrap_or(&matches);
+        (app, SpecifiedArgs::from_matches(download))
+    }
+
+    /// ダウンロードのオプション（他のサブコマンドの場合はそのサブコマンド）
+    pub fn into_download(self) -> std::result::Result<Cli, Command> {
+        match self.command {
+            None => Ok(self.download),
+            Some(Command::Download(cli)) => Ok(*cli),
+            Some(Command::Queue {
+                action: QueueAction::Run(cli),
+            }) => Ok(Cli {
+                run_queue: true,
+                ..*cli
+            }),
+            Some(Command::WatchDir(args)) => Ok(Cli {
+                watch_dir: Some(args.dir),
+                ..*args.download
+            }),
+            Some(command) => Err(command),
+        }
+    }
+}
+
+/// コマンドライン引数・環境変数で指定された（デフォルト値ではない）オプション
+///
+/// 設定ファイルの値より優先するかを判断するために使います。名前は [`Cli`] のフィールド名です。
+#[derive(Debug, Default, Clone)]
+pub struct SpecifiedArgs(HashSet<String>);
+
+impl SpecifiedArgs {
+    fn from_matches(matches: &ArgMatches) -> Self {
+        Self(
+            matches
+                .ids()
+                .filter(|id| {
+                    matches!(
+                        matches.value_source(id.as_str()),
+                        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+                    )
+                })
+                .map(|id| id.to_string())
+                .collect(),
+        )
+    }
+
+    /// 指定されているか
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.contains(id)
+    }
+
+    /// 指定済みとして記録（まだ指定されていなかった場合はtrue）
+    pub fn insert(&mut self, id: &str) -> bool {
+        self.0.insert(id.to_string())
+    }
+}
+
+/// サブコマンド
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 動画をダウンロード（サブコマンドを省略した場合と同じ）
+    Download(Box<Cli>),
+    /// 動画の情報（タイトル・チャンネル・再生時間など）を表示
+    Info(InfoArgs),
+    /// 利用できるフォーマットの一覧を表示（-f に指定するID・解像度・コーデック・サイズ）
+    Formats(InfoArgs),
+    /// yt-dlpを最新版に更新
+    Update,
+    /// ダウンロード済みアーカイブ（downloaded.txt）の確認・編集
+    Archive(ArchiveArgs),
+    /// 動画ごとのyt-dlpの出力を表示（IDを省略すると保存されているログの一覧）
+    Logs {
+        /// 動画のID
+        #[arg(value_name = "ID")]
+        id: Option<String>,
+    },
+    /// ダウンロードキュー（再起動しても残る）の追加・確認・並べ替え・実行
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+    /// ダウンロード履歴（いつ・何を・どこに保存したか）の確認
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// 表示する言語の確認
+    Lang {
+        #[command(subcommand)]
+        action: LangAction,
+    },
+    /// フォルダを監視し、置かれたURLファイル（.txt / .url / .webloc）を自動でダウンロード
+    ///
+    /// 処理したファイルは done/（失敗した場合は failed/）へ移動します。
+    /// スマートフォンの同期フォルダなど、ファイルを書き込めるものと連携できます。
+    WatchDir(WatchDirArgs),
+    /// フォルダ内の内容が同一のファイルを探し、ハードリンク化または削除
+    ///
+    /// `--record-hashes` で記録したフォルダ外のファイルとも比較します。
+    Dedupe {
+        /// 整理するフォルダ（変更するのはこのフォルダ内のファイルのみ）
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+        /// 処理方法を選択せず、一覧の表示のみ行う
+        #[arg(long = "non-interactive", env = "YTDL_NON_INTERACTIVE")]
+        non_interactive: bool,
+    },
+    /// 実行中のダウンロードを一時停止（yt-dlpとffmpeg・aria2cを中断）
+    Pause {
+        /// 一時停止する ytdl のプロセスID
+        #[arg(value_name = "PID")]
+        pid: u32,
+    },
+    /// 一時停止したダウンロードを再開
+    Resume {
+        /// 再開する ytdl のプロセスID
+        #[arg(value_name = "PID")]
+        pid: u32,
+    },
+    /// ダウンロードに使うCookieの取得
+    Cookies {
+        #[command(subcommand)]
+        action: CookiesAction,
+    },
+}
+
+/// `ytdl watch-dir` の引数（URLは監視フォルダから受け取るため表示しない）
+#[derive(Args, Debug)]
+#[command(mut_arg("url", |arg| arg.hide(true)))]
+pub struct WatchDirArgs {
+    /// 監視するフォルダ
+    #[arg(value_name = "DIR", env = "YTDL_WATCH_DIR")]
+    pub dir: PathBuf,
+
+    #[command(flatten)]
+    pub download: Box<Cli>,
+}
+
+/// Cookieの操作
+#[derive(Subcommand, Debug)]
+pub enum CookiesAction {
+    /// リモートマシンからcookies.txtをSCPで取得して保存
+    ///
+    /// "user@host"（リモートのホームの cookies.txt）または
+    /// "user@host:path/to/cookies.txt" を指定します。
+    /// 保存したファイルは以降の実行で自動的に使用されます。
+    Pull {
+        #[arg(value_name = "USER@HOST[:PATH]")]
+        remote: String,
+    },
+}
+
+/// 表示する言語の操作
+#[derive(Subcommand, Debug)]
+pub enum LangAction {
+    /// 利用できる言語の一覧を表示（* は現在の言語）
+    List,
+}
+
+/// ダウンロード履歴の操作
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    /// 新しい順に一覧表示
+    List {
+        /// 表示する件数
+        #[arg(short = 'n', long = "limit", default_value = "20")]
+        limit: usize,
+    },
+    /// タイトル・動画ID・URL・保存先で検索
+    Search {
+        #[arg(value_name = "QUERY")]
+        query: String,
+    },
+    /// 履歴をすべて削除（ダウンロードしたファイルは削除しない）
+    Clear,
+}
+
+/// キューの操作（位置は `ytdl queue list` の番号）
+#[derive(Subcommand, Debug)]
+pub enum QueueAction {
+    /// キューの末尾にURLを追加
+    Add {
+        /// 動画またはプレイリストのURL
+        #[arg(value_name = "URL", required = true)]
+        urls: Vec<String>,
+    },
+    /// キューの一覧を表示
+    List,
+    /// 動画を別の位置へ移動（例: `ytdl queue move 5 1` で5番目を先頭に）
+    Move {
+        #[arg(value_name = "FROM")]
+        from: usize,
+        #[arg(value_name = "TO")]
+        to: usize,
+    },
+    /// キューから削除
+    Remove {
+        #[arg(value_name = "POSITION", required = true)]
+        positions: Vec<usize>,
+    },
+    /// キューを空にする
+    Clear,
+    /// キューのURLを先頭から順にダウンロード（オプションは download と同じ）
+    ///
+    /// 完了したURLはキューから削除し、失敗したURLは残します。
+    Run(Box<Cli>),
+}
+
+/// info / formats のオプション
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// 動画のURL（プレイリストの場合は含まれるすべての動画）
+    #[arg(value_name = "URL", required = true)]
+    pub url: Vec<String>,
+
+    /// 使用するブラウザのCookie（限定公開・メンバー限定の動画など）
+    #[arg(short = 'c', long = "cookies", value_name = "BROWSER")]
+    pub cookie_browser: Option<String>,
+
+    /// cookies.txt（Netscape形式）を使用
+    #[arg(long = "cookies-file", value_name = "PATH", conflicts_with = "cookie_browser")]
+    pub cookies_file: Option<PathBuf>,
+
+    /// タイトル・説明文の言語（例: ja、en。翻訳がある場合のみ）
+    #[arg(long = "metadata-lang", value_name = "LANG", value_parser = parse_metadata_lang)]
+    pub metadata_lang: Option<String>,
+
+    /// yt-dlpの動画情報をそのままJSON（1動画につき1行）で出力
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+/// archive のオプション
+#[derive(Args, Debug)]
+pub struct ArchiveArgs {
+    /// アーカイブファイル（デフォルト: 保存先の "downloaded.txt"）
+    #[arg(long = "file", value_name = "PATH", global = true, env = "YTDL_DOWNLOAD_ARCHIVE")]
+    pub file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub action: ArchiveAction,
+}
+
+/// アーカイブの操作
+#[derive(Subcommand, Debug)]
+pub enum ArchiveAction {
+    /// 記録されている動画を一覧表示
+    List,
+    /// 動画の記録を削除（次回のダウンロードで再取得される）
+    Remove {
+        /// 削除する動画のID
+        #[arg(value_name = "ID", required = true)]
+        ids: Vec<String>,
+    },
+}
+
+/// ダウンロードのオプション
+#[derive(Args, Debug, Default, Clone)]
 pub struct Cli {
     /// ダウンロード対象のURL（動画URLまたはプレイリストURL）
     ///
+    /// URLの代わりに .url / .webloc / テキストファイル（1行に1つのURL）を
+    /// 指定することもできます（exeへのドラッグ&ドロップに対応）。
     /// URLを指定しない場合はインタラクティブモードで起動します。
     #[arg(value_name = "URL")]
-    pub url: Option<String>,
+    pub url: Vec<String>,
+
+
+    /// まだダウンロードできない動画（プレミア公開前・処理中・地域制限など）を確認し続け、できるようになったらダウンロード
+    ///
+    /// --poll の間隔で品質の指定に合うフォーマットがあるか確認し、ダウンロードが終わったらデスクトップに通知します。
+    #[arg(
+        long = "watch-until-available",
+        value_name = "URL",
+        conflicts_with_all = ["url", "dry_run"],
+        env = "YTDL_WATCH_UNTIL_AVAILABLE"
+    )]
+    pub watch_until_available: Option<String>,
+
+    /// --watch-until-available で確認する間隔（例: 30s、10m、1h。30秒以上）
+    #[arg(
+        long = "poll",
+        value_name = "INTERVAL",
+        default_value = "10m",
+        value_parser = parse_interval,
+        env = "YTDL_POLL"
+    )]
+    pub poll: Duration,
+
+    /// 終了要求（SIGTERM）後、実行中のダウンロードの完了を待つ秒数
+    ///
+    /// 時間内に終わらない場合はダウンロードを中断して終了します。
+    /// Dockerのデフォルトの停止猶予（10秒）に収まるように指定してください。
+    #[arg(long = "shutdown-grace", value_name = "SECS", default_value = "8", env = "YTDL_SHUTDOWN_GRACE")]
+    pub shutdown_grace: u64,
+
+    /// 指定したプロセスが終了したら、実行中のyt-dlpも終了させて終了する
+    ///
+    /// GUIやスクリプトから起動する場合に、起動元のPIDを指定すると
+    /// 起動元が異常終了してもダウンロードがバックグラウンドに残りません。
+    #[arg(long = "parent-pid", value_name = "PID", env = "YTDL_PARENT_PID")]
+    pub parent_pid: Option<u32>,
 
     /// インタラクティブモードをスキップ（CI/CDなど自動実行時用）
-    #[arg(long = "non-interactive")]
+    #[arg(long = "non-interactive", env = "YTDL_NON_INTERACTIVE")]
     pub non_interactive: bool,
 
+    /// 設定ファイルの [profiles.<名前>] で定義したオプションの組み合わせを使用
+    #[arg(long = "profile", value_name = "NAME", env = "YTDL_PROFILE")]
+    pub profile: Option<String>,
+
     /// ダウンロード品質プリセット
     ///
     /// - max-video: 最高画質（4K対応）
+    /// - balanced: 1080p以下の最高画質（4Kより容量を抑える）
     /// - max-audio: 最高音質（音声のみ、MP3変換）
     /// - min-video: 最低画質（プレビュー用）
     /// - min-size: 最小容量
-    #[arg(short = 'q', long = "quality", default_value = "max-video")]
-    pub quality: QualityPreset,
+    ///
+    /// 設定ファイル（config.toml）の [presets.<名前>] で定義したプリセットも指定できます。
+    #[arg(short = 'q', long = "quality", default_value = "max-video", env = "YTDL_QUALITY")]
+    pub quality: String,
+
+    /// 音声抽出時の音質（max-audio など音声のみのプリセットで有効）
+    ///
+    /// - best: 最高品質（デフォルト）
+    /// - 320k / 192k / 128k / 96k: ビットレートを指定（ポッドキャストなどは96kで十分）
+    /// - v2 / v5: VBRのレベル（数字が大きいほど小さいファイル、v5はトーク向け）
+    #[arg(long = "audio-quality", value_enum, default_value = "best", env = "YTDL_AUDIO_QUALITY")]
+    pub audio_quality: AudioQuality,
+
+    /// 音声抽出時の形式（mp3 / m4a / opus / flac / wav / best、未指定ならプリセットの形式）
+    ///
+    /// best は元の形式のまま保存し、再エンコードによる劣化を避けます。
+    #[arg(long = "audio-format", value_enum, env = "YTDL_AUDIO_FORMAT")]
+    pub audio_format: Option<AudioFormat>,
+
+    /// 解像度の上限（例: 1080 で1080p以下）
+    ///
+    /// max-video では4Kが選ばれることが多いため、容量を抑えたい場合に指定します。
+    /// 選択したプリセットのフォーマットに条件を付けるため、プリセットの画質・コーデックの指定は維持されます。
+    /// 音声のみのプリセットでは無視されます。
+    #[arg(long = "resolution", value_enum, value_name = "HEIGHT", env = "YTDL_RESOLUTION")]
+    pub resolution: Option<Resolution>,
+
+    /// 映像のコーデック（av1 / vp9 / h264、該当がなければ指定なしで選択）
+    ///
+    /// 再生できない機器がある場合は h264、容量を抑える場合は av1 を指定します。
+    #[arg(long = "vcodec", value_enum, env = "YTDL_VCODEC")]
+    pub vcodec: Option<VideoCodec>,
+
+    /// 映像付きダウンロードのコンテナ形式（mp4 / mkv / webm、未指定ならyt-dlpが選択）
+    ///
+    /// 結合したファイルも単体のファイルも指定の形式で保存します。
+    /// webm はVP9・AV1の映像のみ格納できるため、--vcodec vp9 などと組み合わせます。
+    #[arg(long = "container", value_enum, env = "YTDL_CONTAINER")]
+    pub container: Option<Container>,
+
+    /// yt-dlpのフォーマット指定をそのまま使う（品質プリセットのフォーマットの代わり）
+    ///
+    /// 例: "bv*[height<=1080][fps<=30]+ba"
+    /// --resolution / --vcodec・サイトごとの設定の format より優先します。
+    /// 音声の抽出・後処理はプリセット（--quality）のものを使います。
+    #[arg(long = "format-string", value_name = "FORMAT", env = "YTDL_FORMAT_STRING")]
+    pub format_string: Option<String>,
+
+    /// ファイルサイズの上限（例: 100M、1.5G、25MB）
+    ///
+    /// 動画ごとに情報を取得し、上限に収まる最も高画質なフォーマットの
+    /// 組み合わせを選んでダウンロードします。収まらない動画はスキップします。
+    #[arg(
+        long = "target-size",
+        value_name = "SIZE",
+        value_parser = parse_byte_size,
+        env = "YTDL_TARGET_SIZE"
+    )]
+    pub target_size: Option<u64>,
 
     /// 出力先ディレクトリ（デフォルト: exeと同じフォルダ）
-    #[arg(short = 'o', long = "output")]
+    #[arg(short = 'o', long = "output", env = "YTDL_OUTPUT_DIR")]
     pub output_dir: Option<PathBuf>,
 
     /// 使用するブラウザのCookie（YouTube認証用）
@@ -39,18 +440,70 @@ pub struct Cli {
     /// YouTubeのBot対策により、ブラウザのCookieがほぼ必須です。
     /// 指定されたブラウザのCookieを自動検出します。
     /// デフォルト: chrome
+    /// キーリングを指定する場合は "chrome+basictext" のように指定します。
     /// 無効化する場合は --no-cookies を使用してください。
-    #[arg(short = 'c', long = "cookies", default_value = "chrome")]
+    #[arg(short = 'c', long = "cookies", default_value = "chrome", env = "YTDL_COOKIES")]
     pub cookie_browser: Option<String>,
 
     /// Cookieを使用しない（Bot判定される可能性が高い）
-    #[arg(long = "no-cookies", conflicts_with = "cookies")]
+    #[arg(long = "no-cookies", conflicts_with = "cookie_browser", env = "YTDL_NO_COOKIES")]
     pub no_cookies: bool,
 
-    /// プレイリスト全体をダウンロード
-    #[arg(short = 'p', long = "playlist")]
+    /// cookies.txt（Netscape形式）を使用（ブラウザのCookieより優先）
+    #[arg(long = "cookies-file", value_name = "PATH", env = "YTDL_COOKIES_FILE")]
+    pub cookies_file: Option<PathBuf>,
+
+    /// ダウンロードしたファイルのハッシュを記録（`ytdl dedupe` でフォルダをまたいで重複を検出）
+    #[arg(long = "record-hashes", env = "YTDL_RECORD_HASHES")]
+    pub record_hashes: bool,
+
+    /// 起動時にCookieデータベースを一時フォルダへコピーして使用
+    ///
+    /// 長時間のプレイリストダウンロード中にブラウザのファイルロックと
+    /// 競合するのを防ぎます。Windowsではロック中でもシャドウコピーを試みます。
+    #[arg(long = "cookie-snapshot", env = "YTDL_COOKIE_SNAPSHOT")]
+    pub cookie_snapshot: bool,
+
+    /// ダウンロード完了後、rcloneでクラウドストレージへ転送（例: gdrive:YouTube）
+    ///
+    /// 振り分けルールによるフォルダ構成は転送先でも維持されます。
+    #[arg(long = "rclone-dest", value_name = "REMOTE:PATH", env = "YTDL_RCLONE_DEST")]
+    pub rclone_dest: Option<String>,
+
+    /// ダウンロード完了後、rcloneでS3（互換）ストレージへ転送（例: s3://bucket/youtube）
+    ///
+    /// rcloneのS3バックエンドの簡易指定で、rcloneのリモートを設定せずに使えます（rcloneのインストールは必要）。
+    /// 認証情報は AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY などの環境変数、
+    /// または ~/.aws の設定を使用します。
+    #[arg(
+        long = "s3-dest",
+        value_name = "S3_URL",
+        conflicts_with = "rclone_dest",
+        env = "YTDL_S3_DEST"
+    )]
+    pub s3_dest: Option<String>,
+
+    /// S3互換ストレージのエンドポイント（MinIO、Cloudflare R2など）
+    #[arg(
+        long = "s3-endpoint",
+        value_name = "URL",
+        requires = "s3_dest",
+        env = "YTDL_S3_ENDPOINT"
+    )]
+    pub s3_endpoint: Option<String>,
+
+    /// クラウドへ転送後、ローカルのファイルを削除（--rclone-dest / --s3-dest と併用）
+    #[arg(long = "rclone-move", env = "YTDL_RCLONE_MOVE")]
+    pub rclone_move: bool,
+
+    /// プレイリスト全体をダウンロード（プレイリスト内の動画のURLでもプレイリスト全体）
+    #[arg(short = 'p', long = "playlist", visible_alias = "whole-list")]
     pub playlist: bool,
 
+    /// プレイリスト内の動画のURL（watch?v=...&list=...）でも、その動画のみダウンロード
+    #[arg(long = "this-video-only", conflicts_with = "playlist")]
+    pub this_video_only: bool,
+
     /// プレイリストの開始位置（1から始まる）
     #[arg(long = "from")]
     pub playlist_start: Option<usize>,
@@ -59,42 +512,369 @@ pub struct Cli {
     #[arg(long = "to")]
     pub playlist_end: Option<usize>,
 
+    /// 大きなプレイリストを分割してダウンロードする本数（0で分割しない）
+    ///
+    /// これより多い動画があるプレイリストは、この本数ずつ順にダウンロードし、
+    /// 完了した区切りを記録します。中断した場合は次回の実行で続きから再開します。
+    #[arg(long = "playlist-chunk", value_name = "N", default_value = "200", env = "YTDL_PLAYLIST_CHUNK")]
+    pub playlist_chunk: usize,
+
+    /// プレイリストの動画を同時にダウンロードする本数（--playlist と併用）
+    ///
+    /// 動画ごとにyt-dlpを起動し、最大N本を同時にダウンロードします。
+    /// ダウンロード中の動画ごとの進捗バーと、全体の進捗バーを表示します。
+    #[arg(long = "parallel", value_name = "N", default_value = "1", env = "YTDL_PARALLEL")]
+    pub parallel: usize,
+
     /// 字幕も保存
     #[arg(short = 's', long = "subtitle")]
     pub download_subtitle: bool,
 
+    /// 字幕を動画ファイルに埋め込む（MP4・WebMに埋め込めない形式の字幕はMKVで保存）
+    #[arg(long = "embed-subs", env = "YTDL_EMBED_SUBS")]
+    pub embed_subs: bool,
+
+    /// ストーリーボード（シーク時のプレビュー画像）も保存（"タイトル.storyboard.mhtml"）
+    ///
+    /// メディアサーバーのプラグインなどでシーク時のプレビューに使えます。
+    #[arg(long = "write-storyboards", env = "YTDL_WRITE_STORYBOARDS")]
+    pub write_storyboards: bool,
+
+    /// 「最も再生された部分」のデータをJSONで保存（"タイトル.heatmap.json"）
+    #[arg(long = "write-heatmap", env = "YTDL_WRITE_HEATMAP")]
+    pub write_heatmap: bool,
+
     /// 説明文・メタデータも保存
     #[arg(short = 'm', long = "metadata")]
     pub save_metadata: bool,
 
+    /// 音声抽出・結合の後も元の動画ファイルを残す
+    ///
+    /// 音声のみのプリセットでは、元の動画を残すために映像付きでダウンロードします。
+    #[arg(long = "keep-video", env = "YTDL_KEEP_VIDEO")]
+    pub keep_video: bool,
+
+    /// チャプターごとに曲を分割し、トラック番号付きのアルバムフォルダを作成（音声のみ）
+    #[arg(long = "split-album", conflicts_with = "output_template")]
+    pub split_album: bool,
+
+    /// 音声の先頭・末尾の無音を削除（配信の録画などの開始前・終了後の無音。音声のみ、ffmpegが必要）
+    #[arg(long = "trim-silence", env = "YTDL_TRIM_SILENCE")]
+    pub trim_silence: bool,
+
+    /// ダウンロードした動画とは別に、変換したコピーも作成（h264-720p / h264-480p / hevc）
+    ///
+    /// 元のファイルはそのまま残し、同じフォルダに "タイトル.720p.mp4" のように保存します。
+    /// 変換はダウンロードと並行してバックグラウンドで行います（ffmpegが必要）。
+    #[arg(long = "also-encode", value_enum, value_name = "PRESET", env = "YTDL_ALSO_ENCODE")]
+    pub also_encode: Option<EncodePreset>,
+
+    /// 同時に実行する変換の数（--also-encode と併用）
+    #[arg(
+        long = "encode-jobs",
+        value_name = "N",
+        default_value = "1",
+        requires = "also_encode",
+        env = "YTDL_ENCODE_JOBS"
+    )]
+    pub encode_jobs: usize,
+
+    /// 変換にハードウェアエンコーダー（NVENC / Quick Sync / VideoToolbox）を使わない
+    #[arg(long = "sw-encode", requires = "also_encode", env = "YTDL_SW_ENCODE")]
+    pub sw_encode: bool,
+
+    /// ダウンロード後もフラグメント（DASH/HLSの分割ファイル）を残す
+    #[arg(long = "keep-fragments", env = "YTDL_KEEP_FRAGMENTS")]
+    pub keep_fragments: bool,
+
+    /// 進捗バーの表示スタイル（detailed / minimal / ascii）
+    #[arg(
+        long = "progress-style",
+        value_enum,
+        default_value = "detailed",
+        env = "YTDL_PROGRESS_STYLE"
+    )]
+    pub progress_style: ProgressTheme,
+
+    /// 進捗の出力形式（bar / plain / none / json）
+    ///
+    /// plain では数秒ごとに1行の進捗を、none では動画ごとの開始・完了のみを出力します（CIのログ向け）。
+    /// json では進捗バーの代わりに、進捗ごとに1行のJSON（percent・bytes・speed・eta・filename・phase）を
+    /// 標準出力に出力します。GUIやスクリプトからは "{" で始まる行を読み取ってください。
+    #[arg(
+        long = "progress-format",
+        visible_alias = "progress",
+        value_enum,
+        default_value = "bar",
+        env = "YTDL_PROGRESS_FORMAT"
+    )]
+    pub progress_format: ProgressFormat,
+
+    /// サイズを10進単位（MB/GB）で表示（デフォルトはMiB/GiB）
+    #[arg(long = "si-units", env = "YTDL_SI_UNITS")]
+    pub si_units: bool,
+
     /// 帯域制限（例: 1M, 500K）
-    #[arg(long = "limit-rate")]
+    #[arg(long = "limit-rate", env = "YTDL_LIMIT_RATE")]
     pub rate_limit: Option<String>,
 
+    /// yt-dlpに渡す環境変数（複数指定可、例: ALL_PROXY=socks5://127.0.0.1:1080）
+    ///
+    /// 値を空にすると（例: ALL_PROXY=）このツールの環境変数を引き継がずに削除します。
+    /// 設定ファイルの [defaults] / [profiles.<名前>] の env と同じ名前の場合はこちらが優先です。
+    #[arg(long = "ytdlp-env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub ytdlp_env: Vec<(String, String)>,
+
+    /// 同時にダウンロードする断片の数（1〜32。DASH/HLSの動画が速くなる）
+    #[arg(
+        long = "fragments",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=32),
+        env = "YTDL_FRAGMENTS"
+    )]
+    pub fragments: Option<u32>,
+
+    /// 断片・動画の同時ダウンロード数を自動調整する
+    ///
+    /// 断片1・動画1本から始め、速度が落ちない間は断片（最大16）、次にプレイリストの動画の
+    /// 同時ダウンロード本数（最大 --parallel、未指定なら4）を上げます。
+    /// HTTP 429を受けたら半分に下げ、遅くなった場合は元に戻します。
+    #[arg(long = "auto-tune", conflicts_with = "fragments", env = "YTDL_AUTO_TUNE")]
+    pub auto_tune: bool,
+
+    /// バッテリー駆動中・従量制接続の間はダウンロードを一時停止し、解除後に自動再開
+    #[arg(long = "respect-metered", env = "YTDL_RESPECT_METERED")]
+    pub respect_metered: bool,
+
     /// リトライ回数
-    #[arg(short = 'r', long = "retry", default_value = "3")]
+    #[arg(short = 'r', long = "retry", default_value = "3", env = "YTDL_RETRY")]
     pub retry_count: usize,
 
-    /// 詳細ログ表示
-    #[arg(short = 'v', long = "verbose")]
-    pub verbose: bool,
+    /// 失敗した動画（通信エラー・403など）を、残りの動画が終わった後にやり直す回数（0でやり直さない）
+    ///
+    /// 設定ファイルの `[retry]` でエラーの種類ごとに回数を変えられます。
+    #[arg(long = "retry-failed", value_name = "N", default_value = "1", env = "YTDL_RETRY_FAILED")]
+    pub retry_failed: usize,
+
+    /// 途中までダウンロードしたファイル（.part）の続きから再開する（確認しない）
+    ///
+    /// どちらも指定しない場合、.part ファイルがあれば再開するか確認します（非対話モードでは再開）。
+    #[arg(long = "resume", overrides_with = "no_resume", env = "YTDL_RESUME")]
+    pub resume: bool,
+
+    /// 途中までダウンロードしたファイルを使わず、最初からダウンロードし直す
+    #[arg(long = "no-resume", overrides_with = "resume")]
+    pub no_resume: bool,
+
+    /// 詳細ログ表示（-vv で処理段階ごとの所要時間も表示）
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 実行するyt-dlpのコマンドを表示（パスワード・トークン・Cookieのパスは伏せる）
+    #[arg(long = "print-command")]
+    pub print_command: bool,
+
+    /// 終了時に処理段階ごとの所要時間の内訳を表示
+    #[arg(long = "timings")]
+    pub timings: bool,
+
+    /// 処理段階ごとのログ（所要時間を含む）とyt-dlpの出力を時刻付きで書き出すファイル
+    #[arg(long = "log-file", value_name = "PATH", env = "YTDL_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// ログファイルの上限サイズ（超えたら "<名前>.1" 〜 "<名前>.5" に移して新しいファイルに書く）
+    #[arg(
+        long = "log-max-size",
+        value_name = "SIZE",
+        default_value = "10M",
+        value_parser = parse_byte_size,
+        env = "YTDL_LOG_MAX_SIZE"
+    )]
+    pub log_max_size: u64,
 
     /// ファイル名フォーマット
     ///
     /// yt-dlpのフォーマット文字列を指定可能。
     /// 例: "%(upload_date)s_%(title)s.%(ext)s"
     /// デフォルト: "%(title)s-%(id)s.%(ext)s"
-    #[arg(long = "output-template")]
+    ///
+    /// yt-dlpの変数に加えて %(sanitized_title)s / %(upload_year)s / %(channel_slug)s /
+    /// %(quality_preset)s を使えます（動画のメタデータから決めます）。
+    #[arg(long = "output-template", env = "YTDL_OUTPUT_TEMPLATE")]
     pub output_template: Option<String>,
 
+    /// タイトル・説明文の言語（例: ja、en）
+    ///
+    /// 投稿者が翻訳を登録している場合は、その言語のタイトル・説明文を
+    /// ファイル名（テンプレート）とタグに使用します。翻訳がない動画は元のままです。
+    #[arg(
+        long = "metadata-lang",
+        value_name = "LANG",
+        value_parser = parse_metadata_lang,
+        env = "YTDL_METADATA_LANG"
+    )]
+    pub metadata_lang: Option<String>,
+
+    /// ファイル名の日本語・記号などをローマ字・ASCIIに変換（埋め込むタグのタイトルは元のまま）
+    ///
+    /// Unicodeのファイル名を正しく扱えないファイルシステムや同期ツール向けです。
+    #[arg(
+        long = "transliterate",
+        conflicts_with_all = ["split_album", "filename_policy"],
+        env = "YTDL_TRANSLITERATE"
+    )]
+    pub transliterate: bool,
+
+    /// ファイル名に使う文字の制限（strip-emoji / windows-safe / ascii）
+    ///
+    /// - strip-emoji: 絵文字・絵記号を削除
+    /// - windows-safe: Windowsで使えない文字（: ? * など）・名前（CON など）を置き換える
+    /// - ascii: 絵文字を削除し、ローマ字・ASCIIに変換（--transliterate と同じ）
+    #[arg(
+        long = "filename-policy",
+        value_enum,
+        value_name = "POLICY",
+        conflicts_with = "split_album",
+        env = "YTDL_FILENAME_POLICY"
+    )]
+    pub filename_policy: Option<FilenamePolicy>,
+
+    /// 保存先に同じ名前のファイルがある場合の扱い（skip / overwrite / rename）
+    ///
+    /// 指定するとダウンロード済みの動画も含めて確認します。未指定の場合、ファイル名に動画IDを
+    /// 含まないテンプレートでは毎回確認します（非対話モードでは rename）。
+    #[arg(
+        long = "on-conflict",
+        alias = "on-collision",
+        value_enum,
+        env = "YTDL_ON_CONFLICT"
+    )]
+    pub on_collision: Option<CollisionPolicy>,
+
+    /// ダウンロード履歴（ytdl history）に記録しない
+    #[arg(long = "no-history", env = "YTDL_NO_HISTORY")]
+    pub no_history: bool,
+
     /// ダウンロード済みアーカイブファイル（中断再開・重複回避用）
     /// デフォルト: exeと同じフォルダに "downloaded.txt" を作成
-    #[arg(long = "download-archive")]
+    #[arg(long = "download-archive", env = "YTDL_DOWNLOAD_ARCHIVE")]
     pub download_archive: Option<PathBuf>,
 
     /// アーカイブ機能を無効化（毎回全てダウンロードし直す）
-    #[arg(long = "no-archive")]
+    #[arg(long = "no-archive", env = "YTDL_NO_ARCHIVE")]
     pub no_archive: bool,
+
+    /// 履歴ではダウンロード済みでもファイルが削除されている動画は、確認せずにダウンロードし直す
+    ///
+    /// 指定しない場合は確認します（非対話モードではアーカイブに従ってスキップ）。
+    #[arg(long = "redownload-missing", env = "YTDL_REDOWNLOAD_MISSING")]
+    pub redownload_missing: bool,
+
+    /// 削除・非公開などで視聴できない動画の代わりに "<動画ID>.unavailable.json" を保存先に作成
+    ///
+    /// ファイルには理由と確認した日を記録します。後でダウンロードできた場合は削除します。
+    #[arg(long = "unavailable-placeholders", env = "YTDL_UNAVAILABLE_PLACEHOLDERS")]
+    pub unavailable_placeholders: bool,
+
+    /// 指定したチャンネル以外の動画はダウンロードしない（複数指定・カンマ区切り可）
+    ///
+    /// ハンドル（@name）、チャンネルID（UC...）、チャンネルのURLで指定します。
+    /// ダウンロード前に動画情報を取得して確認し、他のチャンネルの動画は失敗として扱います。
+    #[arg(
+        long = "own-channel",
+        value_name = "HANDLE",
+        value_delimiter = ',',
+        env = "YTDL_OWN_CHANNEL"
+    )]
+    pub own_channels: Vec<String>,
+
+    /// クリエイティブ・コモンズのライセンスの動画のみダウンロード（それ以外・不明はスキップ）
+    ///
+    /// データセットの作成など、ライセンスの条件を守る必要がある場合に使います。
+    /// ダウンロード前に動画情報のライセンスを確認します。
+    #[arg(long = "only-creative-commons", env = "YTDL_ONLY_CREATIVE_COMMONS")]
+    pub only_creative_commons: bool,
+
+    /// メタデータに基づく出力先の振り分けルール（複数指定可、最初に一致したものを適用）
+    ///
+    /// 書式: "<フィールド> <演算子> <値> -> <ディレクトリ>"
+    /// フィールド: duration（例: 60m, 1h, 90）, uploader, channel, title
+    /// 演算子: ==, !=, >, >=, <, <=, ~=（部分一致）
+    /// 例: --route "duration > 60m -> Lectures/" --route "uploader == X -> Music/X/"
+    /// 環境変数 YTDL_ROUTES では ";" で区切って複数指定できます。
+    #[arg(
+        long = "route",
+        value_name = "RULE",
+        value_parser = RouteRule::parse,
+        value_delimiter = ';',
+        env = "YTDL_ROUTES"
+    )]
+    pub routes: Vec<RouteRule>,
+
+    /// ダウンロード状態を配信するMQTTブローカー（例: localhost:1883）
+    ///
+    /// "<トピック>/status" に状態のサマリー（retain）、
+    /// "<トピック>/<動画ID>/state" に動画ごとの状態（retain）、
+    /// "<トピック>/<動画ID>/progress" に動画ごとの進捗を配信します。
+    /// IPv6アドレスでポートを指定する場合は "[::1]:1883" のように括弧で囲みます。
+    #[arg(long = "mqtt", value_name = "HOST[:PORT]", env = "YTDL_MQTT")]
+    pub mqtt_broker: Option<String>,
+
+    /// MQTTトピックのプレフィックス
+    #[arg(long = "mqtt-topic", default_value = "ytdl", env = "YTDL_MQTT_TOPIC")]
+    pub mqtt_topic: String,
+
+    /// 処理状況をJSONで返すソケット（Unixソケットのパス、または 127.0.0.1:PORT）
+    ///
+    /// 接続するとその時点の状態（URL・タイトル・進捗・速度など）を1行のJSONで返します。
+    /// ステータスバーなどから長時間のダウンロードの状況を確認できます。
+    #[arg(long = "status-socket", value_name = "PATH|ADDR", env = "YTDL_STATUS_SOCKET")]
+    pub status_socket: Option<String>,
+
+    /// タスクバーのアイコンに進捗を表示しない（Windows）
+    ///
+    /// Windowsでは、ウィンドウを最小化していても状況がわかるよう、タスクバーのアイコンに進捗を表示します。
+    #[arg(long = "no-taskbar-progress", env = "YTDL_NO_TASKBAR_PROGRESS")]
+    pub no_taskbar_progress: bool,
+
+    /// 進捗と「一時停止／再開」「中止」のボタンを通知に表示（Windows）
+    #[arg(long = "toast", env = "YTDL_TOAST")]
+    pub toast: bool,
+
+    /// Dockのアイコンのバッジに進捗を表示（macOS、Finderから.appとして起動した場合）
+    #[arg(long = "dock-badge", env = "YTDL_DOCK_BADGE")]
+    pub dock_badge: bool,
+
+    /// ダウンロード中はスリープしない（終わると元に戻す）
+    ///
+    /// Linux（systemd-inhibit）・Windowsで有効にします。macOSでは指定しなくても常に有効です。
+    #[arg(long = "keep-awake", env = "YTDL_KEEP_AWAKE")]
+    pub keep_awake: bool,
+
+    /// 実行結果のレポート（ファイル数・合計サイズ・所要時間・失敗したURLと理由）を保存
+    ///
+    /// 拡張子が .csv ならCSV（1行に1件）、それ以外はJSONで保存します。
+    #[arg(long = "report", value_name = "PATH", env = "YTDL_REPORT")]
+    pub report: Option<PathBuf>,
+
+    /// ダウンロードせず、新しくダウンロードされる動画の一覧のみ表示
+    ///
+    /// タイトル・アップロード日・推定サイズを表示します。
+    /// アーカイブ済みの動画は一覧に含まれません。
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// `ytdl queue run` で起動した（ダウンロードキューのURLを順にダウンロード）
+    #[arg(skip)]
+    pub run_queue: bool,
+
+    /// `ytdl watch-dir` で監視するフォルダ
+    #[arg(skip)]
+    pub watch_dir: Option<PathBuf>,
+
+    /// サイトごとの設定（設定ファイルの `[site.*]`、コマンドラインで指定したオプションを除く）
+    #[arg(skip)]
+    pub sites: Vec<(String, SiteConfig)>,
 }
 
 impl Cli {
@@ -103,22 +883,39 @@ impl Cli {
         // プレイリスト範囲の妥当性チェック
         if let (Some(start), Some(end)) = (self.playlist_start, self.playlist_end) {
             if start > end {
-                return Err(format!(
+                return Err(t!(
                     "プレイリスト開始位置({})が終了位置({})より大きいです",
                     start, end
                 ));
             }
             if start == 0 || end == 0 {
-                return Err("プレイリスト位置は1から始まります".to_string());
+                return Err(t!("プレイリスト位置は1から始まります").to_string());
             }
         }
 
+        // フォルダ監視ではURLを監視フォルダから受け取る
+        if self.watch_dir.is_some() && !self.url.is_empty() {
+            return Err(t!("ytdl watch-dir ではURLを指定できません（監視フォルダにURLファイルを置いてください）").to_string());
+        }
+        if self.watch_dir.is_some() && self.dry_run {
+            return Err(t!("ytdl watch-dir では --dry-run を使用できません").to_string());
+        }
+
+        if self.parallel == 0 {
+            return Err(t!("--parallel は1以上を指定してください").to_string());
+        }
+
+        if self.rclone_move && self.rclone_dest.is_none() && self.s3_dest.is_none() {
+            return Err(t!("--rclone-move には --rclone-dest または --s3-dest が必要です").to_string());
+        }
+        self.upload_dest().transpose()?;
+
         // 出力ディレクトリのチェック（存在しない場合は警告のみ）
         if let Some(output) = &self.output_dir {
             if !output.exists() {
                 eprintln!(
-                    "警告: 出力ディレクトリ '{}' が存在しません。自動作成します。",
-                    output.display()
+                    "{}",
+                    t!("警告: 出力ディレクトリ '{}' が存在しません。自動作成します。", output.display())
                 );
             }
         }
@@ -126,52 +923,389 @@ impl Cli {
         Ok(())
     }
 
+    /// プレイリスト内の動画のURLの扱い（未指定ならNone）
+    pub fn playlist_choice(&self) -> Option<PlaylistChoice> {
+        if self.playlist {
+            Some(PlaylistChoice::WholeList)
+        } else if self.this_video_only {
+            Some(PlaylistChoice::ThisVideo)
+        } else {
+            None
+        }
+    }
+
+    /// クラウド転送先（rcloneに渡す形式）
+    pub fn upload_dest(&self) -> Option<Result<String, String>> {
+        if let Some(dest) = &self.s3_dest {
+            return Some(s3_remote(dest, self.s3_endpoint.as_deref()));
+        }
+        self.rclone_dest.clone().map(Ok)
+    }
+
+    /// 品質プリセットを解決し、解像度の上限（`--resolution`）・コーデック（`--vcodec`）・音声の形式（`--audio-format`）を適用
+    ///
+    /// `--format-string` を指定した場合は、プリセットのフォーマットの代わりにそのまま使います。
+    pub fn quality_profile(&self, config: &Config) -> Result<QualityProfile, String> {
+        let mut profile = QualityProfile::resolve(&self.quality, config)?;
+        if let Some(format) = &self.format_string {
+            profile.format = format.clone();
+            profile.description = format.clone();
+        } else if let Some(resolution) = self.resolution {
+            profile = profile.with_resolution(resolution)?;
+        }
+        if let (Some(codec), None) = (self.vcodec, &self.format_string) {
+            profile = profile.with_vcodec(codec);
+        }
+        if let Some(format) = self.audio_format {
+            profile = profile.with_audio_format(format);
+        }
+        if let Some(container) = self.container {
+            profile = profile.with_container(container);
+        }
+        Ok(profile)
+    }
+
     /// 現在の設定を表示
-    pub fn display_config(&self) {
-        println!("=== ダウンロード設定 ===");
-        if let Some(url) = &self.url {
-            println!("URL: {}", url);
-        }
-        println!("品質: {} ({})",
-            format!("{:?}", self.quality).to_lowercase(),
-            self.quality.description()
-        );
+    pub fn display_config(&self, profile: &QualityProfile) {
+        println!("{}", t!("=== ダウンロード設定 ==="));
+        match self.url.as_slice() {
+            [] => {}
+            [url] => println!("URL: {}", url),
+            urls => println!("{}", t!("URL: {}件", i18n::number(urls.len()))),
+        }
+        if let Some(name) = &self.profile {
+            println!("{}", t!("プロファイル: {}", name));
+        }
+        println!("{}", t!("品質: {} ({})", profile.name, profile.display_description()));
+        if profile.extract_audio && self.audio_format.is_some() {
+            println!("{}", t!("音声の形式: {}", profile.audio_format()));
+        }
+        if let (false, Some(container)) = (profile.extract_audio, &profile.container) {
+            println!("{}", t!("コンテナ: {}", container));
+        }
+        if profile.extract_audio && self.audio_quality != AudioQuality::Best {
+            println!("{}", t!("音質: {}", self.audio_quality.to_ytdlp_arg()));
+        }
+        if let Some(max_bytes) = self.target_size {
+            println!("{}", t!("サイズ上限: {}", format_bytes(max_bytes)));
+        }
         if let Some(output) = &self.output_dir {
-            println!("出力先: {}", output.display());
+            println!("{}", t!("出力先: {}", output.display()));
         } else {
-            println!("出力先: exeと同じフォルダ");
+            println!("{}", t!("出力先: exeと同じフォルダ"));
         }
 
-        if let Some(browser) = &self.cookie_browser {
-            println!("Cookie: {} ブラウザから自動検出", browser);
+        if let Some(file) = &self.cookies_file {
+            println!("Cookie: {}", file.display());
+        } else if let Some(browser) = &self.cookie_browser {
+            println!("{}", t!("Cookie: {} ブラウザから自動検出", browser));
         } else {
-            println!("Cookie: 使用しない（公開動画のみ）");
+            println!("{}", t!("Cookie: 使用しない（公開動画のみ）"));
+        }
+
+        if self.cookie_browser.is_some() && self.cookie_snapshot {
+            println!("{}", t!("Cookieスナップショット: 有効"));
         }
 
         if self.playlist {
-            print!("プレイリスト: 全体");
+            print!("{}", t!("プレイリスト: 全体"));
             if let Some(start) = self.playlist_start {
-                print!(" (開始: {})", start);
+                print!("{}", t!(" (開始: {})", start));
             }
             if let Some(end) = self.playlist_end {
-                print!(" (終了: {})", end);
+                print!("{}", t!(" (終了: {})", end));
             }
             println!();
+            if self.parallel > 1 {
+                println!("{}", t!("同時ダウンロード: {}本", self.parallel));
+            }
+        }
+
+        if self.embed_subs {
+            println!("{}", t!("字幕: 動画に埋め込む"));
+        } else if self.download_subtitle {
+            println!("{}", t!("字幕: ダウンロードする"));
+        }
+
+        if self.write_storyboards {
+            println!("{}", t!("ストーリーボード: 保存する"));
         }
 
-        if self.download_subtitle {
-            println!("字幕: ダウンロードする");
+        if self.write_heatmap {
+            println!("{}", t!("ヒートマップ: 保存する"));
         }
 
         if self.save_metadata {
-            println!("メタデータ: 保存する");
+            println!("{}", t!("メタデータ: 保存する"));
+        }
+
+        if self.split_album {
+            println!("{}", t!("アルバム: チャプターごとに曲を分割する"));
+        }
+
+        if self.trim_silence {
+            println!("{}", t!("無音: 先頭・末尾を削除する"));
+        }
+
+        if let Some(preset) = self.also_encode {
+            println!("{}", t!("変換コピー: {}（同時に{}件まで）", preset.description(), self.encode_jobs.max(1)));
+        }
+
+        if let Some(lang) = &self.metadata_lang {
+            println!("{}", t!("メタデータの言語: {}", lang));
+        }
+
+        if let Some(policy) = self.filename_policy {
+            println!("{}", t!("ファイル名: {}", policy.description()));
+        } else if self.transliterate {
+            println!("{}", t!("ファイル名: {}", FilenamePolicy::Ascii.description()));
+        }
+
+        if let Some(policy) = self.on_collision {
+            println!("{}", t!("同名ファイル: {}", policy.description()));
+        }
+
+        if self.keep_video || self.keep_fragments {
+            let kept: Vec<&str> = [
+                (self.keep_video, t!("元の動画")),
+                (self.keep_fragments, t!("フラグメント")),
+            ]
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, name)| *name)
+            .collect();
+            println!("{}", t!("中間ファイル: {}を残す", kept.join(t!("・"))));
         }
 
         if let Some(rate) = &self.rate_limit {
-            println!("帯域制限: {}", rate);
+            println!("{}", t!("帯域制限: {}", rate));
+        }
+
+        if let Some(fragments) = self.fragments {
+            println!("{}", t!("断片の同時ダウンロード: {}", fragments));
+        }
+
+        if self.auto_tune {
+            println!("{}", t!("同時ダウンロード数: 自動調整"));
+        }
+
+        println!("{}", t!("リトライ回数: {}", self.retry_count));
+        if self.retry_failed != 1 {
+            println!("{}", t!("失敗した動画のやり直し: {}回", self.retry_failed));
+        }
+
+        if self.resume {
+            println!("{}", t!("中断したダウンロード: 続きから再開する"));
+        } else if self.no_resume {
+            println!("{}", t!("中断したダウンロード: 最初からやり直す"));
+        }
+
+        if self.respect_metered {
+            println!("{}", t!("省電力・従量制接続: 一時停止する"));
+        }
+
+        if !self.own_channels.is_empty() {
+            println!("{}", t!("許可するチャンネル: {}", self.own_channels.join(", ")));
+        }
+
+        if self.only_creative_commons {
+            println!("{}", t!("ライセンス: クリエイティブ・コモンズのみ"));
+        }
+
+        if !self.routes.is_empty() {
+            println!("{}", t!("振り分けルール: {}件", i18n::number(self.routes.len())));
+        }
+
+        if let Some(dest) = self.s3_dest.as_ref().or(self.rclone_dest.as_ref()) {
+            let mode = if self.rclone_move { t!("移動") } else { t!("コピー") };
+            println!("{}", t!("クラウド転送: {} ({})", dest, mode));
+        }
+
+        if let Some(broker) = &self.mqtt_broker {
+            println!("{}", t!("MQTT配信: {} (トピック: {}/...)", broker, self.mqtt_topic));
+        }
+
+        if let Some(socket) = &self.status_socket {
+            println!("{}", t!("状態ソケット: {}", socket));
+        }
+
+        if let Some(report) = &self.report {
+            println!("{}", t!("レポート: {}", report.display()));
         }
 
-        println!("リトライ回数: {}", self.retry_count);
+        // 値にはプロキシの認証情報などが含まれることがあるため、名前のみ表示
+        if !self.ytdlp_env.is_empty() {
+            let names: Vec<&str> = self.ytdlp_env.iter().map(|(key, _)| key.as_str()).collect();
+            println!("{}", t!("yt-dlpの環境変数: {}", names.join(", ")));
+        }
+
+        if self.dry_run {
+            println!("{}", t!("モード: ドライラン（ダウンロードしない）"));
+        }
+        if let Some(dir) = &self.watch_dir {
+            println!("{}", t!("モード: フォルダ監視 ({})", dir.display()));
+        }
+        if let Some(url) = &self.watch_until_available {
+            println!("{}", t!("モード: ダウンロードできるまで確認 ({}、{}秒ごと)", url, self.poll.as_secs()));
+        }
         println!("========================\n");
     }
 }
+
+/// `--poll` の間隔をパース（例: "90" -> 90秒, "10m" -> 600秒, "1h" -> 3600秒）
+fn parse_interval(input: &str) -> Result<Duration, String> {
+    let value = input.trim();
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3600),
+        _ => (value, 1),
+    };
+    // 秒に換算してあふれる値は不正な指定として扱う
+    match number.trim().parse::<u64>().ok().and_then(|n| n.checked_mul(multiplier)) {
+        Some(seconds) if seconds >= 30 => Ok(Duration::from_secs(seconds)),
+        Some(_) => Err(t!("確認の間隔は30秒以上を指定してください: {}", input)),
+        None => Err(t!("確認の間隔が不正です: {}（例: 30s、10m、1h）", input)),
+    }
+}
+
+/// `--ytdlp-env` の "KEY=VALUE" をパース
+fn parse_env_var(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(t!("環境変数の指定が不正です: {}（例: ALL_PROXY=socks5://127.0.0.1:1080）", input)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        App::command().debug_assert();
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let url = "https://www.youtube.com/watch?v=abc";
+
+        // サブコマンドを省略した場合はダウンロード
+        let cli = App::parse_from(["ytdl", "-q", "max-audio", url]).into_download().unwrap();
+        assert_eq!(cli.url, vec![url]);
+        assert_eq!(cli.quality, "max-audio");
+
+        let cli = App::parse_from(["ytdl", "download", "-q", "max-audio", url])
+            .into_download()
+            .unwrap();
+        assert_eq!(cli.url, vec![url]);
+        assert_eq!(cli.quality, "max-audio");
+
+        let command = App::parse_from(["ytdl", "archive", "remove", "abc", "--file", "a.txt"])
+            .into_download()
+            .unwrap_err();
+        assert!(matches!(
+            command,
+            Command::Archive(ArchiveArgs { file: Some(_), action: ArchiveAction::Remove { .. } })
+        ));
+
+        let app = App::parse_from(["ytdl", "lang", "list", "--lang", "en"]);
+        assert_eq!(app.lang, Some(Lang::En));
+        assert!(matches!(app.command, Some(Command::Lang { action: LangAction::List })));
+
+        let command = App::parse_from(["ytdl", "cookies", "pull", "user@host"]).into_download().unwrap_err();
+        assert!(matches!(command, Command::Cookies { action: CookiesAction::Pull { remote } } if remote == "user@host"));
+
+        let command = App::parse_from(["ytdl", "pause", "1234"]).into_download().unwrap_err();
+        assert!(matches!(command, Command::Pause { pid: 1234 }));
+
+        let command = App::parse_from(["ytdl", "dedupe", "videos"]).into_download().unwrap_err();
+        assert!(matches!(command, Command::Dedupe { dir, non_interactive: false } if dir.as_os_str() == "videos"));
+
+        let cli = App::parse_from(["ytdl", "watch-dir", "inbox", "-q", "max-audio"]).into_download().unwrap();
+        assert_eq!(cli.watch_dir, Some(PathBuf::from("inbox")));
+        assert_eq!(cli.quality, "max-audio");
+        assert!(cli.url.is_empty());
+
+        // フォルダ監視ではURLを受け取らない
+        let cli = App::parse_from(["ytdl", "watch-dir", "inbox", url]).into_download().unwrap();
+        assert!(cli.validate().is_err());
+
+        // ダウンロードのオプションは他のサブコマンドには指定できない
+        assert!(App::try_parse_from(["ytdl", "info", url, "-q", "max-audio"]).is_err());
+    }
+
+    #[test]
+    fn test_routes_delimiter() {
+        let url = "https://www.youtube.com/watch?v=abc";
+        let cli = App::parse_from([
+            "ytdl",
+            "--route",
+            "duration > 60m -> Lectures/;uploader == A, B -> Music/",
+            "--route",
+            "title ~= live -> Live/",
+            url,
+        ])
+        .into_download()
+        .unwrap();
+        let dirs: Vec<_> = cli.routes.iter().map(|rule| rule.dir.clone()).collect();
+        assert_eq!(dirs, [PathBuf::from("Lectures/"), PathBuf::from("Music/"), PathBuf::from("Live/")]);
+    }
+
+    #[test]
+    fn test_fragments_range() {
+        let url = "https://www.youtube.com/watch?v=abc";
+        let cli = App::parse_from(["ytdl", "--fragments", "8", url]).into_download().unwrap();
+        assert_eq!(cli.fragments, Some(8));
+
+        assert!(App::try_parse_from(["ytdl", "--fragments", "0", url]).is_err());
+        assert!(App::try_parse_from(["ytdl", "--fragments", "33", url]).is_err());
+        assert!(App::try_parse_from(["ytdl", "--auto-tune", "--fragments", "8", url]).is_err());
+    }
+
+    #[test]
+    fn test_parse_env_var() {
+        assert_eq!(
+            parse_env_var("SSLKEYLOGFILE=/tmp/keys.log"),
+            Ok(("SSLKEYLOGFILE".to_string(), "/tmp/keys.log".to_string()))
+        );
+        assert_eq!(parse_env_var("ALL_PROXY="), Ok(("ALL_PROXY".to_string(), String::new())));
+        assert!(parse_env_var("=value").is_err());
+        assert!(parse_env_var("NO_VALUE").is_err());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_interval("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_interval("90"), Ok(Duration::from_secs(90)));
+        assert!(parse_interval("5s").is_err());
+        assert!(parse_interval("soon").is_err());
+        assert!(parse_interval("99999999999999999h").is_err());
+    }
+
+    #[test]
+    fn test_format_string() {
+        let url = "https://www.youtube.com/watch?v=abc";
+        let format = "bv*[height<=1080][fps<=30]+ba";
+        let cli = App::parse_from(["ytdl", "--format-string", format, "--resolution", "720", url])
+            .into_download()
+            .unwrap();
+        let profile = cli.quality_profile(&Config::default()).unwrap();
+        assert_eq!(profile.format, format);
+        assert_eq!(profile.name, "max-video");
+    }
+
+    #[test]
+    fn test_container() {
+        let url = "https://www.youtube.com/watch?v=abc";
+        let cli = App::parse_from(["ytdl", "-q", "balanced", "--container", "mkv", url])
+            .into_download()
+            .unwrap();
+        let profile = cli.quality_profile(&Config::default()).unwrap();
+        assert_eq!(profile.container.as_deref(), Some("mkv"));
+        assert!(App::try_parse_from(["ytdl", "--container", "avi", url]).is_err());
+    }
+}
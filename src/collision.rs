@@ -0,0 +1,115 @@
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+use crate::t;
+
+/// 保存先に同じ名前のファイルがある場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CollisionPolicy {
+    /// 名前の末尾に番号を付けて保存（"タイトル (2).mp4"）
+    #[value(alias = "suffix")]
+    Rename,
+    /// 既存のファイルを上書き
+    Overwrite,
+    /// ダウンロードしない
+    Skip,
+}
+
+impl CollisionPolicy {
+    /// 説明文
+    pub fn description(&self) -> &str {
+        match self {
+            CollisionPolicy::Rename => t!("番号を付けて保存"),
+            CollisionPolicy::Overwrite => t!("上書き"),
+            CollisionPolicy::Skip => t!("スキップ"),
+        }
+    }
+
+    /// yt-dlpの上書きの指定（番号を付ける場合は保存先の名前を変えるため指定しない）
+    pub fn ytdlp_arg(&self) -> Option<&'static str> {
+        match self {
+            CollisionPolicy::Rename => None,
+            CollisionPolicy::Overwrite => Some("--force-overwrites"),
+            CollisionPolicy::Skip => Some("--no-overwrites"),
+        }
+    }
+}
+
+/// yt-dlpがダウンロード済みとしてスキップしたファイル
+/// （"[download] 保存先 has already been downloaded"）
+pub fn already_downloaded(line: &str) -> Option<PathBuf> {
+    let path = line
+        .strip_prefix("[download] ")?
+        .strip_suffix(" has already been downloaded")?;
+    Some(PathBuf::from(path))
+}
+
+/// 拡張子を除いた名前が同じファイルを探す（後処理で拡張子が変わるため）
+///
+/// `target` はyt-dlpが決めた保存先（拡張子は変換前のもの）。ダウンロード途中のファイルは除きます。
+pub fn find_existing(target: &Path) -> Option<PathBuf> {
+    let dir = target.parent()?;
+    let stem = target.file_stem()?;
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            !matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("part" | "ytdl" | "temp")
+            )
+        })
+        .find(|path| path.file_stem() == Some(stem))
+}
+
+/// 名前が衝突しない保存先（拡張子を除く）を探す（例: "dir/タイトル" -> "dir/タイトル (2)"）
+pub fn free_stem(target: &Path) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = target
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|n| format!("{} ({})", stem, n))
+        .find(|name| find_existing(&target.with_file_name(format!("{}{}", name, ext))).is_none())
+        .map(|name| target.with_file_name(name))
+        .expect("空き番号が見つかるまで探す")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_existing_and_free_stem() {
+        let root = std::env::temp_dir().join(format!("ytdl-test-collision-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("Song.mp3"), b"a").unwrap();
+        std::fs::write(root.join("Song (2).mp3"), b"b").unwrap();
+        std::fs::write(root.join("Other.webm.part"), b"c").unwrap();
+        std::fs::write(root.join("Vol. 2.mp3"), b"d").unwrap();
+
+        // 変換前の拡張子が違っても、同じ名前なら既存とみなす
+        assert_eq!(find_existing(&root.join("Song.webm")), Some(root.join("Song.mp3")));
+        assert_eq!(find_existing(&root.join("Other.webm")), None);
+        assert_eq!(free_stem(&root.join("Song.webm")), root.join("Song (3)"));
+        assert_eq!(free_stem(&root.join("Vol. 2.webm")), root.join("Vol. 2 (2)"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_already_downloaded() {
+        assert_eq!(
+            already_downloaded("[download] /tmp/out/Song.mp3 has already been downloaded"),
+            Some(PathBuf::from("/tmp/out/Song.mp3"))
+        );
+        assert_eq!(already_downloaded("[download] Destination: /tmp/out/Song.webm"), None);
+    }
+}
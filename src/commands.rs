@@ -0,0 +1,438 @@
+use std::process::Command as ProcessCommand;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::archive;
+use crate::cli::{ArchiveAction, ArchiveArgs, Command, CookiesAction, HistoryAction, InfoArgs, LangAction, QueueAction};
+use crate::cookie_detector::{self, CookieDetector};
+use crate::dedupe::{self, DedupeAction};
+use crate::error::{Result, YtdlError};
+use crate::history::{History, HistoryEntry};
+use crate::i18n::{self, Lang};
+use crate::interactive::InteractiveMode;
+use crate::item_logs;
+use crate::metadata::{self, FormatInfo, VideoInfo};
+use crate::pause;
+use crate::progress_parser::format_bytes;
+use crate::queue::Queue;
+use crate::updater::Updater;
+use crate::ytdlp_wrapper::YtdlpWrapper;
+use crate::t;
+
+/// ダウンロード以外のサブコマンドを実行
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Download(_) | Command::WatchDir(_) => unreachable!("ダウンロードは呼び出し元で実行する"),
+        Command::Info(args) => info(&args),
+        Command::Formats(args) => formats(&args),
+        Command::Update => update(),
+        Command::Archive(args) => archive(&args),
+        Command::Logs { id } => logs(id.as_deref()),
+        Command::Queue { action } => queue(action),
+        Command::History { action } => history(&action),
+        Command::Lang { action } => lang(&action),
+        Command::Dedupe { dir, non_interactive } => dedupe(&dir, non_interactive),
+        Command::Pause { pid } => {
+            pause::request(pid, true)?;
+            println!("{}", t!("⏸  プロセス {} に一時停止を要求しました", pid));
+            Ok(())
+        }
+        Command::Resume { pid } => {
+            pause::request(pid, false)?;
+            println!("{}", t!("▶  プロセス {} に再開を要求しました", pid));
+            Ok(())
+        }
+        Command::Cookies { action } => cookies(&action),
+    }
+}
+
+/// 重複ファイルの整理（非対話モードでは一覧の表示のみ）
+fn dedupe(dir: &Path, non_interactive: bool) -> Result<()> {
+    dedupe::run(dir, |_| {
+        if non_interactive {
+            DedupeAction::Keep
+        } else {
+            InteractiveMode::ask_dedupe_action()
+        }
+    })
+}
+
+/// Cookieの取得
+fn cookies(action: &CookiesAction) -> Result<()> {
+    match action {
+        CookiesAction::Pull { remote } => {
+            println!("{}", t!("🍪 {} からcookies.txtを取得中...", remote));
+            let path = cookie_detector::pull_remote_cookies(remote)?;
+            println!("{}", t!("✅ cookies.txtを保存しました: {}", path.display()));
+            println!("{}", t!("   以降の実行で自動的に使用されます"));
+        }
+    }
+    Ok(())
+}
+
+/// 表示する言語の確認
+fn lang(action: &LangAction) -> Result<()> {
+    match action {
+        LangAction::List => {
+            for lang in Lang::ALL {
+                let mark = if *lang == i18n::lang() { "*" } else { " " };
+                println!("{} {:<4} {}", mark, lang.code(), lang.native_name());
+            }
+            println!(
+                "{}",
+                t!("\n変更: --lang <コード>、環境変数 YTDL_LANG、または設定ファイルの lang = \"<コード>\"")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 動画の情報を表示
+fn info(args: &InfoArgs) -> Result<()> {
+    for (info, json) in fetch_videos(args)? {
+        if args.json {
+            println!("{}", json);
+            continue;
+        }
+        println!("📺 {}", info.display_title());
+        println!("   ID: {}", info.id);
+        if let Some(channel) = info.channel.as_ref().or(info.uploader.as_ref()) {
+            println!("{}", t!("   チャンネル: {}", channel));
+        }
+        if let Some(duration) = info.duration {
+            println!("{}", t!("   再生時間: {}", format_length(duration as u64)));
+        }
+        if let Some(date) = info.upload_date_str() {
+            println!("{}", t!("   アップロード日: {}", i18n::localize_date(&date)));
+        }
+        if let Some(views) = info.view_count {
+            println!("{}", t!("   再生回数: {}", i18n::number(views)));
+        }
+        if let Some(url) = &info.webpage_url {
+            println!("   URL: {}", url);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// 利用できるフォーマットを表示
+fn formats(args: &InfoArgs) -> Result<()> {
+    for (info, json) in fetch_videos(args)? {
+        if args.json {
+            println!("{}", json);
+            continue;
+        }
+        println!("📺 {} ({})", info.display_title(), info.id);
+        // 列がずれないよう、見出しはyt-dlpの -F と同じ英語にする
+        println!(
+            "   {:<10} {:<5} {:<11} {:>5} {:<14} {:<10} {:>9} {:>12}",
+            "ID", "EXT", "RESOLUTION", "FPS", "VCODEC", "ACODEC", "TBR", "SIZE"
+        );
+        for format in sorted_formats(info.formats.as_deref().unwrap_or_default()) {
+            println!("   {}", format_row(format, info.duration));
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// yt-dlpを更新してバージョンを表示
+fn update() -> Result<()> {
+    println!("{}", t!("🔄 yt-dlpを最新版に更新中..."));
+    Updater::update_ytdlp()?;
+    println!("📦 yt-dlp {}", Updater::show_version()?);
+    Ok(())
+}
+
+/// アーカイブの確認・編集
+fn archive(args: &ArchiveArgs) -> Result<()> {
+    let path = args.file.clone().unwrap_or_else(archive::default_path);
+    match &args.action {
+        ArchiveAction::List => {
+            let entries = archive::read_entries(&path)?;
+            for entry in &entries {
+                println!("{} {}", entry.extractor, entry.id);
+            }
+            eprintln!("{}", t!("📋 {}件（{}）", i18n::number(entries.len()), path.display()));
+        }
+        ArchiveAction::Remove { ids } => {
+            let removed = archive::remove_ids(&path, ids)?;
+            println!("{}", t!("🗑  {}件の記録を削除しました（{}）", i18n::number(removed), path.display()));
+            if removed < ids.len() {
+                eprintln!("{}", t!("警告: 記録されていないIDがあります"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 動画のログを表示（IDを省略した場合は一覧）
+fn logs(id: Option<&str>) -> Result<()> {
+    if let Some(id) = id {
+        print!("{}", item_logs::read(id)?);
+        return Ok(());
+    }
+
+    let logs = item_logs::list()?;
+    for (id, modified) in &logs {
+        let age = modified.elapsed().unwrap_or_default().as_secs();
+        println!("{:<14} {}", id, t!("{}前", format_age(age)));
+    }
+    eprintln!("{}", t!("📋 {}件（{}）", i18n::number(logs.len()), item_logs::logs_dir()?.display()));
+    Ok(())
+}
+
+/// ダウンロードキューの確認・編集
+fn queue(action: QueueAction) -> Result<()> {
+    let mut queue = Queue::open()?;
+    match action {
+        QueueAction::Run(_) => unreachable!("キューのダウンロードは呼び出し元で実行する"),
+        QueueAction::List => {
+            for (i, entry) in queue.entries().iter().enumerate() {
+                let age = SystemTime::now()
+                    .duration_since(UNIX_EPOCH + Duration::from_secs(entry.added))
+                    .unwrap_or_default()
+                    .as_secs();
+                println!("{:>3}. {}", i + 1, t!("{}（{}前に追加）", entry.url, format_age(age)));
+                if let Some(error) = &entry.error {
+                    println!("{}", t!("     ✗ 前回失敗: {}", error));
+                }
+            }
+            eprintln!("{}", t!("📋 {}件（{}）", i18n::number(queue.entries().len()), queue.path().display()));
+            return Ok(());
+        }
+        QueueAction::Add { urls } => {
+            for url in &urls {
+                if queue.add(url) {
+                    println!("➕ {}", url);
+                } else {
+                    eprintln!("{}", t!("警告: 追加済みです: {}", url));
+                }
+            }
+            println!("{}", t!("📋 キュー: {}件（ytdl queue run でダウンロード）", i18n::number(queue.entries().len())));
+        }
+        QueueAction::Move { from, to } => {
+            queue.move_to(from, to)?;
+            println!("{}", t!("↕  {}番目を{}番目に移動しました", from, to));
+        }
+        QueueAction::Remove { positions } => {
+            for entry in queue.remove(&positions)? {
+                println!("🗑  {}", entry.url);
+            }
+        }
+        QueueAction::Clear => {
+            println!("{}", t!("🗑  {}件を削除しました", i18n::number(queue.clear())));
+        }
+    }
+    queue.save()
+}
+
+/// ダウンロード履歴の確認・削除
+fn history(action: &HistoryAction) -> Result<()> {
+    let history = History::open()?;
+    let entries = match action {
+        HistoryAction::List { limit } => history.list(*limit)?,
+        HistoryAction::Search { query } => history.search(query)?,
+        HistoryAction::Clear => {
+            println!("{}", t!("🗑  {}件の履歴を削除しました", i18n::number(history.clear()?)));
+            return Ok(());
+        }
+    };
+    for entry in &entries {
+        print_history_entry(entry);
+    }
+    eprintln!("{}", t!("📋 {}件", i18n::number(entries.len())));
+    Ok(())
+}
+
+/// 履歴の1件（日時・タイトル・品質・サイズと保存先）
+fn print_history_entry(entry: &HistoryEntry) {
+    let title = entry
+        .title
+        .as_deref()
+        .or(entry.video_id.as_deref())
+        .unwrap_or(t!("（タイトル不明）"));
+    println!(
+        "{}  {}（{}、{}）",
+        i18n::localize_date(&entry.date),
+        title,
+        entry.quality,
+        format_bytes(entry.size)
+    );
+    let exists = if entry.path.exists() { "" } else { t!("（削除済み）") };
+    println!("    {}{}", entry.path.display(), exists);
+}
+
+/// yt-dlpで動画情報（パースしたものと元のJSON）を取得
+fn fetch_videos(args: &InfoArgs) -> Result<Vec<(VideoInfo, String)>> {
+    YtdlpWrapper::check_ytdlp_available()?;
+
+    let mut videos = Vec::new();
+    for url in &args.url {
+        let mut cmd = ProcessCommand::new("yt-dlp");
+        cmd.args(["--dump-json", "--ignore-errors"]);
+        if let Some(file) = &args.cookies_file {
+            cmd.arg("--cookies").arg(file);
+        } else if let Some(browser) = &args.cookie_browser {
+            let detector = CookieDetector::from_str(browser)?;
+            cmd.arg("--cookies-from-browser")
+                .arg(detector.get_ytdlp_browser_arg());
+        }
+        if let Some(lang) = &args.metadata_lang {
+            cmd.arg("--extractor-args").arg(metadata::metadata_lang_arg(lang));
+        }
+        cmd.arg(url);
+
+        let output = cmd
+            .output()
+            .map_err(|e| YtdlError::ProcessError(t!("動画情報の取得に失敗: {}", e)))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let before = videos.len();
+        for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+            match VideoInfo::from_json_line(line) {
+                Ok(info) => videos.push((info, line.to_string())),
+                Err(e) => eprintln!("{}", t!("警告: {}", e)),
+            }
+        }
+        if videos.len() == before && !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(YtdlError::DownloadFailed(t!("情報取得失敗: {}", error.trim())));
+        }
+    }
+    Ok(videos)
+}
+
+/// 表示する順に並べたフォーマット
+///
+/// 映像と音声 → 映像のみ → 音声のみ → その他（ストーリーボードなど）の順で、
+/// それぞれ解像度・フレームレート・ビットレートの高い順にします。
+fn sorted_formats(formats: &[FormatInfo]) -> Vec<&FormatInfo> {
+    let group = |format: &FormatInfo| match (format.has_video(), format.has_audio()) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (false, false) => 3,
+    };
+    let mut sorted: Vec<&FormatInfo> = formats.iter().collect();
+    sorted.sort_by(|a, b| {
+        group(a)
+            .cmp(&group(b))
+            .then(b.height.cmp(&a.height))
+            .then(b.fps.unwrap_or_default().total_cmp(&a.fps.unwrap_or_default()))
+            .then(b.tbr.unwrap_or_default().total_cmp(&a.tbr.unwrap_or_default()))
+    });
+    sorted
+}
+
+/// フォーマット一覧の1行
+fn format_row(format: &FormatInfo, duration: Option<f64>) -> String {
+    let resolution = match (format.width, format.height) {
+        (Some(width), Some(height)) => format!("{}x{}", width, height),
+        (None, Some(height)) => format!("{}p", height),
+        _ if format.has_audio() && !format.has_video() => "audio only".to_string(),
+        _ => "-".to_string(),
+    };
+    let codec = |codec: &Option<String>| match codec.as_deref() {
+        None | Some("none") => "-".to_string(),
+        Some(codec) => codec.to_string(),
+    };
+    let size = format
+        .size_estimate()
+        .map(format_bytes)
+        .or_else(|| {
+            // 不明な場合はビットレートと再生時間から推定
+            let bytes = format.tbr? * 1000.0 / 8.0 * duration?;
+            Some(format!("~{}", format_bytes(bytes as u64)))
+        })
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "{:<10} {:<5} {:<11} {:>5} {:<14} {:<10} {:>9} {:>12}",
+        format.format_id.as_deref().unwrap_or("-"),
+        format.ext.as_deref().unwrap_or("-"),
+        resolution,
+        format.fps.map(|fps| format!("{}", fps)).unwrap_or_else(|| "-".to_string()),
+        codec(&format.vcodec),
+        codec(&format.acodec),
+        format.tbr.map(|tbr| format!("{:.0}", tbr)).unwrap_or_else(|| "-".to_string()),
+        size
+    )
+}
+
+/// 経過時間を "5分" / "3時間" / "2日" の形式にする
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => t!("{}秒", seconds),
+        60..=3599 => t!("{}分", seconds / 60),
+        3600..=86399 => t!("{}時間", seconds / 3600),
+        _ => t!("{}日", seconds / 86400),
+    }
+}
+
+/// 再生時間を "1:02:03" / "3:25" の形式にする
+pub(crate) fn format_length(seconds: u64) -> String {
+    let (hours, minutes, secs) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_row() {
+        let format: FormatInfo = serde_json::from_str(
+            r#"{"format_id": "137", "ext": "mp4", "width": 1920, "height": 1080, "fps": 30,
+                "vcodec": "avc1.640028", "acodec": "none", "tbr": 4000}"#,
+        )
+        .unwrap();
+        let row = format_row(&format, Some(100.0));
+        assert!(row.starts_with("137        mp4   1920x1080      30 avc1.640028    -"));
+        assert!(row.ends_with("~47.68 MiB"));
+
+        let format: FormatInfo =
+            serde_json::from_str(r#"{"format_id": "251", "vcodec": "none", "acodec": "opus"}"#).unwrap();
+        assert!(format_row(&format, None).contains("audio only"));
+    }
+
+    #[test]
+    fn test_sorted_formats() {
+        let formats: Vec<FormatInfo> = serde_json::from_str(
+            r#"[
+                {"format_id": "sb0", "ext": "mhtml", "vcodec": "none", "acodec": "none"},
+                {"format_id": "140", "vcodec": "none", "acodec": "mp4a.40.2", "tbr": 129},
+                {"format_id": "251", "vcodec": "none", "acodec": "opus", "tbr": 135},
+                {"format_id": "136", "height": 720, "vcodec": "avc1", "acodec": "none"},
+                {"format_id": "18", "height": 360, "vcodec": "avc1", "acodec": "mp4a.40.2"},
+                {"format_id": "299", "height": 1080, "fps": 60, "vcodec": "avc1", "acodec": "none"},
+                {"format_id": "137", "height": 1080, "fps": 30, "vcodec": "avc1", "acodec": "none"}
+            ]"#,
+        )
+        .unwrap();
+        let ids: Vec<&str> = sorted_formats(&formats)
+            .iter()
+            .filter_map(|format| format.format_id.as_deref())
+            .collect();
+        assert_eq!(ids, ["18", "299", "137", "136", "251", "140", "sb0"]);
+    }
+
+    #[test]
+    fn test_format_age() {
+        crate::i18n::set_lang(crate::i18n::Lang::Ja);
+        assert_eq!(format_age(42), "42秒");
+        assert_eq!(format_age(300), "5分");
+        assert_eq!(format_age(7200), "2時間");
+        assert_eq!(format_age(3 * 86400), "3日");
+    }
+
+    #[test]
+    fn test_format_length() {
+        assert_eq!(format_length(205), "3:25");
+        assert_eq!(format_length(3723), "1:02:03");
+    }
+}
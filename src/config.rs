@@ -0,0 +1,439 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{Cli, SpecifiedArgs};
+use crate::error::{Result, YtdlError};
+use crate::filename::FilenamePolicy;
+use crate::hooks::Hooks;
+use crate::i18n::Lang;
+use crate::paths;
+use crate::quality::{AudioFormat, Container, QualityProfile, Resolution, VideoCodec};
+use crate::retry_policy::RetryPolicy;
+use crate::routing::RouteRule;
+use crate::site::SiteConfig;
+use crate::t;
+
+/// 設定ファイル（config.toml）
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// 表示する言語（`--lang`、初回の実行時に確認して保存）
+    #[serde(default)]
+    pub lang: Option<Lang>,
+    /// ユーザー定義の品質プリセット
+    #[serde(default)]
+    pub presets: BTreeMap<String, QualityProfile>,
+    /// 処理の段階ごとに実行するコマンド
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// ダウンロードのオプションのデフォルト
+    #[serde(default)]
+    pub defaults: Defaults,
+    /// `--profile` で選択するオプションの組み合わせ
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Defaults>,
+    /// 失敗した動画をやり直す回数（エラーの分類ごと）
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// サイトごとの設定（`[site."twitch.tv"]`、URLのホスト名で選択）
+    #[serde(default)]
+    pub site: BTreeMap<String, SiteConfig>,
+}
+
+/// 設定ファイルで指定するダウンロードのオプション（`[defaults]` / `[profiles.<名前>]`）
+///
+/// コマンドライン引数・環境変数で指定したものが優先されます。
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Defaults {
+    /// 品質プリセット（`--quality`）
+    pub quality: Option<String>,
+    /// 解像度の上限（`--resolution`、例: 1080）
+    pub resolution: Option<Resolution>,
+    /// 映像のコーデック（`--vcodec`）
+    pub vcodec: Option<VideoCodec>,
+    /// 音声抽出時の形式（`--audio-format`）
+    pub audio_format: Option<AudioFormat>,
+    /// 映像付きダウンロードのコンテナ形式（`--container`）
+    pub container: Option<Container>,
+    /// 出力先ディレクトリ（`--output`）
+    pub output: Option<PathBuf>,
+    /// ファイル名のテンプレート（`--output-template`）
+    pub output_template: Option<String>,
+    /// Cookieを読み込むブラウザ（`--cookies`）
+    pub cookies: Option<String>,
+    /// cookies.txt（`--cookies-file`）
+    pub cookies_file: Option<PathBuf>,
+    /// Cookieを使用しない（`--no-cookies`）
+    pub no_cookies: Option<bool>,
+    /// 帯域制限（`--limit-rate`）
+    pub limit_rate: Option<String>,
+    /// yt-dlpに渡す環境変数（`--ytdlp-env`、例: `env = { ALL_PROXY = "socks5://127.0.0.1:1080" }`）
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// リトライ回数（`--retry`）
+    pub retry: Option<usize>,
+    /// 字幕も保存（`--subtitle`）
+    pub subtitle: Option<bool>,
+    /// 説明文・メタデータも保存（`--metadata`）
+    pub metadata: Option<bool>,
+    /// タイトル・説明文の言語（`--metadata-lang`）
+    pub metadata_lang: Option<String>,
+    /// ダウンロード済みアーカイブファイル（`--download-archive`）
+    pub download_archive: Option<PathBuf>,
+    /// ダウンロードを許可するチャンネル（`--own-channel`）
+    pub own_channel: Option<Vec<String>>,
+    /// ファイル名に使う文字の制限（`--filename-policy`）
+    pub filename_policy: Option<FilenamePolicy>,
+    /// バッテリー駆動中・従量制接続の間は一時停止（`--respect-metered`）
+    pub respect_metered: Option<bool>,
+    /// 出力先の振り分けルール（`--route`、例: `routes = ["duration > 60m -> Lectures/"]`）
+    pub routes: Option<Vec<RouteRule>>,
+}
+
+impl Defaults {
+    /// 指定されていないオプションに設定値を反映（反映したものは指定済みとして記録）
+    fn apply(&self, cli: &mut Cli, specified: &mut SpecifiedArgs) {
+        if let Some(value) = &self.quality {
+            if specified.insert("quality") {
+                cli.quality = value.clone();
+            }
+        }
+
+        if let Some(value) = self.resolution {
+            if specified.insert("resolution") {
+                cli.resolution = Some(value);
+            }
+        }
+
+        if let Some(value) = self.vcodec {
+            if specified.insert("vcodec") {
+                cli.vcodec = Some(value);
+            }
+        }
+
+        if let Some(value) = self.audio_format {
+            if specified.insert("audio_format") {
+                cli.audio_format = Some(value);
+            }
+        }
+
+        if let Some(value) = self.container {
+            if specified.insert("container") {
+                cli.container = Some(value);
+            }
+        }
+
+        if let Some(value) = &self.output {
+            if specified.insert("output_dir") {
+                cli.output_dir = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = &self.output_template {
+            if specified.insert("output_template") {
+                cli.output_template = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = &self.cookies {
+            if specified.insert("cookie_browser") {
+                cli.cookie_browser = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = &self.cookies_file {
+            if specified.insert("cookies_file") {
+                cli.cookies_file = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = self.no_cookies {
+            if specified.insert("no_cookies") {
+                cli.no_cookies = value;
+            }
+        }
+
+        if let Some(value) = self.respect_metered {
+            if specified.insert("respect_metered") {
+                cli.respect_metered = value;
+            }
+        }
+
+        if let Some(value) = &self.routes {
+            if specified.insert("routes") {
+                cli.routes = value.clone();
+            }
+        }
+
+        if let Some(value) = &self.limit_rate {
+            if specified.insert("rate_limit") {
+                cli.rate_limit = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = self.retry {
+            if specified.insert("retry_count") {
+                cli.retry_count = value;
+            }
+        }
+
+        if let Some(value) = self.subtitle {
+            if specified.insert("download_subtitle") {
+                cli.download_subtitle = value;
+            }
+        }
+
+        if let Some(value) = self.metadata {
+            if specified.insert("save_metadata") {
+                cli.save_metadata = value;
+            }
+        }
+
+        if let Some(value) = &self.metadata_lang {
+            if specified.insert("metadata_lang") {
+                cli.metadata_lang = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = &self.download_archive {
+            if specified.insert("download_archive") {
+                cli.download_archive = Some(value.clone());
+            }
+        }
+
+        if let Some(value) = &self.own_channel {
+            if specified.insert("own_channels") {
+                cli.own_channels = value.clone();
+            }
+        }
+
+        // 環境変数は名前ごとに、指定済みでないものを追加する
+        for (key, value) in &self.env {
+            if !cli.ytdlp_env.iter().any(|(existing, _)| existing == key) {
+                cli.ytdlp_env.push((key.clone(), value.clone()));
+            }
+        }
+
+        if let Some(value) = self.filename_policy {
+            // --transliterate も同じ設定の指定とみなす
+            if !specified.contains("transliterate") && specified.insert("filename_policy") {
+                cli.filename_policy = Some(value);
+            }
+        }
+    }
+}
+
+impl Config {
+    /// 設定ファイルを読み込む（存在しない場合はデフォルト）
+    pub fn load() -> Result<Self> {
+        Self::load_from(&paths::config_file()?)
+    }
+
+    /// 指定したパスから設定ファイルを読み込む
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| YtdlError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// 表示する言語を設定ファイルに保存（保存したファイルを返す）
+    pub fn save_lang(lang: Lang) -> Result<PathBuf> {
+        let path = paths::config_file()?;
+        Self::save_lang_to(&path, lang)?;
+        Ok(path)
+    }
+
+    /// 指定したパスの設定ファイルの先頭に `lang` を追加（他の設定はそのまま残す）
+    ///
+    /// `lang` がまだ設定されていない場合に使います。
+    pub fn save_lang_to(path: &Path, lang: Lang) -> Result<()> {
+        let content = if path.exists() {
+            std::fs::read_to_string(path)?
+        } else {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            String::new()
+        };
+        std::fs::write(path, format!("lang = \"{}\"\n{}", lang.code(), content))?;
+        Ok(())
+    }
+
+    /// `--profile` で選択したプロファイルと `[defaults]` をダウンロードのオプションに反映
+    ///
+    /// 優先順位は コマンドライン引数・環境変数 > `[site.*]` > プロファイル > `[defaults]` です。
+    /// サイトごとの設定はURLごとに適用するため、ここではコマンドラインで指定したオプションを除いて渡します。
+    pub fn apply_defaults(&self, cli: &mut Cli, specified: &mut SpecifiedArgs) -> Result<()> {
+        cli.sites = self
+            .site
+            .iter()
+            .map(|(host, site)| (host.clone(), site.without_specified(specified)))
+            .collect();
+        if let Some(name) = &cli.profile {
+            let profile = self.profiles.get(name).ok_or_else(|| {
+                let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                YtdlError::Config(t!(
+                    "プロファイル '{}' が設定ファイルにありません（定義済み: {}）",
+                    name,
+                    if names.is_empty() { t!("なし").to_string() } else { names.join(", ") }
+                ))
+            })?;
+            profile.apply(cli, specified);
+        }
+        self.defaults.apply(cli, specified);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [defaults]
+            quality = "min-size"
+            retry = 5
+            limit_rate = "2M"
+            resolution = 1080
+
+            filename_policy = "windows-safe"
+            env = { ALL_PROXY = "socks5://proxy:1080", TMPDIR = "/tmp/ytdl" }
+
+            [profiles.music]
+            quality = "max-audio"
+            output = "Music"
+            env = { TMPDIR = "/mnt/scratch" }
+            "#,
+        )
+        .unwrap();
+
+        let mut cli = Cli {
+            quality: "max-video".to_string(),
+            retry_count: 3,
+            profile: Some("music".to_string()),
+            ytdlp_env: vec![("ALL_PROXY".to_string(), String::new())],
+            ..Default::default()
+        };
+        let mut specified = SpecifiedArgs::default();
+        specified.insert("retry_count");
+        config.apply_defaults(&mut cli, &mut specified).unwrap();
+
+        // プロファイル > [defaults]、コマンドラインで指定したものはそのまま
+        assert_eq!(cli.quality, "max-audio");
+        assert_eq!(cli.output_dir, Some(PathBuf::from("Music")));
+        assert_eq!(cli.retry_count, 3);
+        assert_eq!(cli.rate_limit.as_deref(), Some("2M"));
+        assert_eq!(cli.resolution, Some(Resolution::P1080));
+        assert_eq!(cli.filename_policy, Some(FilenamePolicy::WindowsSafe));
+        assert_eq!(
+            cli.ytdlp_env,
+            vec![
+                ("ALL_PROXY".to_string(), String::new()),
+                ("TMPDIR".to_string(), "/mnt/scratch".to_string()),
+            ]
+        );
+
+        cli.profile = Some("video".to_string());
+        assert!(config.apply_defaults(&mut cli, &mut specified).is_err());
+    }
+
+    #[test]
+    fn test_apply_respect_metered() {
+        let config: Config = toml::from_str(
+            r#"
+            [defaults]
+            respect_metered = true
+
+            [profiles.desk]
+            respect_metered = false
+            "#,
+        )
+        .unwrap();
+
+        let mut cli = Cli::default();
+        config.apply_defaults(&mut cli, &mut SpecifiedArgs::default()).unwrap();
+        assert!(cli.respect_metered);
+
+        // プロファイル > [defaults]
+        let mut cli = Cli {
+            profile: Some("desk".to_string()),
+            ..Default::default()
+        };
+        config.apply_defaults(&mut cli, &mut SpecifiedArgs::default()).unwrap();
+        assert!(!cli.respect_metered);
+
+        // コマンドラインで指定したものはそのまま
+        let mut cli = Cli::default();
+        let mut specified = SpecifiedArgs::default();
+        specified.insert("respect_metered");
+        config.apply_defaults(&mut cli, &mut specified).unwrap();
+        assert!(!cli.respect_metered);
+    }
+
+    #[test]
+    fn test_apply_routes() {
+        let path = std::env::temp_dir().join(format!("ytdl-config-routes-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+            [defaults]
+            routes = ["duration > 60m -> Lectures/", "uploader == Some Band -> Music/"]
+
+            [profiles.podcast]
+            routes = ["title ~= ep -> Podcasts/"]
+            "#,
+        )
+        .unwrap();
+        let config = Config::load_from(&path).unwrap();
+
+        let mut cli = Cli::default();
+        config.apply_defaults(&mut cli, &mut SpecifiedArgs::default()).unwrap();
+        let dirs: Vec<&Path> = cli.routes.iter().map(|rule| rule.dir.as_path()).collect();
+        assert_eq!(dirs, [Path::new("Lectures/"), Path::new("Music/")]);
+
+        // プロファイルのルールは [defaults] のルールを置き換える
+        let mut cli = Cli {
+            profile: Some("podcast".to_string()),
+            ..Default::default()
+        };
+        config.apply_defaults(&mut cli, &mut SpecifiedArgs::default()).unwrap();
+        assert_eq!(cli.routes.len(), 1);
+        assert_eq!(cli.routes[0].dir, PathBuf::from("Podcasts/"));
+
+        // --route を指定した場合は設定ファイルのルールを使わない
+        let mut cli = Cli {
+            routes: vec![RouteRule::parse("channel == X -> X/").unwrap()],
+            ..Default::default()
+        };
+        let mut specified = SpecifiedArgs::default();
+        specified.insert("routes");
+        config.apply_defaults(&mut cli, &mut specified).unwrap();
+        assert_eq!(cli.routes.len(), 1);
+        assert_eq!(cli.routes[0].dir, PathBuf::from("X/"));
+
+        // 不正なルールは読み込み時にエラー
+        std::fs::write(&path, "[defaults]\nroutes = [\"views > 100 -> Popular/\"]\n").unwrap();
+        assert!(Config::load_from(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_save_lang() {
+        let path = std::env::temp_dir().join(format!("ytdl-config-lang-{}.toml", std::process::id()));
+        std::fs::write(&path, "[defaults]\nquality = \"max-audio\"\n").unwrap();
+        Config::save_lang_to(&path, Lang::En).unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.lang, Some(Lang::En));
+        assert_eq!(config.defaults.quality.as_deref(), Some("max-audio"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
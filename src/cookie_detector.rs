@@ -1,7 +1,9 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, YtdlError};
+use crate::paths;
+use crate::t;
 
 /// サポートされているブラウザ
 #[derive(Debug, Clone)]
@@ -15,6 +17,7 @@ pub enum Browser {
 
 impl Browser {
     /// 文字列からブラウザを解析
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "chrome" => Some(Browser::Chrome),
@@ -41,23 +44,134 @@ impl Browser {
 /// Cookie検出器
 pub struct CookieDetector {
     browser: Browser,
+    /// yt-dlpに渡すキーリング指定（例: "basictext", "kwallet"）
+    keyring: Option<String>,
 }
 
 impl CookieDetector {
     /// 新しいCookie検出器を作成
     pub fn new(browser: Browser) -> Self {
-        Self { browser }
+        Self {
+            browser,
+            keyring: None,
+        }
     }
 
     /// 文字列からCookie検出器を作成
+    ///
+    /// yt-dlpと同じ "ブラウザ+キーリング" 形式（例: "chrome+basictext"）も受け付けます。
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(browser_name: &str) -> Result<Self> {
-        let browser = Browser::from_str(browser_name).ok_or_else(|| {
-            YtdlError::CookieDetection(format!(
+        let (name, keyring) = match browser_name.split_once('+') {
+            Some((name, keyring)) => (name, Some(keyring.to_string())),
+            None => (browser_name, None),
+        };
+        let browser = Browser::from_str(name).ok_or_else(|| {
+            YtdlError::CookieDetection(t!(
                 "サポートされていないブラウザ: {}",
                 browser_name
             ))
         })?;
-        Ok(Self::new(browser))
+        Ok(Self { browser, keyring })
+    }
+
+    /// 暗号化キー（キーチェーン/キーリング）へのアクセスを事前確認
+    ///
+    /// yt-dlpがキーチェーンの認証待ちで0%のまま止まったように見えるのを防ぐため、
+    /// 問題がありそうな場合は警告し、必要に応じて別のブラウザ指定に切り替えます。
+    /// 切り替えが必要な場合は新しいブラウザ指定を返します。
+    pub fn preflight_keyring(&self) -> Option<String> {
+        // FirefoxのCookieは暗号化されていない。キーリング指定済みならユーザーの指定を尊重
+        if matches!(self.browser, Browser::Firefox) || self.keyring.is_some() {
+            return None;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            self.preflight_macos_keychain()
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.preflight_linux_keyring()
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            // WindowsのDPAPIはユーザー操作なしで復号できる
+            None
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn preflight_macos_keychain(&self) -> Option<String> {
+        let service = match self.browser {
+            Browser::Chrome => "Chrome Safe Storage",
+            Browser::Edge => "Microsoft Edge Safe Storage",
+            Browser::Brave => "Brave Safe Storage",
+            Browser::Opera => "Opera Safe Storage",
+            Browser::Firefox => return None,
+        };
+
+        // -w を付けなければパスワードを読まないため、確認ダイアログは表示されない
+        let found = std::process::Command::new("security")
+            .args(["find-generic-password", "-s", service])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+
+        if found {
+            println!(
+                "{}",
+                t!("🔑 macOSのキーチェーン確認ダイアログ（\"{}\"）が表示される場合があります。", service)
+            );
+            println!("{}", t!("   「常に許可」を選択してください。0%のまま進まない場合はダイアログを確認してください。"));
+            None
+        } else {
+            eprintln!(
+                "{}",
+                t!("警告: キーチェーンに \"{}\" が見つかりません。{}のCookieを復号できない可能性があります", service, self.browser.name())
+            );
+            Self::firefox_fallback()
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn preflight_linux_keyring(&self) -> Option<String> {
+        // GNOME Keyring / KWallet はD-Busセッション経由でアクセスされる
+        let has_session_bus = env::var("DBUS_SESSION_BUS_ADDRESS")
+            .map(|v| !v.is_empty())
+            .unwrap_or(false);
+        if has_session_bus {
+            return None;
+        }
+
+        eprintln!(
+            "{}",
+            t!("警告: D-Busセッションが見つからないため、キーリングから{}のCookie復号キーを取得できません", self.browser.name())
+        );
+        Self::firefox_fallback().or_else(|| {
+            eprintln!("{}", t!("   キーリングを使わない復号（basictext）で続行します"));
+            Some(format!("{}+basictext", self.browser.name()))
+        })
+    }
+
+    /// FirefoxのCookieが利用可能ならFirefoxに切り替える
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn firefox_fallback() -> Option<String> {
+        let available = Self::new(Browser::Firefox)
+            .get_browser_cookie_path()
+            .map(|path| path.exists())
+            .unwrap_or(false);
+        if available {
+            eprintln!("{}", t!("   FirefoxのCookieに切り替えます（--cookies firefox）"));
+            Some(Browser::Firefox.name().to_string())
+        } else {
+            eprintln!("{}", t!("   ヒント: --cookies firefox を使用するとキーチェーンを経由しません"));
+            None
+        }
     }
 
     /// Cookieファイルのパスを検出
@@ -72,11 +186,10 @@ impl CookieDetector {
         } else {
             // Cookieファイルが見つからない場合は警告
             eprintln!(
-                "警告: {}のCookieファイルが見つかりません: {}",
-                self.browser.name(),
-                path.display()
+                "{}",
+                t!("警告: {}のCookieファイルが見つかりません: {}", self.browser.name(), path.display())
             );
-            eprintln!("公開動画のみダウンロード可能です。");
+            eprintln!("{}", t!("公開動画のみダウンロード可能です。"));
             Ok(None)
         }
     }
@@ -101,7 +214,7 @@ impl CookieDetector {
         #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
         {
             Err(YtdlError::CookieDetection(
-                "サポートされていないOSです".to_string(),
+                t!("サポートされていないOSです").to_string(),
             ))
         }
     }
@@ -109,7 +222,7 @@ impl CookieDetector {
     #[cfg(target_os = "windows")]
     fn get_windows_cookie_path(&self) -> Result<PathBuf> {
         let local_appdata = env::var("LOCALAPPDATA").map_err(|_| {
-            YtdlError::CookieDetection("LOCALAPPDATA環境変数が設定されていません".to_string())
+            YtdlError::CookieDetection(t!("LOCALAPPDATA環境変数が設定されていません").to_string())
         })?;
 
         let path = match self.browser {
@@ -119,7 +232,7 @@ impl CookieDetector {
             Browser::Firefox => {
                 // FirefoxはプロファイルがランダムなのでAppData\Roamingから探す必要がある
                 let appdata = env::var("APPDATA").map_err(|_| {
-                    YtdlError::CookieDetection("APPDATA環境変数が設定されていません".to_string())
+                    YtdlError::CookieDetection(t!("APPDATA環境変数が設定されていません").to_string())
                 })?;
                 PathBuf::from(appdata).join(r"Mozilla\Firefox\Profiles")
             }
@@ -141,7 +254,7 @@ impl CookieDetector {
     #[cfg(target_os = "macos")]
     fn get_macos_cookie_path(&self) -> Result<PathBuf> {
         let home = env::var("HOME")
-            .map_err(|_| YtdlError::CookieDetection("HOME環境変数が設定されていません".to_string()))?;
+            .map_err(|_| YtdlError::CookieDetection(t!("HOME環境変数が設定されていません").to_string()))?;
 
         let path = match self.browser {
             Browser::Chrome => PathBuf::from(home)
@@ -163,7 +276,7 @@ impl CookieDetector {
     #[cfg(target_os = "linux")]
     fn get_linux_cookie_path(&self) -> Result<PathBuf> {
         let home = env::var("HOME")
-            .map_err(|_| YtdlError::CookieDetection("HOME環境変数が設定されていません".to_string()))?;
+            .map_err(|_| YtdlError::CookieDetection(t!("HOME環境変数が設定されていません").to_string()))?;
 
         let path = match self.browser {
             Browser::Chrome => PathBuf::from(home).join(".config/google-chrome/Default/Cookies"),
@@ -178,12 +291,220 @@ impl CookieDetector {
         Ok(path)
     }
 
+    /// Cookieデータベースを一時ディレクトリにコピーしたスナップショットを作成
+    ///
+    /// 長時間のプレイリストダウンロード中にブラウザのファイルロックと
+    /// 何度も競合しないよう、起動時に一度だけコピーします。
+    pub fn snapshot(&self) -> Result<CookieSnapshot> {
+        let source = self.get_browser_cookie_path()?;
+        let dir = env::temp_dir().join(format!("ytdl-cookies-{}", std::process::id()));
+        create_private_dir(&dir)?;
+        let snapshot = CookieSnapshot {
+            browser: self.browser.clone(),
+            keyring: self.keyring.clone(),
+            dir,
+        };
+
+        match self.browser {
+            Browser::Firefox => {
+                // Firefoxはプロファイルディレクトリ内の最新の cookies.sqlite を使用
+                let db = find_newest_file(&source, "cookies.sqlite").ok_or_else(|| {
+                    YtdlError::CookieDetection(t!(
+                        "Firefoxの cookies.sqlite が見つかりません: {}",
+                        source.display()
+                    ))
+                })?;
+                copy_locked_file(&db, &snapshot.dir.join("cookies.sqlite"))?;
+            }
+            _ => {
+                // Chromium系: <User Data>/<Profile>/[Network/]Cookies の構成を再現する
+                // （暗号化キーの Local State は yt-dlp がプロファイルの親ディレクトリから読む）
+                let parent = source.parent().ok_or_else(|| {
+                    YtdlError::CookieDetection(t!("不正なCookieパス: {}", source.display()))
+                })?;
+                let (profile, relative) = if parent.file_name().is_some_and(|n| n == "Network") {
+                    (parent.parent().unwrap_or(parent), PathBuf::from("Network").join("Cookies"))
+                } else {
+                    (parent, PathBuf::from("Cookies"))
+                };
+
+                let target = snapshot.profile_dir().join(&relative);
+                if let Some(target_parent) = target.parent() {
+                    std::fs::create_dir_all(target_parent)?;
+                }
+                copy_locked_file(&source, &target)?;
+
+                // Local State（プロファイル直下にあるブラウザもある）
+                for (from, to) in [
+                    (profile.parent(), snapshot.dir.clone()),
+                    (Some(profile), snapshot.profile_dir()),
+                ] {
+                    if let Some(local_state) = from.map(|d| d.join("Local State")) {
+                        if local_state.exists() {
+                            std::fs::copy(&local_state, to.join("Local State"))?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(snapshot)
+    }
+
     /// yt-dlp用のブラウザ指定文字列を取得
     ///
     /// yt-dlpは `--cookies-from-browser chrome` のような形式でブラウザを指定します。
     /// これにより、yt-dlpが自動的にCookieの暗号化を解除してくれます。
     pub fn get_ytdlp_browser_arg(&self) -> String {
-        self.browser.name().to_string()
+        match &self.keyring {
+            Some(keyring) => format!("{}+{}", self.browser.name(), keyring),
+            None => self.browser.name().to_string(),
+        }
+    }
+}
+
+/// 一時ディレクトリにコピーしたCookieデータベース
+///
+/// ドロップ時に一時ディレクトリごと削除されます。
+pub struct CookieSnapshot {
+    browser: Browser,
+    keyring: Option<String>,
+    dir: PathBuf,
+}
+
+impl CookieSnapshot {
+    /// スナップショット内のプロファイルディレクトリ
+    fn profile_dir(&self) -> PathBuf {
+        match self.browser {
+            Browser::Firefox => self.dir.clone(),
+            _ => self.dir.join("Default"),
+        }
+    }
+
+    /// yt-dlp用のブラウザ指定文字列（"chrome:<プロファイルパス>" 形式）
+    pub fn get_ytdlp_browser_arg(&self) -> String {
+        let browser = match &self.keyring {
+            Some(keyring) => format!("{}+{}", self.browser.name(), keyring),
+            None => self.browser.name().to_string(),
+        };
+        format!("{}:{}", browser, self.profile_dir().display())
+    }
+}
+
+impl Drop for CookieSnapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// リモートマシンからcookies.txtをSCPで取得し、データディレクトリに保存
+///
+/// `remote` は "user@host"（リモートのホームディレクトリの cookies.txt）または
+/// "user@host:path/to/cookies.txt" 形式で指定します。
+/// 保存したファイルは以降の実行で自動的に使用されます。
+pub fn pull_remote_cookies(remote: &str) -> Result<PathBuf> {
+    let source = if remote.contains(':') {
+        remote.to_string()
+    } else {
+        format!("{}:cookies.txt", remote)
+    };
+
+    let target = paths::stored_cookies_file()?;
+    if let Some(dir) = target.parent() {
+        create_private_dir(dir)?;
+    }
+    let partial = target.with_extension("txt.part");
+
+    // パスワード入力などのため、標準入出力はそのまま引き継ぐ
+    let status = std::process::Command::new("scp")
+        .arg("-q")
+        .arg(&source)
+        .arg(&partial)
+        .status()
+        .map_err(|e| YtdlError::CookieDetection(t!("scpを実行できません: {}", e)))?;
+
+    if !status.success() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(YtdlError::CookieDetection(t!(
+            "{} からcookies.txtを取得できませんでした",
+            source
+        )));
+    }
+
+    // Netscape形式のCookieファイルか確認（警告のみ）
+    let content = std::fs::read_to_string(&partial).unwrap_or_default();
+    if !content.lines().next().unwrap_or("").contains("HTTP Cookie File") {
+        eprintln!("{}", t!("警告: 取得したファイルがNetscape形式のcookies.txtではない可能性があります"));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&partial, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&partial, &target)?;
+
+    Ok(target)
+}
+
+/// 他のユーザーから読めない一時ディレクトリを作成
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)?;
+    Ok(())
+}
+
+/// ディレクトリ直下（1階層下まで）から最も新しいファイルを探す
+fn find_newest_file(root: &Path, file_name: &str) -> Option<PathBuf> {
+    let mut candidates = vec![root.join(file_name)];
+    if let Ok(entries) = std::fs::read_dir(root) {
+        candidates.extend(entries.flatten().map(|e| e.path().join(file_name)));
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// ファイルをコピー（ロック中の場合、Windowsではボリュームシャドウコピーを試す）
+fn copy_locked_file(from: &Path, to: &Path) -> Result<()> {
+    match std::fs::copy(from, to) {
+        Ok(_) => Ok(()),
+        #[cfg(target_os = "windows")]
+        Err(e) => {
+            // esentutl の /vss はブラウザが起動中でもロックされたファイルを読める（管理者権限が必要）
+            let status = std::process::Command::new("esentutl.exe")
+                .arg("/y")
+                .arg(from)
+                .arg("/vss")
+                .arg("/d")
+                .arg(to)
+                .stdout(std::process::Stdio::null())
+                .status();
+            match status {
+                Ok(status) if status.success() => Ok(()),
+                _ => Err(YtdlError::CookieDetection(t!(
+                    "Cookieデータベースをコピーできません（ブラウザを終了するか、管理者として実行してください）: {}",
+                    e
+                ))),
+            }
+        }
+        #[cfg(not(target_os = "windows"))]
+        Err(e) => Err(YtdlError::CookieDetection(t!(
+            "Cookieデータベースをコピーできません: {}",
+            e
+        ))),
     }
 }
 
@@ -212,4 +533,30 @@ mod tests {
         let detector = CookieDetector::from_str("invalid");
         assert!(detector.is_err());
     }
+
+    #[test]
+    fn test_browser_arg_with_keyring() {
+        let detector = CookieDetector::from_str("chrome+basictext").unwrap();
+        assert_eq!(detector.get_ytdlp_browser_arg(), "chrome+basictext");
+
+        let detector = CookieDetector::from_str("firefox").unwrap();
+        assert_eq!(detector.get_ytdlp_browser_arg(), "firefox");
+        assert!(detector.preflight_keyring().is_none());
+    }
+
+    #[test]
+    fn test_find_newest_file() {
+        let root = env::temp_dir().join(format!("ytdl-test-newest-{}", std::process::id()));
+        let profile = root.join("abcd.default-release");
+        std::fs::create_dir_all(&profile).unwrap();
+        std::fs::write(profile.join("cookies.sqlite"), b"").unwrap();
+
+        assert_eq!(
+            find_newest_file(&root, "cookies.sqlite"),
+            Some(profile.join("cookies.sqlite"))
+        );
+        assert_eq!(find_newest_file(&root, "missing.sqlite"), None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
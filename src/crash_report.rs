@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::SystemTime;
+
+use crate::error::Result;
+use crate::paths;
+use crate::redact;
+use crate::t;
+use crate::updater::Updater;
+
+/// 不具合報告に含める出力の行数
+pub const BUFFER_LINES: usize = 200;
+
+/// 最後の数行だけを残す出力のバッファ（`--verbose` なしでも失敗時の出力を残す）
+#[derive(Debug)]
+pub struct OutputBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl OutputBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// 1行追加（容量を超えたら古い行を捨てる。トークンなどは伏せる）
+    pub fn push(&mut self, line: &str) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(redact::redact_line(line.trim_end()));
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+}
+
+/// 予期しない失敗の不具合報告（"crash-report-<UNIX時間>.txt"）を保存
+///
+/// 環境（バージョン・OS）、実行したコマンド（Cookieのパスなどは伏せる）、
+/// yt-dlpの最後の出力を含めます。保存したファイルを返します。
+pub fn write(
+    cmd: &Command,
+    exit_status: &str,
+    stdout: &OutputBuffer,
+    stderr: &OutputBuffer,
+) -> Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let mut report = String::new();
+    let _ = writeln!(report, "{}", t!("=== 環境 ==="));
+    let _ = writeln!(report, "ytdl: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        report,
+        "yt-dlp: {}",
+        Updater::show_version().unwrap_or_else(|e| t!("不明（{}）", e))
+    );
+    let _ = writeln!(report, "OS: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(report, "{}", t!("日時: {}（UNIX時間）", timestamp));
+    let _ = writeln!(report, "{}", t!("終了状態: {}", exit_status));
+    let _ = writeln!(report, "{}", t!("\n=== コマンド ===\n{}", redact::command_line(cmd)));
+    for (name, buffer) in [("stdout", stdout), ("stderr", stderr)] {
+        let _ = writeln!(report, "{}", t!("\n=== yt-dlpの{}（最後の{}行まで） ===", name, BUFFER_LINES));
+        for line in buffer.lines() {
+            let _ = writeln!(report, "{}", line);
+        }
+    }
+
+    let dir = paths::data_dir()?.join("crash-reports");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("crash-report-{}.txt", timestamp));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_buffer() {
+        let mut buffer = OutputBuffer::new(2);
+        for line in ["a\n", "b\n", "c\n"] {
+            buffer.push(line);
+        }
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["b", "c"]);
+    }
+}
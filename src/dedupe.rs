@@ -0,0 +1,260 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, YtdlError};
+use crate::i18n;
+use crate::paths;
+use crate::t;
+
+/// 重複ファイルの処理方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupeAction {
+    /// 重複をハードリンクに置き換える（容量だけ節約し、どちらのパスも残す）
+    HardLink,
+    /// 重複を削除する
+    Delete,
+    /// 何もしない
+    Keep,
+}
+
+/// ダウンロードしたファイルのハッシュの記録（`--record-hashes`）
+///
+/// 1行に `SHA-256<TAB>パス` を記録します。`ytdl dedupe` で指定したフォルダの外にある
+/// ファイルとも比較できるよう、重複の検出に使います。
+pub struct HashHistory {
+    path: PathBuf,
+}
+
+impl HashHistory {
+    /// データディレクトリの記録ファイルを使用
+    pub fn open() -> Result<Self> {
+        Ok(Self {
+            path: paths::data_dir()?.join("file-hashes.txt"),
+        })
+    }
+
+    /// ファイルのハッシュを計算して記録
+    pub fn record(&self, file: &Path) -> Result<()> {
+        let hash = hash_file(file)?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let path = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        let mut history = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(history, "{}\t{}", hash, path.display())?;
+        Ok(())
+    }
+
+    /// 記録済みのファイルのうち、現在も存在するもの
+    fn existing_files(&self) -> Vec<PathBuf> {
+        let Ok(file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = BufReader::new(file)
+            .lines()
+            .map_while(std::result::Result::ok)
+            .filter_map(|line| line.split_once('\t').map(|(_, path)| PathBuf::from(path)))
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+/// ファイルのSHA-256（16進数）
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// フォルダ内（と記録済みのファイル）から、内容が同一のファイルの組を探す
+///
+/// 各組はフォルダ外のファイル → 更新日時の古い順に並び、先頭を元のファイルとして扱います。
+/// すでにハードリンクになっているファイルは重複として扱いません。
+pub fn find_duplicates(dir: &Path, extra_files: &[PathBuf]) -> Result<Vec<Vec<PathBuf>>> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    for file in extra_files {
+        if !files.iter().any(|known| same_file(known, file)) {
+            files.push(file.clone());
+        }
+    }
+
+    // サイズが同じファイルだけハッシュを計算する
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for file in files {
+        if let Ok(metadata) = std::fs::metadata(&file) {
+            if metadata.len() > 0 {
+                by_size.entry(metadata.len()).or_default().push(file);
+            }
+        }
+    }
+
+    let mut groups = Vec::new();
+    for candidates in by_size.into_values().filter(|files| files.len() > 1) {
+        let mut by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for file in candidates {
+            let hash = hash_file(&file)?;
+            let group = by_hash.entry(hash).or_default();
+            if !group.iter().any(|known| same_file(known, &file)) {
+                group.push(file);
+            }
+        }
+        groups.extend(by_hash.into_values().filter(|group| group.len() > 1));
+    }
+
+    for group in &mut groups {
+        group.sort_by_key(|file| {
+            let modified = std::fs::metadata(file)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            (file.starts_with(dir), modified)
+        });
+    }
+    groups.sort();
+    Ok(groups)
+}
+
+/// 重複ファイルを探して処理する（`ytdl dedupe`）
+///
+/// `choose` は重複の組ごとに処理方法を選びます（非対話モードでは常に [`DedupeAction::Keep`]）。
+pub fn run(dir: &Path, mut choose: impl FnMut(&[PathBuf]) -> DedupeAction) -> Result<()> {
+    println!("{}", t!("🔍 {} の重複ファイルを確認中...", dir.display()));
+    let extra_files = HashHistory::open()
+        .map(|history| history.existing_files())
+        .unwrap_or_default();
+    let groups = find_duplicates(dir, &extra_files)?;
+    if groups.is_empty() {
+        println!("{}", t!("重複ファイルはありません"));
+        return Ok(());
+    }
+
+    let mut saved = 0;
+    for group in &groups {
+        let (original, duplicates) = group.split_first().expect("重複の組は2件以上");
+        println!("\n📄 {}", original.display());
+        for duplicate in duplicates {
+            println!("   = {}", duplicate.display());
+        }
+
+        let action = choose(group);
+        // 変更するのは指定したフォルダ内のファイルのみ
+        for duplicate in duplicates.iter().filter(|file| file.starts_with(dir)) {
+            let size = std::fs::metadata(duplicate).map(|m| m.len()).unwrap_or(0);
+            let result = match action {
+                DedupeAction::HardLink => replace_with_hard_link(original, duplicate),
+                DedupeAction::Delete => std::fs::remove_file(duplicate).map_err(YtdlError::from),
+                DedupeAction::Keep => continue,
+            };
+            match result {
+                Ok(()) => saved += size,
+                Err(e) => eprintln!("❌ {}: {}", duplicate.display(), e),
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        t!("\n重複: {}組 / 削減: {}", i18n::number(groups.len()), crate::progress_parser::format_bytes(saved))
+    );
+    Ok(())
+}
+
+/// 重複ファイルを元のファイルへのハードリンクに置き換える
+fn replace_with_hard_link(original: &Path, duplicate: &Path) -> Result<()> {
+    let file_name = duplicate
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = duplicate.with_file_name(format!(".dedupe-{}", file_name));
+
+    // 同じファイルシステムでない場合はここで失敗し、元のファイルは残る
+    std::fs::hard_link(original, &temp).map_err(|e| {
+        YtdlError::Other(t!("ハードリンクを作成できません（別のドライブの可能性）: {}", e))
+    })?;
+    std::fs::rename(&temp, duplicate)?;
+    Ok(())
+}
+
+/// フォルダ内のファイルを再帰的に集める（隠しファイルは除く）
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if hidden || file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_files(&path, files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 同じファイル（同じパス、または同じ実体へのハードリンク）か
+fn same_file(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let (Ok(a), Ok(b)) = (std::fs::metadata(a), std::fs::metadata(b)) {
+            return a.dev() == b.dev() && a.ino() == b.ino();
+        }
+    }
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicates() {
+        let root = std::env::temp_dir().join(format!("ytdl-test-dedupe-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+        std::fs::write(root.join("a/video.mp4"), b"same content").unwrap();
+        std::fs::write(root.join("b/Video-abc.mp4"), b"same content").unwrap();
+        std::fs::write(root.join("b/other.mp4"), b"diff content").unwrap();
+        std::fs::write(root.join("b/.hidden.mp4"), b"same content").unwrap();
+
+        let groups = find_duplicates(&root, &[]).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+
+        // ハードリンク済みのものは重複として扱わない
+        replace_with_hard_link(&groups[0][0], &groups[0][1]).unwrap();
+        assert!(find_duplicates(&root, &[]).unwrap().is_empty());
+        assert_eq!(std::fs::read(root.join("b/Video-abc.mp4")).unwrap(), b"same content");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let path = std::env::temp_dir().join(format!("ytdl-test-hash-{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+
+/// Dockのアイコンのバッジへの進捗表示（macOS、`--dock-badge`）
+///
+/// Finderから起動した（.appとして起動した）場合にDockのアイコンに "42%" のように進捗を表示します。
+/// AppKitはメインスレッドからのみ操作できるため、他のスレッド（並列ダウンロード）からの更新は無視します。
+pub struct DockBadge {
+    /// 最後に表示したバッジ（同じ内容では更新しない）
+    label: Mutex<String>,
+}
+
+impl DockBadge {
+    /// バッジの表示を開始（macOS以外ではNone）
+    pub fn new() -> Option<Self> {
+        cfg!(target_os = "macos").then(|| Self {
+            label: Mutex::new(String::new()),
+        })
+    }
+
+    /// 進捗を表示
+    pub fn set_progress(&self, percent: f64) {
+        self.set(&format!("{:.0}%", percent.clamp(0.0, 100.0)));
+    }
+
+    /// 失敗を表示
+    pub fn set_error(&self) {
+        self.set("!");
+    }
+
+    /// バッジを消す
+    pub fn clear(&self) {
+        self.set("");
+    }
+
+    fn set(&self, label: &str) {
+        let Ok(mut current) = self.label.lock() else {
+            return;
+        };
+        if *current == label {
+            return;
+        }
+        #[cfg(target_os = "macos")]
+        if !macos::set_badge_label(label) {
+            return;
+        }
+        *current = label.to_string();
+    }
+}
+
+impl Drop for DockBadge {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void, CString};
+
+    type Id = *mut c_void;
+    type Sel = *const c_void;
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Sel;
+        fn objc_msgSend();
+    }
+
+    extern "C" {
+        fn pthread_main_np() -> i32;
+    }
+
+    /// `[[NSApp dockTile] setBadgeLabel:label]`（メインスレッド以外ではfalse）
+    pub fn set_badge_label(label: &str) -> bool {
+        let Ok(label) = CString::new(label) else {
+            return false;
+        };
+        // SAFETY: メインスレッドで、各メソッドの引数・戻り値の型に合わせた関数ポインタとして呼び出す
+        unsafe {
+            if pthread_main_np() == 0 {
+                return false;
+            }
+            let send: unsafe extern "C" fn(Id, Sel) -> Id = std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let send_id: unsafe extern "C" fn(Id, Sel, Id) = std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let send_str: unsafe extern "C" fn(Id, Sel, *const c_char) -> Id =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+            let app = send(objc_getClass(c"NSApplication".as_ptr()), sel_registerName(c"sharedApplication".as_ptr()));
+            let tile = send(app, sel_registerName(c"dockTile".as_ptr()));
+            if tile.is_null() {
+                return false;
+            }
+            let string = send_str(
+                objc_getClass(c"NSString".as_ptr()),
+                sel_registerName(c"stringWithUTF8String:".as_ptr()),
+                label.as_ptr(),
+            );
+            send_id(tile, sel_registerName(c"setBadgeLabel:".as_ptr()), string);
+            send(tile, sel_registerName(c"display".as_ptr()));
+        }
+        true
+    }
+}
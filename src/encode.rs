@@ -0,0 +1,427 @@
+use clap::ValueEnum;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// 変換したコピーのプリセット（`--also-encode`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EncodePreset {
+    /// H.264 720p（タブレット向け）
+    #[value(name = "h264-720p")]
+    H264_720p,
+    /// H.264 480p（スマートフォン向け・容量優先）
+    #[value(name = "h264-480p")]
+    H264_480p,
+    /// H.265 元の解像度（画質を保って容量を削減）
+    Hevc,
+}
+
+impl EncodePreset {
+    /// 説明文
+    pub fn description(&self) -> &str {
+        match self {
+            EncodePreset::H264_720p => "H.264 720p",
+            EncodePreset::H264_480p => "H.264 480p",
+            EncodePreset::Hevc => "H.265",
+        }
+    }
+
+    /// 変換後のファイル名に付ける名前
+    fn suffix(&self) -> &str {
+        match self {
+            EncodePreset::H264_720p => "720p",
+            EncodePreset::H264_480p => "480p",
+            EncodePreset::Hevc => "hevc",
+        }
+    }
+
+    /// 映像コーデック
+    pub fn codec(&self) -> VideoCodec {
+        match self {
+            EncodePreset::H264_720p | EncodePreset::H264_480p => VideoCodec::H264,
+            EncodePreset::Hevc => VideoCodec::Hevc,
+        }
+    }
+
+    /// 最大の高さ（元の解像度より大きくはしない。Noneなら元の解像度）
+    fn max_height(&self) -> Option<u32> {
+        match self {
+            EncodePreset::H264_720p => Some(720),
+            EncodePreset::H264_480p => Some(480),
+            EncodePreset::Hevc => None,
+        }
+    }
+
+    /// 画質（x264/x265のCRF相当。小さいほど高画質）
+    fn quality(&self) -> u32 {
+        match self {
+            EncodePreset::H264_720p => 23,
+            EncodePreset::H264_480p | EncodePreset::Hevc => 26,
+        }
+    }
+
+    /// 音声のビットレート
+    fn audio_bitrate(&self) -> &str {
+        match self {
+            EncodePreset::H264_480p => "96k",
+            EncodePreset::H264_720p | EncodePreset::Hevc => "128k",
+        }
+    }
+
+    /// ffmpegの映像・音声のオプション
+    fn ffmpeg_args(&self, encoder: VideoEncoder) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(height) = self.max_height() {
+            args.extend(["-vf".to_string(), format!("scale=-2:'min({},ih)'", height)]);
+        }
+        args.extend(["-c:v".to_string(), encoder.name(self.codec()).to_string()]);
+        args.extend(encoder.quality_args(self.quality()));
+        if self.codec() == VideoCodec::Hevc {
+            // Appleの機器で再生できるようにする
+            args.extend(["-tag:v".to_string(), "hvc1".to_string()]);
+        }
+        args.extend(["-c:a", "aac", "-b:a", self.audio_bitrate()].map(String::from));
+        args
+    }
+
+    /// 変換後のパス（元のファイルと同じフォルダに "タイトル.720p.mp4"）
+    pub fn output_path(&self, source: &Path) -> PathBuf {
+        let stem = source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        source.with_file_name(format!("{}.{}.mp4", stem, self.suffix()))
+    }
+
+    /// ffmpegで変換したコピーを作成（ハードウェアエンコーダーで失敗した場合はソフトウェアでやり直す）
+    pub fn encode(&self, source: &Path, encoder: VideoEncoder) -> Result<PathBuf> {
+        match self.encode_with(source, encoder) {
+            Err(e) if encoder != VideoEncoder::Software => {
+                eprintln!(
+                    "{}",
+                    t!("警告: {}での変換に失敗したため、ソフトウェアで変換します: {}", encoder.description(), e)
+                );
+                self.encode_with(source, VideoEncoder::Software)
+            }
+            result => result,
+        }
+    }
+
+    fn encode_with(&self, source: &Path, encoder: VideoEncoder) -> Result<PathBuf> {
+        let output = self.output_path(source);
+        let file_name = output
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let temp = output.with_file_name(format!(".encoding-{}", file_name));
+
+        let status = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-nostdin", "-i"])
+            .arg(source)
+            .args(self.ffmpeg_args(encoder))
+            .args(["-movflags", "+faststart"])
+            .arg(&temp)
+            .status()
+            .map_err(|e| YtdlError::ProcessError(t!("ffmpegの起動失敗: {}", e)))?;
+
+        if !status.success() {
+            let _ = std::fs::remove_file(&temp);
+            return Err(YtdlError::ProcessError(t!(
+                "変換に失敗しました: {}",
+                source.display()
+            )));
+        }
+        std::fs::rename(&temp, &output)?;
+        Ok(output)
+    }
+}
+
+/// 映像コーデック
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    Hevc,
+}
+
+/// 映像のエンコーダー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoEncoder {
+    /// CPU（x264 / x265）
+    Software,
+    /// NVIDIA NVENC
+    Nvenc,
+    /// Intel Quick Sync Video
+    Qsv,
+    /// macOS VideoToolbox
+    VideoToolbox,
+}
+
+impl VideoEncoder {
+    /// 優先して使うハードウェアエンコーダーの順
+    const HARDWARE: [VideoEncoder; 3] = [
+        VideoEncoder::Nvenc,
+        VideoEncoder::Qsv,
+        VideoEncoder::VideoToolbox,
+    ];
+
+    /// 利用できるハードウェアエンコーダーを探す（なければソフトウェア）
+    ///
+    /// `ffmpeg -encoders` に含まれていても、GPUやドライバがなければ使えないため、
+    /// 短い映像を実際に変換して確認します。
+    pub fn detect(codec: VideoCodec) -> Self {
+        let Ok(output) = Command::new("ffmpeg")
+            .args(["-hide_banner", "-encoders"])
+            .output()
+        else {
+            return VideoEncoder::Software;
+        };
+        let available = parse_encoders(&String::from_utf8_lossy(&output.stdout));
+        Self::HARDWARE
+            .into_iter()
+            .filter(|encoder| available.iter().any(|name| name == encoder.name(codec)))
+            .find(|encoder| encoder.works(codec))
+            .unwrap_or(VideoEncoder::Software)
+    }
+
+    /// 表示名
+    pub fn description(&self) -> &str {
+        match self {
+            VideoEncoder::Software => t!("ソフトウェア"),
+            VideoEncoder::Nvenc => "NVENC",
+            VideoEncoder::Qsv => "Quick Sync Video",
+            VideoEncoder::VideoToolbox => "VideoToolbox",
+        }
+    }
+
+    /// ffmpegのエンコーダー名
+    fn name(&self, codec: VideoCodec) -> &str {
+        match (self, codec) {
+            (VideoEncoder::Software, VideoCodec::H264) => "libx264",
+            (VideoEncoder::Software, VideoCodec::Hevc) => "libx265",
+            (VideoEncoder::Nvenc, VideoCodec::H264) => "h264_nvenc",
+            (VideoEncoder::Nvenc, VideoCodec::Hevc) => "hevc_nvenc",
+            (VideoEncoder::Qsv, VideoCodec::H264) => "h264_qsv",
+            (VideoEncoder::Qsv, VideoCodec::Hevc) => "hevc_qsv",
+            (VideoEncoder::VideoToolbox, VideoCodec::H264) => "h264_videotoolbox",
+            (VideoEncoder::VideoToolbox, VideoCodec::Hevc) => "hevc_videotoolbox",
+        }
+    }
+
+    /// 画質の指定（`quality` はCRF相当の値）
+    fn quality_args(&self, quality: u32) -> Vec<String> {
+        let args = match self {
+            VideoEncoder::Software => vec!["-preset", "medium", "-crf"],
+            VideoEncoder::Nvenc => vec!["-preset", "p5", "-rc", "vbr", "-b:v", "0", "-cq"],
+            VideoEncoder::Qsv => vec!["-preset", "medium", "-global_quality"],
+            VideoEncoder::VideoToolbox => {
+                // VideoToolboxは1〜100（大きいほど高画質）
+                let q = 100u32.saturating_sub(quality * 3 / 2);
+                return vec!["-q:v".to_string(), q.to_string()];
+            }
+        };
+        let mut args: Vec<String> = args.into_iter().map(String::from).collect();
+        args.push(quality.to_string());
+        args
+    }
+
+    /// 短い映像を変換して使えるか確認
+    fn works(&self, codec: VideoCodec) -> bool {
+        Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-nostdin"])
+            .args(["-f", "lavfi", "-i", "color=size=256x256:duration=0.1"])
+            .args(["-c:v", self.name(codec), "-f", "null", "-"])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// `ffmpeg -encoders` の出力からエンコーダー名を取り出す
+fn parse_encoders(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(String::from)
+        .collect()
+}
+
+/// 変換の結果
+#[derive(Debug, Default)]
+pub struct EncodeResults {
+    /// 作成したファイル
+    pub created: Vec<PathBuf>,
+    /// 変換に失敗した元のファイルとエラー
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// 変換待ちのファイルと実行状況
+#[derive(Default)]
+struct QueueState {
+    pending: VecDeque<PathBuf>,
+    running: usize,
+    results: EncodeResults,
+    closed: bool,
+}
+
+/// 後処理（変換）のキュー
+///
+/// ダウンロードと並行して、最大 `jobs` 件ずつバックグラウンドで変換します。
+pub struct EncodeQueue {
+    state: Arc<(Mutex<QueueState>, Condvar)>,
+}
+
+impl EncodeQueue {
+    /// 変換用のスレッドを起動
+    pub fn new(jobs: usize, encoder: impl Fn(&Path) -> Result<PathBuf> + Send + Sync + 'static) -> Self {
+        let state = Arc::new((Mutex::new(QueueState::default()), Condvar::new()));
+        let encoder = Arc::new(encoder);
+        for _ in 0..jobs.max(1) {
+            let state = Arc::clone(&state);
+            let encoder = Arc::clone(&encoder);
+            std::thread::spawn(move || worker(&state, encoder.as_ref()));
+        }
+        Self { state }
+    }
+
+    /// 変換するファイルを追加
+    pub fn submit(&self, source: PathBuf) {
+        let (lock, condvar) = &*self.state;
+        if let Ok(mut state) = lock.lock() {
+            state.pending.push_back(source);
+            condvar.notify_all();
+        }
+    }
+
+    /// 追加したファイルの変換がすべて終わるまで待ち、前回以降の結果を返す
+    pub fn wait(&self) -> EncodeResults {
+        let (lock, condvar) = &*self.state;
+        let Ok(mut state) = lock.lock() else {
+            return EncodeResults::default();
+        };
+        while !state.pending.is_empty() || state.running > 0 {
+            state = match condvar.wait(state) {
+                Ok(state) => state,
+                Err(_) => return EncodeResults::default(),
+            };
+        }
+        std::mem::take(&mut state.results)
+    }
+}
+
+impl Drop for EncodeQueue {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.state;
+        if let Ok(mut state) = lock.lock() {
+            state.closed = true;
+            condvar.notify_all();
+        }
+    }
+}
+
+/// キューからファイルを取り出して変換する
+fn worker(state: &(Mutex<QueueState>, Condvar), encoder: &dyn Fn(&Path) -> Result<PathBuf>) {
+    let (lock, condvar) = state;
+    loop {
+        let source = {
+            let Ok(mut state) = lock.lock() else {
+                return;
+            };
+            loop {
+                if let Some(source) = state.pending.pop_front() {
+                    state.running += 1;
+                    break source;
+                }
+                if state.closed {
+                    return;
+                }
+                state = match condvar.wait(state) {
+                    Ok(state) => state,
+                    Err(_) => return,
+                };
+            }
+        };
+
+        let result = encoder(&source);
+
+        let Ok(mut state) = lock.lock() else {
+            return;
+        };
+        state.running -= 1;
+        match result {
+            Ok(output) => state.results.created.push(output),
+            Err(e) => state.results.failed.push((source, e.to_string())),
+        }
+        condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path() {
+        assert_eq!(
+            EncodePreset::H264_720p.output_path(Path::new("/dl/Video-abc.webm")),
+            PathBuf::from("/dl/Video-abc.720p.mp4")
+        );
+        assert_eq!(
+            EncodePreset::Hevc.output_path(Path::new("Vol. 2.mkv")),
+            PathBuf::from("Vol. 2.hevc.mp4")
+        );
+    }
+
+    #[test]
+    fn test_encoder_args() {
+        let args = EncodePreset::H264_720p.ffmpeg_args(VideoEncoder::Nvenc);
+        assert_eq!(args[..4], ["-vf", "scale=-2:'min(720,ih)'", "-c:v", "h264_nvenc"]);
+        assert!(args.ends_with(&["-cq", "23", "-c:a", "aac", "-b:a", "128k"].map(String::from)));
+
+        let args = EncodePreset::Hevc.ffmpeg_args(VideoEncoder::Software);
+        assert_eq!(args[..2], ["-c:v", "libx265"]);
+        assert!(args.contains(&"hvc1".to_string()));
+    }
+
+    #[test]
+    fn test_parse_encoders() {
+        let output = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ V....D h264_nvenc           NVIDIA NVENC H.264 encoder (codec h264)
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+        assert_eq!(parse_encoders(output), vec!["libx264", "h264_nvenc", "aac"]);
+    }
+
+    #[test]
+    fn test_queue_collects_results() {
+        let queue = EncodeQueue::new(2, |source: &Path| {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            if source.ends_with("bad.mp4") {
+                Err(YtdlError::Other("失敗".to_string()))
+            } else {
+                Ok(source.with_extension("720p.mp4"))
+            }
+        });
+        for name in ["a.mp4", "bad.mp4", "c.mp4"] {
+            queue.submit(PathBuf::from(name));
+        }
+
+        let mut results = queue.wait();
+        results.created.sort();
+        assert_eq!(
+            results.created,
+            vec![PathBuf::from("a.720p.mp4"), PathBuf::from("c.720p.mp4")]
+        );
+        assert_eq!(results.failed.len(), 1);
+        assert!(queue.wait().created.is_empty());
+    }
+}
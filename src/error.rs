@@ -4,25 +4,37 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 #[allow(dead_code)]
 pub enum YtdlError {
-    #[error("yt-dlpが見つかりません。Dockerコンテナ内で実行するか、yt-dlpをインストールしてください")]
+    #[error("{}", crate::t!("yt-dlpが見つかりません。Dockerコンテナ内で実行するか、yt-dlpをインストールしてください"))]
     YtdlpNotFound,
 
-    #[error("Cookie検出エラー: {0}")]
+    #[error("{}", crate::t!("Cookie検出エラー: {}", .0))]
     CookieDetection(String),
 
-    #[error("ダウンロードエラー: {0}")]
+    #[error("{}", crate::t!("ダウンロードエラー: {}", .0))]
     DownloadFailed(String),
 
-    #[error("yt-dlpプロセスエラー: {0}")]
+    #[error("{}", crate::t!("yt-dlpプロセスエラー: {}", .0))]
     ProcessError(String),
 
-    #[error("進捗パースエラー: {0}")]
+    #[error("{}", crate::t!("進捗パースエラー: {}", .0))]
     ProgressParseError(String),
 
-    #[error("IO エラー: {0}")]
+    #[error("{}", crate::t!("アップロードエラー: {}", .0))]
+    UploadFailed(String),
+
+    #[error("{}", crate::t!("設定ファイルエラー: {}", .0))]
+    Config(String),
+
+    #[error("{}", crate::t!("IO エラー: {}", .0))]
     IoError(#[from] std::io::Error),
 
-    #[error("その他のエラー: {0}")]
+    #[error("{}", crate::t!("ダウンロード履歴のエラー: {}", .0))]
+    History(#[from] rusqlite::Error),
+
+    #[error("{}", crate::t!("ダウンロードを中止しました（未処理: {}件）", crate::i18n::number(*.0)))]
+    Cancelled(usize),
+
+    #[error("{}", crate::t!("その他のエラー: {}", .0))]
     Other(String),
 }
 
@@ -0,0 +1,196 @@
+use serde::Deserialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::heatmap::HeatmapMarker;
+
+/// イベントファイルの一時ファイル名の連番
+static LOG_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// 動画ごとのイベントを書き出させるyt-dlpのテンプレート（WHEN:TEMPLATE）
+///
+/// `%(.{...})j` は指定したフィールドをJSONオブジェクトとして出力します（存在しないフィールドは省略）。
+const EVENT_TEMPLATES: &[&str] = &[
+    r#"video:{"event":"started","info":%(.{id,title,webpage_url,playlist_index,playlist_count})j}"#,
+    r#"after_move:{"event":"finished","info":%(.{id,title,webpage_url,filepath,heatmap})j}"#,
+];
+
+/// yt-dlpの処理イベント
+///
+/// 人が読むための出力（`[download] Destination: ...` など）はyt-dlpのバージョンで変わるため、
+/// ファイル名の取得や結果の集計にはこのイベントを使います。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum YtdlpEvent {
+    /// 動画の処理を開始
+    Started { info: ItemInfo },
+    /// 後処理とファイルの移動まで完了
+    Finished { info: ItemInfo },
+    /// エラー（stderrの "ERROR:" 行から作成）
+    #[serde(skip)]
+    Error { id: Option<String>, message: String },
+}
+
+/// イベントに含まれる動画の情報
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ItemInfo {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub webpage_url: Option<String>,
+    pub playlist_index: Option<u32>,
+    pub playlist_count: Option<u32>,
+    pub filepath: Option<PathBuf>,
+    /// 「最も再生された部分」（`--write-heatmap` で保存）
+    pub heatmap: Option<Vec<HeatmapMarker>>,
+}
+
+impl YtdlpEvent {
+    /// イベントファイルの1行をパース
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim()).ok()
+    }
+
+    /// stderrの "ERROR:" 行からエラーイベントを作成
+    ///
+    /// 例: `ERROR: [youtube] abc123: Video unavailable` -> id: "abc123", message: "Video unavailable"
+    pub fn from_error_line(line: &str) -> Option<Self> {
+        let message = line.trim().strip_prefix("ERROR:")?.trim();
+
+        // "[extractor] id: message" の形式なら動画IDを取り出す
+        if let Some((_, rest)) = message.strip_prefix('[').and_then(|m| m.split_once("] ")) {
+            if let Some((id, detail)) = rest.split_once(": ") {
+                if !id.is_empty() && !id.contains(char::is_whitespace) {
+                    return Some(YtdlpEvent::Error {
+                        id: Some(id.to_string()),
+                        message: detail.to_string(),
+                    });
+                }
+            }
+        }
+        Some(YtdlpEvent::Error {
+            id: None,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// yt-dlpにイベントを書き出させる一時ファイル（1行1JSON）
+///
+/// `--print-to-file` を使うため、`--print` と違って通常の出力（進捗など）は抑制されません。
+pub struct EventLog {
+    path: PathBuf,
+    /// 読み取り済みの位置
+    offset: u64,
+}
+
+impl EventLog {
+    /// 新しい一時ファイルのパスを用意
+    pub fn new() -> Self {
+        let n = LOG_COUNTER.fetch_add(1, Ordering::SeqCst);
+        Self {
+            path: std::env::temp_dir().join(format!("ytdl-events-{}-{}.jsonl", std::process::id(), n)),
+            offset: 0,
+        }
+    }
+
+    /// yt-dlpに渡すオプション
+    pub fn apply_args(&self, cmd: &mut Command) {
+        for template in EVENT_TEMPLATES {
+            cmd.arg("--print-to-file").arg(template).arg(&self.path);
+        }
+    }
+
+    /// 前回以降に書き出されたイベントを読み取る（書き込み途中の行は次回に回す）
+    pub fn read_new(&mut self) -> Vec<YtdlpEvent> {
+        let Ok(mut file) = std::fs::File::open(&self.path) else {
+            return Vec::new();
+        };
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+        let mut content = String::new();
+        if file.read_to_string(&mut content).is_err() {
+            return Vec::new();
+        }
+
+        let Some(end) = content.rfind('\n') else {
+            return Vec::new();
+        };
+        self.offset += end as u64 + 1;
+        content[..end].lines().filter_map(YtdlpEvent::parse).collect()
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let line = r#"{"event":"started","info":{"id":"abc","title":"Title","playlist_index":2,"playlist_count":5}}"#;
+        let YtdlpEvent::Started { info } = YtdlpEvent::parse(line).unwrap() else {
+            panic!("started以外のイベント");
+        };
+        assert_eq!(info.id.as_deref(), Some("abc"));
+        assert_eq!(info.playlist_index, Some(2));
+        assert_eq!(info.filepath, None);
+
+        let line = r#"{"event":"finished","info":{"id":"abc","filepath":"/tmp/a b.mp4"}}"#;
+        let YtdlpEvent::Finished { info } = YtdlpEvent::parse(line).unwrap() else {
+            panic!("finished以外のイベント");
+        };
+        assert_eq!(info.filepath, Some(PathBuf::from("/tmp/a b.mp4")));
+
+        assert_eq!(YtdlpEvent::parse("/tmp/a.mp4"), None);
+        assert_eq!(YtdlpEvent::parse(r#"{"event":"unknown"}"#), None);
+    }
+
+    #[test]
+    fn test_from_error_line() {
+        assert_eq!(
+            YtdlpEvent::from_error_line("ERROR: [youtube] abc123: Video unavailable"),
+            Some(YtdlpEvent::Error {
+                id: Some("abc123".to_string()),
+                message: "Video unavailable".to_string()
+            })
+        );
+        assert_eq!(
+            YtdlpEvent::from_error_line("ERROR: Unable to download webpage"),
+            Some(YtdlpEvent::Error {
+                id: None,
+                message: "Unable to download webpage".to_string()
+            })
+        );
+        assert_eq!(YtdlpEvent::from_error_line("WARNING: something"), None);
+    }
+
+    #[test]
+    fn test_read_new_skips_partial_lines() {
+        let mut log = EventLog::new();
+        std::fs::write(
+            &log.path,
+            "{\"event\":\"started\",\"info\":{\"id\":\"a\"}}\n{\"event\":\"fin",
+        )
+        .unwrap();
+        assert_eq!(log.read_new().len(), 1);
+        assert!(log.read_new().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log.path).unwrap();
+        std::io::Write::write_all(&mut file, b"ished\",\"info\":{\"id\":\"a\"}}\n").unwrap();
+        assert!(matches!(log.read_new().as_slice(), [YtdlpEvent::Finished { .. }]));
+    }
+}
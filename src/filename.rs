@@ -0,0 +1,145 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::t;
+
+/// Windowsのファイル名に使えない文字
+const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Windowsで予約されている名前（拡張子を付けても使えない）
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// ファイル名に使う文字の制限（`--filename-policy` / 設定ファイルの `filename_policy`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilenamePolicy {
+    /// 絵文字・絵記号を削除
+    StripEmoji,
+    /// Windowsで使えない文字・名前を置き換える（他のOSで保存してWindowsへ同期する場合など）
+    WindowsSafe,
+    /// 絵文字を削除し、ローマ字・ASCIIに変換（`--transliterate` と同じ）
+    Ascii,
+}
+
+impl FilenamePolicy {
+    /// 説明文
+    pub fn description(&self) -> &str {
+        match self {
+            FilenamePolicy::StripEmoji => t!("絵文字を削除"),
+            FilenamePolicy::WindowsSafe => t!("Windowsで使える名前にする"),
+            FilenamePolicy::Ascii => t!("ローマ字・ASCIIに変換"),
+        }
+    }
+
+    /// テンプレートを展開したファイル名（出力先からの相対パス）に適用
+    ///
+    /// フォルダの区切りは "/" にそろえます。
+    pub fn apply(&self, path: &str) -> String {
+        path.split(['/', '\\'])
+            .map(|component| {
+                let component = match self {
+                    FilenamePolicy::StripEmoji => strip_emoji(component),
+                    FilenamePolicy::WindowsSafe => windows_safe(component),
+                    FilenamePolicy::Ascii => windows_safe(&transliterate_component(&strip_emoji(component))),
+                };
+                if component.is_empty() {
+                    "_".to_string()
+                } else {
+                    component
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// 絵文字・絵記号か（肌の色・異体字セレクタ・結合文字など、絵文字の一部も含む）
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF
+            | 0x2300..=0x23FF
+            | 0x2600..=0x27BF
+            | 0x2B00..=0x2BFF
+            | 0xFE0E..=0xFE0F
+            | 0x200D
+            | 0x20E3
+            | 0xE0020..=0xE007F
+    )
+}
+
+/// 絵文字を削除し、続いた空白を1つにまとめる
+fn strip_emoji(name: &str) -> String {
+    let stripped: String = name.chars().filter(|c| !is_emoji(*c)).collect();
+    let mut result = String::new();
+    for c in stripped.chars() {
+        if c == ' ' && (result.is_empty() || result.ends_with(' ')) {
+            continue;
+        }
+        result.push(c);
+    }
+    // 拡張子の前の空白も除く（"タイトル 🎵.mp4" -> "タイトル.mp4"）
+    match result.rsplit_once('.') {
+        // 絵文字だけのタイトルは "_" にする
+        Some((stem, ext)) if stem.trim_end().is_empty() => format!("_.{}", ext),
+        Some((stem, ext)) => format!("{}.{}", stem.trim_end(), ext),
+        None => result.trim_end().to_string(),
+    }
+}
+
+/// Windowsで使えない文字・名前を置き換える
+fn windows_safe(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| if RESERVED_CHARS.contains(&c) || c.is_control() { '_' } else { c })
+        .collect();
+    // 末尾のピリオド・空白は使えない
+    let mut name = replaced.trim_end_matches(['.', ' ']).to_string();
+    let stem = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        name.insert(stem.len(), '_');
+    }
+    name
+}
+
+/// ファイル名の1つの要素をローマ字・ASCIIに変換
+///
+/// 日本語はかな・漢字の読み（漢字は中国語の読みになる場合があります）、
+/// アクセント付きの文字は元の文字に変換します。
+fn transliterate_component(name: &str) -> String {
+    deunicode::deunicode_with_tofu(name, "_").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii() {
+        let ascii = |name| FilenamePolicy::Ascii.apply(name);
+        assert_eq!(ascii("Café Olé-abc123.webm"), "Cafe Ole-abc123.webm");
+        assert_eq!(ascii("ひらがな 🎵.mp4"), "hiragana.mp4");
+        assert_eq!(ascii("Artist\\Song.webm"), "Artist/Song.webm");
+        // 変換後にファイル名に使えない文字は置き換える
+        assert_eq!(ascii("¿Qué?.mp4"), "_Que_.mp4");
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        let strip = |name| FilenamePolicy::StripEmoji.apply(name);
+        assert_eq!(strip("🔥 新曲 🎵 MV ❤️.webm"), "新曲 MV.webm");
+        assert_eq!(strip("👨‍👩‍👧 Family 👍🏽.mp4"), "Family.mp4");
+        assert_eq!(strip("🎵.mp4"), "_.mp4");
+    }
+
+    #[test]
+    fn test_windows_safe() {
+        let safe = |name| FilenamePolicy::WindowsSafe.apply(name);
+        assert_eq!(safe("Q&A: why? <live>.mp4"), "Q&A_ why_ _live_.mp4");
+        assert_eq!(safe("con.mp4"), "con_.mp4");
+        assert_eq!(safe("Vol. 2./etc..."), "Vol. 2/etc");
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, YtdlError};
+use crate::events::ItemInfo;
+use crate::t;
+
+/// 「最も再生された部分」のグラフの1区間（yt-dlpの `heatmap`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapMarker {
+    /// 区間の開始（秒）
+    pub start_time: f64,
+    /// 区間の終了（秒）
+    pub end_time: f64,
+    /// 再生された回数の相対値（最大が1.0）
+    pub value: f64,
+}
+
+/// ヒートマップのサイドカー（"タイトル.heatmap.json"）の内容
+#[derive(Debug, Serialize)]
+struct Sidecar<'a> {
+    id: Option<&'a str>,
+    title: Option<&'a str>,
+    webpage_url: Option<&'a str>,
+    heatmap: &'a [HeatmapMarker],
+}
+
+/// 動画と同じフォルダにヒートマップを保存（`--write-heatmap`）
+///
+/// 保存したファイルを返します。ヒートマップがない動画（再生回数が少ない動画など）はNone。
+pub fn write_sidecar(info: &ItemInfo, video: &Path) -> Result<Option<PathBuf>> {
+    let Some(heatmap) = info.heatmap.as_deref().filter(|heatmap| !heatmap.is_empty()) else {
+        return Ok(None);
+    };
+    let sidecar = Sidecar {
+        id: info.id.as_deref(),
+        title: info.title.as_deref(),
+        webpage_url: info.webpage_url.as_deref(),
+        heatmap,
+    };
+    let json = serde_json::to_string_pretty(&sidecar)
+        .map_err(|e| YtdlError::Other(t!("ヒートマップを保存できません: {}", e)))?;
+    let path = sidecar_path(video);
+    std::fs::write(&path, json)?;
+    Ok(Some(path))
+}
+
+/// 最も再生された区間
+pub fn most_replayed(heatmap: &[HeatmapMarker]) -> Option<&HeatmapMarker> {
+    heatmap.iter().max_by(|a, b| a.value.total_cmp(&b.value))
+}
+
+/// サイドカーのパス（動画の拡張子を ".heatmap.json" に置き換える）
+fn sidecar_path(video: &Path) -> PathBuf {
+    video.with_extension("heatmap.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_replayed() {
+        let marker = |start_time: f64, value: f64| HeatmapMarker {
+            start_time,
+            end_time: start_time + 10.0,
+            value,
+        };
+        let heatmap = [marker(0.0, 0.4), marker(10.0, 1.0), marker(20.0, 0.7)];
+        assert_eq!(most_replayed(&heatmap), Some(&heatmap[1]));
+        assert_eq!(most_replayed(&[]), None);
+
+        assert_eq!(
+            sidecar_path(Path::new("/tmp/out/Vol. 2.mp4")),
+            PathBuf::from("/tmp/out/Vol. 2.heatmap.json")
+        );
+    }
+}
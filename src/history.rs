@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use rusqlite::{params, Connection, Row};
+
+use crate::error::Result;
+use crate::paths;
+
+/// 履歴のファイル名（データディレクトリに作成）
+const FILE_NAME: &str = "history.db";
+
+/// 他のプロセス（並列実行など）が書き込み中の場合に待つ時間
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 一覧・検索で取得する列（日時はローカル時刻の文字列にする）
+const COLUMNS: &str = "video_id, title, url, path, size, quality,
+    strftime('%Y-%m-%d %H:%M', downloaded_at, 'unixepoch', 'localtime')";
+
+/// ダウンロード履歴の1件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub video_id: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// 保存先のファイル
+    pub path: PathBuf,
+    /// ファイルサイズ（バイト）
+    pub size: u64,
+    /// 品質プリセットの名前
+    pub quality: String,
+    /// ダウンロードした日時（"2024-01-02 03:04"、記録時は空）
+    pub date: String,
+}
+
+/// ダウンロード履歴（`ytdl history`）
+///
+/// 完了したダウンロードをデータディレクトリの "history.db"（SQLite）に記録します。
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    /// データディレクトリの履歴を開く（なければ作成）
+    pub fn open() -> Result<Self> {
+        Self::open_at(&paths::data_dir()?.join(FILE_NAME))
+    }
+
+    /// 指定したファイルの履歴を開く（なければ作成）
+    pub fn open_at(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS downloads (
+                id INTEGER PRIMARY KEY,
+                video_id TEXT,
+                title TEXT,
+                url TEXT,
+                path TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                quality TEXT NOT NULL,
+                downloaded_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS downloads_downloaded_at ON downloads (downloaded_at);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// 完了したダウンロードを記録（日時は現在時刻）
+    pub fn record(&self, entry: &HistoryEntry) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.conn.execute(
+            "INSERT INTO downloads (video_id, title, url, path, size, quality, downloaded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry.video_id,
+                entry.title,
+                entry.url,
+                entry.path.to_string_lossy(),
+                entry.size as i64,
+                entry.quality,
+                now as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 新しい順に最大 `limit` 件
+    pub fn list(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM downloads ORDER BY downloaded_at DESC, id DESC LIMIT ?1",
+            COLUMNS
+        ))?;
+        let entries = stmt
+            .query_map(params![limit as i64], entry_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// タイトル・動画ID・URL・保存先に `query` を含むものを新しい順に
+    pub fn search(&self, query: &str) -> Result<Vec<HistoryEntry>> {
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM downloads
+             WHERE title LIKE ?1 ESCAPE '\\' OR video_id LIKE ?1 ESCAPE '\\'
+                OR url LIKE ?1 ESCAPE '\\' OR path LIKE ?1 ESCAPE '\\'
+             ORDER BY downloaded_at DESC, id DESC",
+            COLUMNS
+        ))?;
+        let entries = stmt
+            .query_map(params![pattern], entry_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// 記録したファイルがすべて削除された動画（履歴にない動画は含めない）
+    ///
+    /// 動画ごとに最新の記録を返します。
+    pub fn missing_files(&self, video_ids: &[String]) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM downloads WHERE video_id = ?1 ORDER BY downloaded_at DESC, id DESC",
+            COLUMNS
+        ))?;
+        let mut missing = Vec::new();
+        for id in video_ids {
+            let entries = stmt
+                .query_map(params![id], entry_from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            if entries.iter().all(|entry| !entry.path.exists()) {
+                missing.extend(entries.into_iter().next());
+            }
+        }
+        Ok(missing)
+    }
+
+    /// すべて削除（削除した件数を返す）
+    pub fn clear(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM downloads", [])?)
+    }
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        video_id: row.get(0)?,
+        title: row.get(1)?,
+        url: row.get(2)?,
+        path: PathBuf::from(row.get::<_, String>(3)?),
+        size: row.get::<_, i64>(4)?.max(0) as u64,
+        quality: row.get(5)?,
+        date: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_search_clear() {
+        let root = std::env::temp_dir().join(format!("ytdl-test-history-{}", std::process::id()));
+        let history = History::open_at(&root.join(FILE_NAME)).unwrap();
+
+        let entry = |id: &str, title: &str| HistoryEntry {
+            video_id: Some(id.to_string()),
+            title: Some(title.to_string()),
+            path: PathBuf::from(format!("/videos/{}.mp4", title)),
+            size: 100,
+            quality: "max-video".to_string(),
+            ..Default::default()
+        };
+        history.record(&entry("a1", "Lecture 1")).unwrap();
+        history.record(&entry("b2", "100% Live")).unwrap();
+
+        let titles = |entries: Vec<HistoryEntry>| -> Vec<String> {
+            entries.into_iter().filter_map(|entry| entry.title).collect()
+        };
+        assert_eq!(titles(history.list(10).unwrap()), ["100% Live", "Lecture 1"]);
+        assert_eq!(titles(history.list(1).unwrap()), ["100% Live"]);
+        assert_eq!(titles(history.search("lecture").unwrap()), ["Lecture 1"]);
+        // "%" はワイルドカードとして扱わない
+        assert_eq!(titles(history.search("0%").unwrap()), ["100% Live"]);
+        assert_eq!(history.list(1).unwrap()[0].date.len(), "2024-01-02 03:04".len());
+
+        // 記録したファイルが残っている動画・履歴にない動画は含めない
+        let kept = root.join("kept.mp4");
+        std::fs::write(&kept, b"").unwrap();
+        history.record(&HistoryEntry { path: kept, ..entry("c3", "Kept") }).unwrap();
+        let ids = ["a1", "c3", "zz"].map(str::to_string);
+        assert_eq!(titles(history.missing_files(&ids).unwrap()), ["Lecture 1"]);
+
+        assert_eq!(history.clear().unwrap(), 3);
+        assert!(history.list(10).unwrap().is_empty());
+
+        drop(history);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
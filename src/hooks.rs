@@ -0,0 +1,244 @@
+use serde::Deserialize;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// フックのデフォルトのタイムアウト（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// 処理の段階ごとに実行するコマンド（config.toml の `[hooks]`）
+///
+/// コマンドはシェル（Windowsでは cmd）で実行され、対象の情報は環境変数で渡されます。
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// URLごとのダウンロード開始前
+    pub pre_download: Option<Hook>,
+    /// 動画1本の処理（後処理・タグ付けまで）が完了した後
+    pub post_item: Option<Hook>,
+    /// 実行したすべてのURLの処理が終わった後（失敗した場合も実行）
+    pub post_run: Option<Hook>,
+}
+
+/// フックを実行する段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreDownload,
+    PostItem,
+    PostRun,
+}
+
+impl HookStage {
+    /// 設定ファイルでの名前（環境変数 YTDL_HOOK の値）
+    pub fn name(&self) -> &str {
+        match self {
+            HookStage::PreDownload => "pre_download",
+            HookStage::PostItem => "post_item",
+            HookStage::PostRun => "post_run",
+        }
+    }
+}
+
+/// フックのコマンド
+///
+/// 文字列だけを指定した場合はデフォルトのタイムアウト・失敗時の扱いになります。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "HookEntry")]
+pub struct Hook {
+    /// 実行するコマンド
+    pub command: String,
+    /// タイムアウト（秒）。超えた場合は強制終了して失敗として扱う
+    pub timeout: u64,
+    /// 失敗した場合の扱い
+    pub on_failure: FailurePolicy,
+}
+
+/// フックが失敗した場合（終了コードが0以外・タイムアウト）の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePolicy {
+    /// 何も表示せずに続行
+    Ignore,
+    /// 警告を表示して続行（デフォルト）
+    #[default]
+    Warn,
+    /// 処理を中止（pre_download ならそのURLをダウンロードしない）
+    Abort,
+}
+
+/// 設定ファイルでの書き方（文字列、またはテーブル）
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HookEntry {
+    Command(String),
+    Full {
+        command: String,
+        #[serde(default)]
+        timeout: Option<u64>,
+        #[serde(default)]
+        on_failure: FailurePolicy,
+    },
+}
+
+impl From<HookEntry> for Hook {
+    fn from(entry: HookEntry) -> Self {
+        match entry {
+            HookEntry::Command(command) => Self {
+                command,
+                timeout: DEFAULT_TIMEOUT_SECS,
+                on_failure: FailurePolicy::default(),
+            },
+            HookEntry::Full {
+                command,
+                timeout,
+                on_failure,
+            } => Self {
+                command,
+                timeout: timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
+                on_failure,
+            },
+        }
+    }
+}
+
+impl Hooks {
+    /// 指定した段階のフックを実行（設定されていなければ何もしない）
+    ///
+    /// 失敗時の扱いが abort の場合のみエラーを返します。
+    pub fn run(&self, stage: HookStage, env: &[(&str, String)]) -> Result<()> {
+        let hook = match stage {
+            HookStage::PreDownload => &self.pre_download,
+            HookStage::PostItem => &self.post_item,
+            HookStage::PostRun => &self.post_run,
+        };
+        let Some(hook) = hook else {
+            return Ok(());
+        };
+
+        let _span = tracing::info_span!("hook", stage = stage.name()).entered();
+        let Err(reason) = hook.execute(stage, env) else {
+            return Ok(());
+        };
+        match hook.on_failure {
+            FailurePolicy::Ignore => Ok(()),
+            FailurePolicy::Warn => {
+                eprintln!("{}", t!("警告: フック {} が失敗しました: {}", stage.name(), reason));
+                Ok(())
+            }
+            FailurePolicy::Abort => Err(YtdlError::Other(t!(
+                "フック {} が失敗したため中止しました: {}",
+                stage.name(),
+                reason
+            ))),
+        }
+    }
+}
+
+impl Hook {
+    /// コマンドを実行し、終了を待つ（失敗した場合は理由を返す）
+    fn execute(&self, stage: HookStage, env: &[(&str, String)]) -> std::result::Result<(), String> {
+        let mut cmd = shell_command(&self.command);
+        cmd.env("YTDL_HOOK", stage.name());
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .spawn()
+            .map_err(|e| t!("起動できません: {}", e))?;
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => return Ok(()),
+                Ok(Some(status)) => {
+                    return Err(t!("終了コード {}", status.code().unwrap_or(-1)));
+                }
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(t!("{}秒でタイムアウトしました", self.timeout));
+                }
+                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    }
+}
+
+/// シェル経由でコマンドを実行する
+fn shell_command(command: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hooks() {
+        let hooks: Hooks = toml::from_str(
+            r#"
+            post_item = "notify-send done"
+
+            [post_run]
+            command = "./sync.sh"
+            timeout = 300
+            on_failure = "abort"
+            "#,
+        )
+        .unwrap();
+
+        let post_item = hooks.post_item.unwrap();
+        assert_eq!(post_item.command, "notify-send done");
+        assert_eq!(post_item.timeout, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(post_item.on_failure, FailurePolicy::Warn);
+
+        let post_run = hooks.post_run.unwrap();
+        assert_eq!(post_run.timeout, 300);
+        assert_eq!(post_run.on_failure, FailurePolicy::Abort);
+        assert!(hooks.pre_download.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failure_policy() {
+        let hooks = |command: &str, timeout: u64, on_failure: FailurePolicy| Hooks {
+            pre_download: Some(Hook {
+                command: command.to_string(),
+                timeout,
+                on_failure,
+            }),
+            ..Default::default()
+        };
+        let env = [("YTDL_URL", "https://example.com".to_string())];
+
+        let check_env = r#"[ "$YTDL_HOOK" = pre_download ] && [ "$YTDL_URL" = https://example.com ]"#;
+        assert!(hooks(check_env, 5, FailurePolicy::Abort)
+            .run(HookStage::PreDownload, &env)
+            .is_ok());
+        assert!(hooks("exit 3", 5, FailurePolicy::Abort)
+            .run(HookStage::PreDownload, &env)
+            .is_err());
+        assert!(hooks("exit 3", 5, FailurePolicy::Ignore)
+            .run(HookStage::PreDownload, &env)
+            .is_ok());
+        assert!(hooks("sleep 5", 0, FailurePolicy::Abort)
+            .run(HookStage::PreDownload, &env)
+            .is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod en;
+
+/// 表示する言語（`--lang` / 設定ファイルの `lang`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    /// 日本語
+    Ja,
+    /// 英語
+    En,
+}
+
+impl Lang {
+    /// 利用できるすべての言語
+    pub const ALL: &[Lang] = &[Lang::Ja, Lang::En];
+
+    /// 言語コード（`--lang` に指定する値）
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Ja => "ja",
+            Lang::En => "en",
+        }
+    }
+
+    /// その言語での言語名
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Lang::Ja => "日本語",
+            Lang::En => "English",
+        }
+    }
+
+    /// 訳（日本語はメッセージそのものなのでNone）
+    fn catalog(self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        match self {
+            Lang::Ja => None,
+            Lang::En => Some(&EN),
+        }
+    }
+
+    /// 環境変数（LC_ALL / LC_MESSAGES / LANG）から決める
+    ///
+    /// 設定されていない場合（Windowsなど）と "C" / "POSIX" は日本語、それ以外は "ja" で始まらなければ英語です。
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+        Self::from_locale(locale.as_deref())
+    }
+
+    fn from_locale(locale: Option<&str>) -> Self {
+        match locale {
+            None => Lang::Ja,
+            Some(locale) if locale.starts_with("ja") || locale.starts_with("C.") => Lang::Ja,
+            Some("C" | "POSIX") => Lang::Ja,
+            Some(_) => Lang::En,
+        }
+    }
+
+    /// 桁区切りと小数点の記号
+    fn separators(self) -> (char, char) {
+        match self {
+            Lang::Ja | Lang::En => (',', '.'),
+        }
+    }
+
+    /// 時刻を12時間制（AM/PM）で表示するか
+    fn uses_12_hour_clock(self) -> bool {
+        match self {
+            Lang::Ja => false,
+            Lang::En => true,
+        }
+    }
+
+    /// 整数を桁区切り付きで表示（"1,234"）
+    pub fn format_number(self, n: u64) -> String {
+        let (group, _) = self.separators();
+        let digits = n.to_string();
+        let mut result = String::with_capacity(digits.len() * 4 / 3);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                result.push(group);
+            }
+            result.push(c);
+        }
+        result
+    }
+
+    /// 0以上の小数を小数点以下 `precision` 桁で表示（"1,234.50"）
+    pub fn format_decimal(self, value: f64, precision: usize) -> String {
+        let (_, point) = self.separators();
+        let formatted = format!("{:.*}", precision, value.max(0.0));
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let integer = integer.parse().map(|n| self.format_number(n)).unwrap_or_else(|_| integer.to_string());
+        if fraction.is_empty() {
+            integer
+        } else {
+            format!("{}{}{}", integer, point, fraction)
+        }
+    }
+
+    /// 日付を表示（日本語: "2024/01/02"、英語: "Jan 2, 2024"）
+    pub fn format_date(self, year: u32, month: u32, day: u32) -> String {
+        const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+        match self {
+            Lang::Ja => format!("{}/{:02}/{:02}", year, month, day),
+            Lang::En => {
+                let month = month.checked_sub(1).and_then(|i| MONTHS.get(i as usize)).unwrap_or(&"?");
+                format!("{} {}, {}", month, day, year)
+            }
+        }
+    }
+
+    /// 日時を表示（日本語は24時間制 "2024/01/02 15:04"、英語は12時間制 "Jan 2, 2024 3:04 PM"）
+    pub fn format_date_time(self, year: u32, month: u32, day: u32, hour: u32, minute: u32) -> String {
+        let date = self.format_date(year, month, day);
+        if self.uses_12_hour_clock() {
+            let suffix = if hour < 12 { "AM" } else { "PM" };
+            let hour = match hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            format!("{} {}:{:02} {}", date, hour, minute, suffix)
+        } else {
+            format!("{} {:02}:{:02}", date, hour, minute)
+        }
+    }
+
+    /// "2024-01-02" / "2024-01-02 15:04" をこの言語の形式にする（解釈できなければそのまま）
+    pub fn localize_date(self, iso: &str) -> String {
+        let (date, time) = iso.split_once(' ').map_or((iso, None), |(date, time)| (date, Some(time)));
+        let parse = |text: &str, separator: char, len: usize| -> Option<Vec<u32>> {
+            let parts: Vec<u32> = text.split(separator).map(|part| part.parse().ok()).collect::<Option<_>>()?;
+            (parts.len() == len).then_some(parts)
+        };
+        let Some(date) = parse(date, '-', 3) else {
+            return iso.to_string();
+        };
+        match time.map(|time| parse(time, ':', 2)) {
+            None => self.format_date(date[0], date[1], date[2]),
+            Some(Some(time)) => self.format_date_time(date[0], date[1], date[2], time[0], time[1]),
+            Some(None) => iso.to_string(),
+        }
+    }
+}
+
+/// 設定した言語の [`Lang::ALL`] での位置+1（0は未設定）
+static LANG: AtomicUsize = AtomicUsize::new(0);
+
+/// 環境変数から決めた言語（設定ファイルを読み込む前やコマンドライン引数の解析中に使用）
+static ENV_LANG: LazyLock<Lang> = LazyLock::new(Lang::from_env);
+
+/// 英語の訳（日本語のメッセージ → 英語）
+static EN: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| en::MESSAGES.iter().copied().collect());
+
+/// 表示する言語を設定
+///
+/// 設定する前に表示したメッセージ（バナー・引数のエラーなど）は環境変数の言語で表示されます。
+pub fn set_lang(lang: Lang) {
+    let index = Lang::ALL.iter().position(|l| *l == lang).unwrap_or(0);
+    LANG.store(index + 1, Ordering::Relaxed);
+}
+
+/// 表示する言語（設定されていなければ環境変数から決める）
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        0 => *ENV_LANG,
+        index => Lang::ALL[index - 1],
+    }
+}
+
+/// 整数を表示する言語の形式にする（件数・再生回数など）
+pub fn number(n: impl TryInto<u64>) -> String {
+    n.try_into().map(|n| lang().format_number(n)).unwrap_or_default()
+}
+
+/// "2024-01-02" / "2024-01-02 15:04" を表示する言語の形式にする
+pub fn localize_date(iso: &str) -> String {
+    lang().localize_date(iso)
+}
+
+/// メッセージを表示する言語に翻訳（訳がなければ英語、英語の訳もなければ日本語のまま）
+///
+/// メッセージは日本語の文字列そのものをキーにします。
+pub fn text(message: &'static str) -> &'static str {
+    let Some(catalog) = lang().catalog() else {
+        return message;
+    };
+    catalog
+        .get(message)
+        .or_else(|| EN.get(message))
+        .copied()
+        .unwrap_or(message)
+}
+
+/// "{}" を引数で順に、"{0}" などを番号の引数で置き換える（[`t!`](crate::t) で使用）
+///
+/// 訳では語順に合わせて "{1}" のように番号で指定できます。
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(pos) = rest.find('{') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let Some(end) = rest.find('}') else {
+            result.push('{');
+            continue;
+        };
+        let index = match &rest[..end] {
+            "" => {
+                next += 1;
+                next - 1
+            }
+            digits => match digits.parse::<usize>() {
+                Ok(index) => index,
+                Err(_) => {
+                    result.push('{');
+                    continue;
+                }
+            },
+        };
+        if let Some(arg) = args.get(index) {
+            result.push_str(&arg.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// メッセージを表示する言語に翻訳し、"{}" を引数で置き換える
+///
+/// 引数の書式（桁数など）は指定できないため、必要なら `format!` で整形してから渡します。
+///
+/// ```
+/// let count = 3;
+/// let message = ytdl::t!("{}本のダウンロードに失敗しました", count);
+/// # assert!(message.contains('3'));
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($message:literal) => {
+        $crate::i18n::text($message)
+    };
+    ($message:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($crate::i18n::text($message), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format("{}本中{}本", &[&3, &"1"]), "3本中1本");
+        assert_eq!(format("{1} of {0}", &[&3, &"1"]), "1 of 3");
+        assert_eq!(format("完了", &[]), "完了");
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(Lang::Ja.format_number(0), "0");
+        assert_eq!(Lang::Ja.format_number(999), "999");
+        assert_eq!(Lang::En.format_number(1234), "1,234");
+        assert_eq!(Lang::En.format_number(1_234_567), "1,234,567");
+        assert_eq!(Lang::En.format_decimal(1234.5, 2), "1,234.50");
+        assert_eq!(Lang::Ja.format_decimal(0.004, 2), "0.00");
+        assert_eq!(Lang::Ja.format_decimal(12.0, 0), "12");
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(Lang::Ja.format_date(2024, 1, 2), "2024/01/02");
+        assert_eq!(Lang::En.format_date(2024, 1, 2), "Jan 2, 2024");
+        assert_eq!(Lang::Ja.format_date_time(2024, 1, 2, 15, 4), "2024/01/02 15:04");
+        assert_eq!(Lang::En.format_date_time(2024, 1, 2, 15, 4), "Jan 2, 2024 3:04 PM");
+        assert_eq!(Lang::En.format_date_time(2024, 1, 2, 0, 30), "Jan 2, 2024 12:30 AM");
+        assert_eq!(Lang::En.format_date_time(2024, 1, 2, 12, 0), "Jan 2, 2024 12:00 PM");
+        assert_eq!(Lang::En.localize_date("2023-12-31"), "Dec 31, 2023");
+        assert_eq!(Lang::Ja.localize_date("2024-01-02 03:04"), "2024/01/02 03:04");
+        assert_eq!(Lang::En.localize_date("不明"), "不明");
+    }
+
+    #[test]
+    fn test_from_locale() {
+        assert_eq!(Lang::from_locale(None), Lang::Ja);
+        assert_eq!(Lang::from_locale(Some("ja_JP.UTF-8")), Lang::Ja);
+        assert_eq!(Lang::from_locale(Some("C.UTF-8")), Lang::Ja);
+        assert_eq!(Lang::from_locale(Some("en_US.UTF-8")), Lang::En);
+    }
+
+    #[test]
+    fn test_lang_codes() {
+        for lang in Lang::ALL {
+            assert_eq!(Lang::from_str(lang.code(), false), Ok(*lang));
+        }
+    }
+
+    #[test]
+    fn test_catalog_placeholders() {
+        let placeholders = regex::Regex::new(r"\{\d*\}").unwrap();
+        let mut keys = std::collections::HashSet::new();
+        for (ja, en) in en::MESSAGES {
+            assert!(keys.insert(ja), "重複したメッセージ: {}", ja);
+            assert_eq!(ja.matches("{}").count(), placeholders.find_iter(en).count(), "{}", ja);
+        }
+    }
+
+    #[test]
+    fn test_catalog_covers_sources() {
+        // 訳のないメッセージは英語でも日本語のまま表示されるため、ソース中のメッセージがすべて訳にあることを確認する
+        fn rust_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    rust_files(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+
+        let literal = regex::Regex::new(r#"(?m)(?:^|[^\w])t!\(\s*"((?:\\.|[^"\\])*)""#).unwrap();
+        let escape = regex::Regex::new(r"\\(.)").unwrap();
+        let keys: std::collections::HashSet<&str> = en::MESSAGES.iter().map(|(ja, _)| *ja).collect();
+        let mut files = Vec::new();
+        rust_files(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut files);
+        let mut missing = Vec::new();
+        for file in files {
+            let source = std::fs::read_to_string(&file).unwrap();
+            // ドキュメントの例などコメント内のメッセージは除く
+            let code: Vec<&str> = source.lines().filter(|line| !line.trim_start().starts_with("//")).collect();
+            for captures in literal.captures_iter(&code.join("\n")) {
+                let message = escape.replace_all(&captures[1], |c: &regex::Captures| match &c[1] {
+                    "n" => "\n".to_string(),
+                    "t" => "\t".to_string(),
+                    other => other.to_string(),
+                });
+                if !keys.contains(message.as_ref()) {
+                    missing.push(format!("{}: {}", file.display(), message));
+                }
+            }
+        }
+        assert!(missing.is_empty(), "訳のないメッセージ:\n{}", missing.join("\n"));
+    }
+}
@@ -0,0 +1,450 @@
+/// 英語の訳（日本語のメッセージ, 英語）
+///
+/// 英語では語順に合わせて "{1}" のように引数の番号を指定できます。
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    ("\nエラー: {}", "\nError: {}"),
+    ("\nEnterキーを押して終了...", "\nPress Enter to exit..."),
+    ("警告: {}", "Warning: {}"),
+    ("🍪 {} からcookies.txtを取得中...", "🍪 Fetching cookies.txt from {}..."),
+    ("✅ cookies.txtを保存しました: {}", "✅ Saved cookies.txt: {}"),
+    ("   以降の実行で自動的に使用されます", "   It will be used automatically from now on"),
+    ("🔄 yt-dlpを最新版に更新中...", "🔄 Updating yt-dlp to the latest version..."),
+    ("警告: yt-dlp更新失敗: {}", "Warning: failed to update yt-dlp: {}"),
+    ("続行します...\n", "Continuing...\n"),
+    ("\n📦 yt-dlpの確認中...", "\n📦 Checking yt-dlp..."),
+    ("📁 {}、{} に保存します", "📁 {}; saving to {}"),
+    ("📋 キューは空です（ytdl queue add <URL> で追加）", "📋 The queue is empty (add URLs with ytdl queue add <URL>)"),
+    ("\n🎮 インタラクティブモードで起動しました", "\n🎮 Started in interactive mode"),
+    ("入力エラー: {}", "Input error: {}"),
+    ("\nキャンセルしました", "\nCancelled"),
+    ("エラー: URLを指定してください", "Error: please specify a URL"),
+    ("\n✅ すべてのダウンロードが完了しました！", "\n✅ All downloads completed!"),
+    ("📁 保存先: {}\n", "📁 Saved to: {}\n"),
+    ("Enterキーを押して終了...", "Press Enter to exit..."),
+    ("💡 プレイリスト内の動画のURLです。この動画のみダウンロードします（全体は --whole-list）", "💡 This is a video URL inside a playlist. Downloading only this video (use --whole-list for the whole playlist)"),
+    ("設定エラー: {}", "Configuration error: {}"),
+    ("   （Esc: 前の質問に戻る / URLを空欄でEnter: 中止）", "   (Esc: back to the previous question / Enter with an empty URL: quit)"),
+    ("途中までダウンロードしたファイルがあります（{}件、{}）。続きから再開しますか？", "There are partially downloaded files ({} files, {}). Resume them?"),
+    ("同じ名前のファイルがあります: {}", "A file with the same name exists: {}"),
+    ("\n   例: https://www.youtube.com/watch?v=dQw4w9WgXcQ", "\n   e.g. https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+    ("🍪 Cookieのスナップショットを作成しました", "🍪 Created a cookie snapshot"),
+    ("警告: Cookieのスナップショット作成に失敗しました: {}", "Warning: failed to create a cookie snapshot: {}"),
+    ("ブラウザのCookieを直接使用します", "Using the browser cookies directly"),
+    ("警告: 音声のみのダウンロードでは --also-encode は無視されます", "Warning: --also-encode is ignored for audio-only downloads"),
+    ("🎞  変換に{}を使用します（無効にする場合は --sw-encode）", "🎞  Using {} for encoding (disable with --sw-encode)"),
+    ("警告: 映像付きのダウンロードでは --trim-silence は無視されます", "Warning: --trim-silence is ignored for video downloads"),
+    ("警告: 音声のみのダウンロードでは --embed-subs は無視されます", "Warning: --embed-subs is ignored for audio-only downloads"),
+    ("警告: 音声のみのダウンロードでは --write-storyboards は無視されます", "Warning: --write-storyboards is ignored for audio-only downloads"),
+    ("yt-dlp バージョン: {}", "yt-dlp version: {}"),
+    ("📝 レポートを保存しました: {}", "📝 Saved the report: {}"),
+    ("警告: レポートを保存できません: {}", "Warning: could not save the report: {}"),
+    ("警告: MQTT配信に失敗しました: {}", "Warning: failed to publish to MQTT: {}"),
+    ("{}件中{}件のURLでダウンロードに失敗しました", "Downloads failed for {1} of {0} URLs"),
+    ("🔁 途中までダウンロードしたファイル（{}件、{}）の続きから再開します", "🔁 Resuming partially downloaded files ({} files, {})"),
+    ("🔍 動画情報を取得中...", "🔍 Fetching video information..."),
+    ("新しくダウンロードされる動画はありません（アーカイブ済み）", "No new videos to download (already archived)"),
+    ("{}/{}本完了", "{}/{} done"),
+    ("{}本中{}本のダウンロードに失敗しました: {}", "{1} of {0} downloads failed: {2}"),
+    ("\n[やり直し] {}", "\n[retry] {}"),
+    ("\n✓ 失敗した動画をすべてやり直しました", "\n✓ All failed videos were retried successfully"),
+    ("⏭ {}: {}のためやり直しません", "⏭ {}: not retrying ({})"),
+    ("\n🔁 失敗した{}本をやり直します（{}回目）", "\n🔁 Retrying {} failed videos (pass {})"),
+    ("   プレイヤークライアントを {} に切り替えます", "   Switching the player client to {}"),
+    ("⏩ 最大{}本まで同時ダウンロードの本数を自動調整します", "⏩ Auto-tuning concurrent downloads (up to {})"),
+    ("⏩ {}本ずつ同時にダウンロードします", "⏩ Downloading {} videos at a time"),
+    ("0/{}本完了", "0/{} done"),
+    ("許可されたチャンネル（--own-channel）の動画ではないためダウンロードしません（{}）", "Not downloading: the video is not from an allowed channel (--own-channel) ({})"),
+    ("   ⏭ クリエイティブ・コモンズではないためスキップ（{}）", "   ⏭ Skipped: not Creative Commons ({})"),
+    ("{}以下に収まるフォーマットがありません", "No format fits within {}"),
+    ("   フォーマット: {} (約 {})", "   Format: {} (about {})"),
+    ("   字幕（{}）をそのまま埋め込めないため、{}で保存します", "   Subtitles ({}) cannot be embedded as is; saving as {}"),
+    ("   ファイル名: {}", "   File name: {}"),
+    ("   ⏭ 同名のファイルがあるためスキップ: {}", "   ⏭ Skipped: a file with the same name exists: {}"),
+    ("   上書きします: {}", "   Overwriting: {}"),
+    ("   同名のファイルがあるため別名で保存: {}", "   A file with the same name exists; saving as: {}"),
+    ("\n実行コマンド: {}\n", "\nCommand: {}\n"),
+    ("プロセス起動失敗: {}", "Failed to start the process: {}"),
+    ("警告: 出力読み取りエラー: {}", "Warning: error reading output: {}"),
+    ("⚙ 自動調整: {}", "⚙ Auto-tune: {}"),
+    ("🔇 無音を削除しました: {}", "🔇 Trimmed silence: {}"),
+    ("🖼  ストーリーボードを保存しました: {}", "🖼  Saved the storyboard: {}"),
+    ("   ストーリーボードはありません: {}", "   No storyboard available: {}"),
+    ("\n❌ YouTubeのBot対策により、ブラウザのCookie認証が必要です\n", "\n❌ YouTube's bot protection requires browser cookie authentication\n"),
+    ("📝 解決方法:", "📝 How to fix:"),
+    ("  1. Chromeを開いてYouTubeにログインしてください", "  1. Open Chrome and sign in to YouTube"),
+    ("  2. ログイン後、このツールを再度実行してください", "  2. After signing in, run this tool again"),
+    ("  3. デフォルトでChromeのCookieを使用します\n", "  3. Chrome cookies are used by default\n"),
+    ("別のブラウザを使用する場合:", "To use another browser:"),
+    ("  --cookies firefox  (Firefoxの場合)", "  --cookies firefox  (for Firefox)"),
+    ("  --cookies edge     (Edgeの場合)\n", "  --cookies edge     (for Edge)\n"),
+    ("\n❌ ChromeのCookieデータベースをコピーできませんでした\n", "\n❌ Could not copy the Chrome cookie database\n"),
+    ("📝 解決方法（以下のいずれかを試してください）:", "📝 How to fix (try one of the following):"),
+    ("  1. Chromeを完全に終了してから、再度このツールを実行", "  1. Quit Chrome completely, then run this tool again"),
+    ("  2. タスクマネージャーでChrome関連プロセスを全て終了", "  2. End all Chrome processes in Task Manager"),
+    ("  3. Firefoxを使用: ytdl.exe --cookies firefox <URL>", "  3. Use Firefox: ytdl.exe --cookies firefox <URL>"),
+    ("  4. Edgeを使用: ytdl.exe --cookies edge <URL>\n", "  4. Use Edge: ytdl.exe --cookies edge <URL>\n"),
+    ("💡 ヒント: Chromeが起動中だとCookieファイルがロックされます\n", "💡 Hint: the cookie file is locked while Chrome is running\n"),
+    ("\n❌ yt-dlpエラー詳細:", "\n❌ yt-dlp error details:"),
+    ("🧾 不具合報告用の情報を保存しました: {}", "🧾 Saved information for a bug report: {}"),
+    ("yt-dlpがエラーコード{}で終了しました", "yt-dlp exited with error code {}"),
+    ("警告: ダウンロード履歴に記録できません: {}", "Warning: could not record the download history: {}"),
+    ("🔥 ヒートマップを保存しました: {}", "🔥 Saved the heatmap: {}"),
+    ("   最も再生された部分: {}〜{}", "   Most replayed: {}-{}"),
+    ("   ヒートマップはありません: {}", "   No heatmap available: {}"),
+    ("🎞  変換しました: {}", "🎞  Encoded: {}"),
+    ("終了要求により中断しました（未処理: {}件）", "Interrupted by a shutdown request ({} not processed)"),
+    ("警告: ハッシュを記録できません: {}", "Warning: could not record hashes: {}"),
+    ("警告: ハッシュを記録できません: {}: {}", "Warning: could not record the hash: {}: {}"),
+    ("{}件のファイルを転送できませんでした", "Failed to upload {} files"),
+    ("🍪 {}ブラウザのCookieを使用します", "🍪 Using cookies from {}"),
+    ("警告: Cookieパスの検出に失敗しました: {}", "Warning: failed to detect the cookie path: {}"),
+    ("ヒント: {}でYouTubeにログインしていることを確認してください", "Hint: make sure you are signed in to YouTube in {}"),
+    ("⚠️  Cookieを使用しません（Bot判定される可能性があります）", "⚠️  Not using cookies (YouTube may flag this as a bot)"),
+    ("🔍 ダウンロード予定の動画を確認中...\n", "🔍 Checking videos to download...\n"),
+    ("=== ダウンロード予定の動画 ===", "=== Videos to download ==="),
+    ("約 {}", "about {}"),
+    ("        ⏭ クリエイティブ・コモンズではないためスキップ", "        ⏭ Skipped: not Creative Commons"),
+    ("        ⛔ 許可されたチャンネルの動画ではないためダウンロードしません", "        ⛔ Not downloaded: not from an allowed channel"),
+    ("        字幕（{}）を埋め込むため{}で保存", "        Saved as {1} to embed subtitles ({0})"),
+    ("        ⚠ 同名のファイルがあります: {}", "        ⚠ A file with the same name exists: {}"),
+    ("合計: {}本 / 約 {}", "Total: {} videos / about {}"),
+    ("（サイズ不明 {}本を除く）", " (excluding {} of unknown size)"),
+    ("サイズ上限に収まらずスキップ: {}本", "Skipped (over the size limit): {}"),
+    ("動画情報の取得に失敗: {}", "Failed to fetch video information: {}"),
+    ("情報取得失敗: {}", "Failed to fetch information: {}"),
+    ("\n=== 失敗したURL（{}件） ===", "\n=== Failed URLs ({}) ==="),
+    ("\n⏭ 既存のファイルがあるためスキップ: {}件", "\n⏭ Skipped because the file already exists: {}"),
+    ("\n📊 完了: {}本 / 失敗: {}件", "\n📊 Completed: {} / Failed: {}"),
+    ("💡 動画ごとの詳しい出力: ytdl logs {}", "💡 Detailed output for each video: ytdl logs {}"),
+    ("チャンネル不明", "unknown channel"),
+    ("ライセンス不明", "unknown license"),
+    ("完了", "Done"),
+    ("\n✓ ダウンロードが正常に完了しました", "\n✓ Download completed successfully"),
+    ("YouTube認証エラー: ブラウザでログインしてください", "YouTube authentication error: sign in with your browser"),
+    ("Cookie読み込みエラー: Chromeを終了してください", "Cookie read error: quit Chrome and try again"),
+    ("URLが指定されていません", "No URL was given"),
+    ("上限に収まるフォーマットなし", "no format within the limit"),
+    ("サイズ不明", "unknown size"),
+    ("日付不明", "unknown date"),
+    ("（タイトル不明）", "(unknown title)"),
+    ("端末から実行されていません。URLを引数で指定してください", "Not running in a terminal. Pass the URL as an argument"),
+    ("💬 字幕もダウンロードしますか？", "💬 Download subtitles too?"),
+    ("📁 保存先のフォルダを開く", "📁 Open the output folder"),
+    ("▶ ダウンロードしたファイルを再生", "▶ Play the downloaded file"),
+    ("📺 別のURLをダウンロード", "📺 Download another URL"),
+    ("🎬 品質を変えてダウンロードし直す", "🎬 Download again in a different quality"),
+    ("終了", "Quit"),
+    ("次の操作", "Next"),
+    ("重複の処理", "Duplicates"),
+    ("📺 YouTubeのURL", "📺 YouTube URL"),
+    ("🎬 ダウンロード品質", "🎬 Quality"),
+    ("📋 プレイリスト内の動画です", "📋 This video is part of a playlist"),
+    ("そのまま", "Keep"),
+    ("ハードリンクにする（容量を節約）", "Hard-link (saves space)"),
+    ("重複を削除", "Delete duplicates"),
+    ("番号を付けて保存", "Save with a number"),
+    ("上書き", "Overwrite"),
+    ("スキップ", "Skip"),
+    ("この動画のみ", "This video only"),
+    ("プレイリスト全体", "Whole playlist"),
+    ("URLの形式ではありません（https:// で始まるURLを入力してください）", "Not a URL (enter a URL starting with https://)"),
+    ("yt-dlpが見つかりません。Dockerコンテナ内で実行するか、yt-dlpをインストールしてください", "yt-dlp was not found. Run inside the Docker container or install yt-dlp"),
+    ("Cookie検出エラー: {}", "Cookie detection error: {}"),
+    ("ダウンロードエラー: {}", "Download error: {}"),
+    ("yt-dlpプロセスエラー: {}", "yt-dlp process error: {}"),
+    ("進捗パースエラー: {}", "Progress parse error: {}"),
+    ("アップロードエラー: {}", "Upload error: {}"),
+    ("設定ファイルエラー: {}", "Config file error: {}"),
+    ("IO エラー: {}", "IO error: {}"),
+    ("ダウンロード履歴のエラー: {}", "Download history error: {}"),
+    ("ダウンロードを中止しました（未処理: {}件）", "Download cancelled ({} not processed)"),
+    ("その他のエラー: {}", "Error: {}"),
+    ("通知での進捗表示（--toast）はWindowsのみ対応しています", "Progress notifications (--toast) are only supported on Windows"),
+    ("一時停止", "Pause"),
+    ("再開", "Resume"),
+    ("中止", "Cancel"),
+    ("通知を表示できません: {}", "Could not show the notification: {}"),
+    ("ダウンロードが完了しました", "Download completed"),
+    ("ダウンロードに失敗しました: {}", "Download failed: {}"),
+    ("警告: スリープを防止できません（--keep-awake）", "Warning: could not prevent sleep (--keep-awake)"),
+    ("警告: 音声のみのダウンロードでは --resolution は無視されます", "Warning: --resolution is ignored for audio-only downloads"),
+    ("🌐 サイトごとの設定を使用します: {}", "🌐 Using the site settings for {}"),
+    ("警告: 音声のみのダウンロードでは --vcodec は無視されます", "Warning: --vcodec is ignored for audio-only downloads"),
+    ("警告: 映像付きのダウンロードでは --audio-format は無視されます", "Warning: --audio-format is ignored for downloads with video"),
+    ("   視聴できない動画の代わりのファイルを削除しました: {}", "   Removed the placeholder for the unavailable video: {}"),
+    ("   📄 視聴できない動画の代わりのファイルを作成しました: {}", "   📄 Created a placeholder for the unavailable video: {}"),
+    ("⚠️  視聴できない動画の代わりのファイルを作成できません: {}", "⚠️  Could not create a placeholder for the unavailable video: {}"),
+    ("\n=== URLごとの結果（{}件） ===", "\n=== Results by URL ({}) ==="),
+    ("（{}ファイル）", " ({} files)"),
+    ("成功 {}件 / 失敗 {}件、合計 {}ファイル", "{} succeeded / {} failed, {} files in total"),
+    ("警告: --format-string を指定したため --resolution / --vcodec は無視されます", "Warning: --resolution / --vcodec are ignored because --format-string is given"),
+    ("表示言語を保存しました: {}（一覧: ytdl lang list）", "Saved the display language: {} (list: ytdl lang list)"),
+    ("警告: 表示言語を保存できません: {}", "Warning: could not save the display language: {}"),
+    ("\n変更: --lang <コード>、環境変数 YTDL_LANG、または設定ファイルの lang = \"<コード>\"", "\nChange with --lang <code>, the YTDL_LANG environment variable, or lang = \"<code>\" in the config file"),
+    ("🔖 前回の続き（{}番目）から再開します", "🔖 Resuming from item {} where the last run stopped"),
+    ("📚 {}本のプレイリストを{}本ずつ{}回に分けてダウンロードします", "📚 Downloading the {}-item playlist in chunks of {} ({} chunks)"),
+    ("\n📦 [{}/{}] {}〜{}番目", "\n📦 [{}/{}] Items {}-{}"),
+    ("{}〜{}番目: {}", "Items {}-{}: {}"),
+    ("{}回中{}回の区切りでダウンロードに失敗しました（次回は失敗した区切りから再開します）", "{1} of {0} chunks failed (the next run resumes from the first failed chunk)"),
+    ("警告: 音声のみのダウンロードでは --container は無視されます", "Warning: --container is ignored for audio-only downloads"),
+    ("警告: WebMにはH.264の映像を格納できないため、変換に失敗する場合があります（--vcodec vp9 / av1 を推奨）", "Warning: WebM cannot hold H.264 video, so remuxing may fail (--vcodec vp9 / av1 is recommended)"),
+    ("ファイルが削除されている{}件をダウンロードし直しますか？", "Download the {} deleted files again?"),
+    ("警告: ダウンロード履歴を確認できません: {}", "Warning: Could not check the download history: {}"),
+    ("\n🗑 履歴ではダウンロード済みですが、ファイルが削除されている動画: {}件", "\n🗑 Downloaded before according to the history, but the files were deleted: {}"),
+    ("💡 アーカイブに記録済みのためスキップしました（ダウンロードし直す場合は --redownload-missing）", "💡 Skipped because they are recorded in the archive (use --redownload-missing to download them again)"),
+    ("🔁 アーカイブから外してダウンロードし直します", "🔁 Removing them from the archive and downloading again"),
+    ("⏳ ダウンロードできるようになるまで{}ごとに確認します（Ctrl+Cで終了）", "⏳ Checking every {} until the video can be downloaded (Ctrl+C to stop)"),
+    ("⏳ まだダウンロードできません: {}", "⏳ Not available yet: {}"),
+    ("👋 確認を終了しました", "👋 Stopped checking"),
+    ("🎉 ダウンロードできるようになりました", "🎉 The video is now available"),
+    ("{}時間", "{} h"),
+    ("{}分", "{} min"),
+    ("{}秒", "{} s"),
+    ("アルバムフォルダが不明です: {}", "Unknown album folder: {}"),
+    ("チャプターが見つからないため、曲ごとに分割できませんでした", "Could not split into tracks because the video has no chapters"),
+    ("💿 {}曲に分割しました: {}", "💿 Split into {} tracks: {}"),
+    ("ffmpegの起動失敗: {}", "Failed to start ffmpeg: {}"),
+    ("タグを書き込めませんでした: {}", "Could not write tags: {}"),
+    ("429（リクエストが多すぎる）を受けたため下げます", "Lowering after a 429 (Too Many Requests)"),
+    ("速度が落ちたため戻します", "Reverting because the speed dropped"),
+    ("速度が落ちていないため上げます", "Raising because the speed did not drop"),
+    ("{}（断片: {} → {}、同時ダウンロード: {} → {}本）", "{} (fragments: {} → {}, parallel downloads: {} → {})"),
+    ("ダウンロード処理が異常終了しました", "The download thread terminated abnormally"),
+    ("続きの位置を読み込めません: {}: {}", "Could not read the playlist checkpoints: {}: {}"),
+    ("続きの位置を保存できません: {}", "Could not save the playlist checkpoints: {}"),
+    ("プレイリスト開始位置({})が終了位置({})より大きいです", "The playlist start ({}) is after the end ({})"),
+    ("プレイリスト位置は1から始まります", "Playlist positions start at 1"),
+    ("--parallel は1以上を指定してください", "--parallel must be at least 1"),
+    ("--rclone-move には --rclone-dest または --s3-dest が必要です", "--rclone-move requires --rclone-dest or --s3-dest"),
+    ("警告: 出力ディレクトリ '{}' が存在しません。自動作成します。", "Warning: The output directory '{}' does not exist. It will be created."),
+    ("=== ダウンロード設定 ===", "=== Download settings ==="),
+    ("URL: {}件", "URLs: {}"),
+    ("プロファイル: {}", "Profile: {}"),
+    ("品質: {} ({})", "Quality: {} ({})"),
+    ("音声の形式: {}", "Audio format: {}"),
+    ("コンテナ: {}", "Container: {}"),
+    ("音質: {}", "Audio quality: {}"),
+    ("サイズ上限: {}", "Size limit: {}"),
+    ("出力先: {}", "Output: {}"),
+    ("出力先: exeと同じフォルダ", "Output: the folder of the executable"),
+    ("Cookie: {} ブラウザから自動検出", "Cookies: detected from {}"),
+    ("Cookie: 使用しない（公開動画のみ）", "Cookies: not used (public videos only)"),
+    ("Cookieスナップショット: 有効", "Cookie snapshot: enabled"),
+    ("プレイリスト: 全体", "Playlist: whole list"),
+    (" (開始: {})", " (start: {})"),
+    (" (終了: {})", " (end: {})"),
+    ("同時ダウンロード: {}本", "Parallel downloads: {}"),
+    ("字幕: 動画に埋め込む", "Subtitles: embed in the video"),
+    ("字幕: ダウンロードする", "Subtitles: download"),
+    ("ストーリーボード: 保存する", "Storyboards: save"),
+    ("ヒートマップ: 保存する", "Heatmap: save"),
+    ("メタデータ: 保存する", "Metadata: save"),
+    ("アルバム: チャプターごとに曲を分割する", "Album: split tracks by chapter"),
+    ("無音: 先頭・末尾を削除する", "Silence: trim the start and end"),
+    ("変換コピー: {}（同時に{}件まで）", "Encoded copy: {} (up to {} at a time)"),
+    ("メタデータの言語: {}", "Metadata language: {}"),
+    ("ファイル名: {}", "File names: {}"),
+    ("同名ファイル: {}", "Existing files: {}"),
+    ("元の動画", "original video"),
+    ("フラグメント", "fragments"),
+    ("中間ファイル: {}を残す", "Intermediate files: keep {}"),
+    ("・", ", "),
+    ("帯域制限: {}", "Rate limit: {}"),
+    ("断片の同時ダウンロード: {}", "Concurrent fragments: {}"),
+    ("同時ダウンロード数: 自動調整", "Concurrency: auto-tuned"),
+    ("リトライ回数: {}", "Retries: {}"),
+    ("失敗した動画のやり直し: {}回", "Retry failed videos: {} times"),
+    ("中断したダウンロード: 続きから再開する", "Interrupted downloads: resume"),
+    ("中断したダウンロード: 最初からやり直す", "Interrupted downloads: start over"),
+    ("省電力・従量制接続: 一時停止する", "Battery / metered connection: pause"),
+    ("許可するチャンネル: {}", "Allowed channels: {}"),
+    ("ライセンス: クリエイティブ・コモンズのみ", "License: Creative Commons only"),
+    ("振り分けルール: {}件", "Routing rules: {}"),
+    ("移動", "move"),
+    ("コピー", "copy"),
+    ("クラウド転送: {} ({})", "Cloud upload: {} ({})"),
+    ("MQTT配信: {} (トピック: {}/...)", "MQTT: {} (topic: {}/...)"),
+    ("状態ソケット: {}", "Status socket: {}"),
+    ("レポート: {}", "Report: {}"),
+    ("yt-dlpの環境変数: {}", "yt-dlp environment variables: {}"),
+    ("モード: ドライラン（ダウンロードしない）", "Mode: dry run (no downloads)"),
+    ("モード: フォルダ監視 ({})", "Mode: watching a folder ({})"),
+    ("モード: ダウンロードできるまで確認 ({}、{}秒ごと)", "Mode: wait until available ({}, every {} s)"),
+    ("確認の間隔は30秒以上を指定してください: {}", "The interval must be at least 30 seconds: {}"),
+    ("確認の間隔が不正です: {}（例: 30s、10m、1h）", "Invalid interval: {} (e.g. 30s, 10m, 1h)"),
+    ("環境変数の指定が不正です: {}（例: ALL_PROXY=socks5://127.0.0.1:1080）", "Invalid environment variable: {} (e.g. ALL_PROXY=socks5://127.0.0.1:1080)"),
+    ("   チャンネル: {}", "   Channel: {}"),
+    ("   再生時間: {}", "   Duration: {}"),
+    ("   アップロード日: {}", "   Upload date: {}"),
+    ("   再生回数: {}", "   Views: {}"),
+    ("📋 {}件（{}）", "📋 {} entries ({})"),
+    ("🗑  {}件の記録を削除しました（{}）", "🗑  Removed {} entries ({})"),
+    ("警告: 記録されていないIDがあります", "Warning: Some IDs were not in the archive"),
+    ("{}前", "{} ago"),
+    ("{}（{}前に追加）", "{} (added {} ago)"),
+    ("     ✗ 前回失敗: {}", "     ✗ Last attempt failed: {}"),
+    ("警告: 追加済みです: {}", "Warning: Already queued: {}"),
+    ("📋 キュー: {}件（ytdl queue run でダウンロード）", "📋 Queue: {} URLs (download with ytdl queue run)"),
+    ("↕  {}番目を{}番目に移動しました", "↕  Moved item {} to position {}"),
+    ("🗑  {}件を削除しました", "🗑  Removed {} URLs"),
+    ("🗑  {}件の履歴を削除しました", "🗑  Removed {} history entries"),
+    ("📋 {}件", "📋 {} entries"),
+    ("（削除済み）", "(deleted)"),
+    ("{}日", "{} d"),
+    ("プロファイル '{}' が設定ファイルにありません（定義済み: {}）", "Profile '{}' is not in the config file (defined: {})"),
+    ("なし", "none"),
+    ("サポートされていないブラウザ: {}", "Unsupported browser: {}"),
+    ("🔑 macOSのキーチェーン確認ダイアログ（\"{}\"）が表示される場合があります。", "🔑 macOS may show a keychain dialog (\"{}\")."),
+    ("   「常に許可」を選択してください。0%のまま進まない場合はダイアログを確認してください。", "   Choose \"Always Allow\". If progress stays at 0%, check for the dialog."),
+    ("警告: キーチェーンに \"{}\" が見つかりません。{}のCookieを復号できない可能性があります", "Warning: \"{}\" was not found in the keychain. The {} cookies may not be decryptable"),
+    ("警告: D-Busセッションが見つからないため、キーリングから{}のCookie復号キーを取得できません", "Warning: No D-Bus session was found, so the {} cookie key cannot be read from the keyring"),
+    ("   キーリングを使わない復号（basictext）で続行します", "   Continuing with keyring-less decryption (basictext)"),
+    ("   FirefoxのCookieに切り替えます（--cookies firefox）", "   Switching to Firefox cookies (--cookies firefox)"),
+    ("   ヒント: --cookies firefox を使用するとキーチェーンを経由しません", "   Hint: --cookies firefox does not use the keychain"),
+    ("警告: {}のCookieファイルが見つかりません: {}", "Warning: The {} cookie file was not found: {}"),
+    ("公開動画のみダウンロード可能です。", "Only public videos can be downloaded."),
+    ("サポートされていないOSです", "Unsupported OS"),
+    ("LOCALAPPDATA環境変数が設定されていません", "The LOCALAPPDATA environment variable is not set"),
+    ("APPDATA環境変数が設定されていません", "The APPDATA environment variable is not set"),
+    ("HOME環境変数が設定されていません", "The HOME environment variable is not set"),
+    ("Firefoxの cookies.sqlite が見つかりません: {}", "Firefox cookies.sqlite was not found: {}"),
+    ("不正なCookieパス: {}", "Invalid cookie path: {}"),
+    ("scpを実行できません: {}", "Could not run scp: {}"),
+    ("{} からcookies.txtを取得できませんでした", "Could not fetch cookies.txt from {}"),
+    ("警告: 取得したファイルがNetscape形式のcookies.txtではない可能性があります", "Warning: The fetched file may not be a Netscape-format cookies.txt"),
+    ("Cookieデータベースをコピーできません（ブラウザを終了するか、管理者として実行してください）: {}", "Could not copy the cookie database (close the browser or run as administrator): {}"),
+    ("Cookieデータベースをコピーできません: {}", "Could not copy the cookie database: {}"),
+    ("=== 環境 ===", "=== Environment ==="),
+    ("不明（{}）", "unknown ({})"),
+    ("日時: {}（UNIX時間）", "Time: {} (Unix time)"),
+    ("終了状態: {}", "Exit status: {}"),
+    ("\n=== コマンド ===\n{}", "\n=== Command ===\n{}"),
+    ("\n=== yt-dlpの{}（最後の{}行まで） ===", "\n=== yt-dlp {} (last {} lines) ==="),
+    ("🔍 {} の重複ファイルを確認中...", "🔍 Looking for duplicate files in {}..."),
+    ("重複ファイルはありません", "No duplicate files"),
+    ("\n重複: {}組 / 削減: {}", "\nDuplicates: {} groups / saved: {}"),
+    ("ハードリンクを作成できません（別のドライブの可能性）: {}", "Could not create a hard link (possibly on another drive): {}"),
+    ("警告: {}での変換に失敗したため、ソフトウェアで変換します: {}", "Warning: Encoding with {} failed, falling back to software: {}"),
+    ("変換に失敗しました: {}", "Encoding failed: {}"),
+    ("ソフトウェア", "software"),
+    ("絵文字を削除", "Remove emoji"),
+    ("Windowsで使える名前にする", "Make names valid on Windows"),
+    ("ローマ字・ASCIIに変換", "Transliterate to ASCII"),
+    ("ヒートマップを保存できません: {}", "Could not save the heatmap: {}"),
+    ("警告: フック {} が失敗しました: {}", "Warning: Hook {} failed: {}"),
+    ("フック {} が失敗したため中止しました: {}", "Aborted because hook {} failed: {}"),
+    ("起動できません: {}", "could not start: {}"),
+    ("終了コード {}", "exit code {}"),
+    ("{}秒でタイムアウトしました", "timed out after {} s"),
+    ("動画 {} のログはありません（{}日より前のログは削除されます）", "No log for video {} (logs older than {} days are deleted)"),
+    ("ログファイルを開けません: {}: {}", "Could not open the log file: {}: {}"),
+    ("\n⏱  処理時間の内訳:", "\n⏱  Time breakdown:"),
+    ("合計", "Total"),
+    ("その他の起動処理", "Other startup"),
+    ("yt-dlpの更新", "yt-dlp update"),
+    ("yt-dlpの確認", "yt-dlp check"),
+    ("Cookieの確認", "Cookie check"),
+    ("メタデータ取得", "Metadata"),
+    ("ダウンロード", "Download"),
+    ("後処理（結合・変換）", "Post-processing (merge/convert)"),
+    ("クラウド転送", "Cloud upload"),
+    ("高速・高品質な動画一括ダウンロードツール", "Fast, high-quality batch video downloader"),
+    ("シンプル版 - Cookie不要", "Simple edition - no cookies needed"),
+    ("動画情報のパースに失敗: {}", "Failed to parse the video info: {}"),
+    ("言語コードが不正です: {}（例: ja、en、pt-BR）", "Invalid language code: {} (e.g. ja, en, pt-BR)"),
+    ("MQTTブローカー({})に接続できません: {}", "Could not connect to the MQTT broker ({}): {}"),
+    ("MQTTブローカーが接続を拒否しました（コード: {}）", "The MQTT broker refused the connection (code: {})"),
+    ("{} に書き込めないため", "{} is not writable"),
+    ("{}を起動できません: {}", "Could not start {}: {}"),
+    ("USERPROFILE環境変数が設定されていません", "The USERPROFILE environment variable is not set"),
+    ("警告: シグナルハンドラを登録できません: {}", "Warning: Could not register the signal handler: {}"),
+    ("\n⏸  ダウンロードを一時停止しました", "\n⏸  Download paused"),
+    ("\n▶  ダウンロードを再開しました", "\n▶  Download resumed"),
+    ("不明", "unknown"),
+    ("進捗率のパースに失敗", "Failed to parse the progress"),
+    ("サイズの指定が不正です: {}", "Invalid size: {}"),
+    ("サイズの単位が不正です: {}（K, M, G などを指定）", "Invalid size unit: {} (use K, M, G, ...)"),
+    ("サイズには0より大きい値を指定してください", "The size must be greater than 0"),
+    ("全体", "Total"),
+    ("最高画質（4K対応）", "Best video (up to 4K)"),
+    ("バランス（1080p）", "Balanced (1080p)"),
+    ("最高音質（音声のみ）", "Best audio (audio only)"),
+    ("最低画質（プレビュー用）", "Lowest video (for previews)"),
+    ("最小容量", "Smallest size"),
+    ("不明な解像度: {}（使用可能: 2160, 1440, 1080, 720, 480）", "Unknown resolution: {} (available: 2160, 1440, 1080, 720, 480)"),
+    ("不明な品質プリセット: {}（使用可能: {}）", "Unknown quality preset: {} (available: {})"),
+    ("キューを読み込めません: {}: {}", "Could not read the queue: {}: {}"),
+    ("キューを保存できません: {}", "Could not save the queue: {}"),
+    ("位置 {} の動画はありません（キューは{}件）", "No item at position {} (the queue has {})"),
+    ("⏸  残りのURLは次回の `ytdl queue run` で処理します", "⏸  The remaining URLs will be processed by the next `ytdl queue run`"),
+    ("\n📥 {}（キューの残り: {}件）", "\n📥 {} ({} left in the queue)"),
+    ("\n📋 キュー: 完了 {}件 / 失敗 {}件", "\n📋 Queue: {} completed / {} failed"),
+    ("{}件のURLのダウンロードに失敗しました（キューに残しています）", "{} URLs failed to download (kept in the queue)"),
+    ("{} での転送にはrcloneが必要ですが、見つかりません。rcloneをインストールしてください（https://rclone.org/install/）", "{} needs rclone for uploading, but it was not found. Please install rclone (https://rclone.org/install/)"),
+    ("rcloneを実行できません", "Could not run rclone"),
+    ("ファイルが見つからないか空です: {}", "The file is missing or empty: {}"),
+    ("rcloneの起動失敗: {}", "Failed to start rclone: {}"),
+    ("{} へ転送中", "Uploading to {}"),
+    ("{} へ転送しました", "Uploaded to {}"),
+    ("転送失敗", "Upload failed"),
+    ("rcloneがエラーで終了しました", "rclone exited with an error"),
+    ("転送先は s3://bucket/prefix の形式で指定してください: {}", "Specify the destination as s3://bucket/prefix: {}"),
+    ("バケット名がありません: {}", "The bucket name is missing: {}"),
+    ("転送 {} / {}", "Upload {} / {}"),
+    ("レポートを作成できません: {}", "Could not write the report: {}"),
+    ("通信エラー", "network error"),
+    ("年齢制限", "age restriction"),
+    ("視聴できない動画", "unavailable video"),
+    ("その他のエラー", "other error"),
+    ("ルールに '->' がありません: {}", "The rule has no '->': {}"),
+    ("振り分け先が空です: {}", "The destination is empty: {}"),
+    ("ルールに演算子がありません: {}", "The rule has no operator: {}"),
+    ("未対応のフィールド: {}（duration, uploader, channel, title が使用可能）", "Unsupported field: {} (use duration, uploader, channel or title)"),
+    ("再生時間の値が不正です: {}", "Invalid duration: {}"),
+    ("{} には大小比較を使用できません", "{} cannot be compared with < or >"),
+    ("\n🛑 終了要求を受け付けました。実行中のダウンロードの完了を待ちます（最大{}秒）", "\n🛑 Shutdown requested. Waiting for the running download to finish (up to {} s)"),
+    ("\n🛑 待機時間を過ぎたため、ダウンロードを中断します", "\n🛑 The grace period has passed, stopping the download"),
+    ("親プロセス(PID {})が見つかりません", "The parent process (PID {}) was not found"),
+    ("\n🛑 親プロセス(PID {})が終了したため、ダウンロードを中止します", "\n🛑 The parent process (PID {}) exited, cancelling the download"),
+    ("無音を検出できませんでした: {}", "Could not detect silence: {}"),
+    ("無音を削除できませんでした: {}", "Could not remove silence: {}"),
+    ("{}: このOSではソケットのパスは指定できません。\"127.0.0.1:ポート番号\" を指定してください", "{}: Socket paths are not supported on this OS. Specify \"127.0.0.1:port\""),
+    ("状態取得用のソケット({})を作成できません: {}", "Could not create the status socket ({}): {}"),
+    ("yt-dlpの起動失敗: {}", "Failed to start yt-dlp: {}"),
+    ("ストーリーボードを保存できませんでした: {}", "Could not save the storyboards: {}"),
+    ("\n🔋 {}のため、ダウンロードを一時停止します", "\n🔋 Pausing the download: {}"),
+    ("\n🔌 電源・回線の制約が解除されました", "\n🔌 Power and network restrictions lifted"),
+    ("バッテリー駆動中", "on battery"),
+    ("従量制接続", "metered connection"),
+    ("✅ yt-dlpを最新版に更新しました（pip経由）", "✅ Updated yt-dlp to the latest version (via pip)"),
+    ("✅ yt-dlpを最新版に更新しました", "✅ Updated yt-dlp to the latest version"),
+    ("⚠️ yt-dlpの自動更新をスキップしました（手動更新が必要な場合があります）", "⚠️ Skipped the yt-dlp auto-update (a manual update may be needed)"),
+    ("📄 {} から{}件のURLを読み込みました", "📄 Read {1} URLs from {0}"),
+    ("ファイルにURLが見つかりません: {}", "No URLs found in the file: {}"),
+    ("署名の抽出に失敗", "signature extraction failed"),
+    ("速度制限（スロットリング）", "throttling"),
+    ("一部のフォーマットが取得できない", "some formats are missing"),
+    ("ffmpegが見つからない", "ffmpeg not found"),
+    ("その他の警告", "other warnings"),
+    ("yt-dlpを最新版に更新してください（yt-dlp -U）", "Update yt-dlp to the latest version (yt-dlp -U)"),
+    ("--limit-rate を指定するか、時間をおいて再実行してください", "Use --limit-rate or retry later"),
+    ("ffmpegをインストールしてください（映像と音声の結合・音声変換に必要です）", "Install ffmpeg (needed to merge video and audio and to convert audio)"),
+    ("--verbose で詳細を確認してください", "Check the details with --verbose"),
+    ("\n⚠️  警告のまとめ:", "\n⚠️  Warning summary:"),
+    ("👀 {} を監視しています（Ctrl+Cで終了）", "👀 Watching {} (Ctrl+C to stop)"),
+    ("👋 フォルダの監視を終了しました", "👋 Stopped watching the folder"),
+    ("\n📄 {} を処理します", "\n📄 Processing {}"),
+    ("⏸  {} は次回の起動時に処理します", "⏸  {} will be processed on the next start"),
+    ("警告: {} を移動できません: {}", "Warning: Could not move {}: {}"),
+    ("MQTTブローカーの指定が不正です: {}", "Invalid MQTT broker: {}"),
+    ("品質 '{}' のフォーマット（{}）には解像度の上限を指定できません", "A resolution limit cannot be applied to the format of quality '{}' ({})"),
+    ("{}、{}p以下", "{}, up to {}p"),
+    ("ytdl watch-dir ではURLを指定できません（監視フォルダにURLファイルを置いてください）", "ytdl watch-dir does not take URLs (put URL files in the watched folder)"),
+    ("ytdl watch-dir では --dry-run を使用できません", "--dry-run cannot be used with ytdl watch-dir"),
+    ("警告: 一時停止の要求を受け付けられません: {}", "Warning: Cannot accept pause requests: {}"),
+    ("プロセスIDが不正です: {}", "Invalid process ID: {}"),
+    ("プロセス {} に接続できません: {}", "Cannot connect to process {}: {}"),
+    ("このOSでは外部からの一時停止に対応していません", "Pausing from outside is not supported on this OS"),
+    ("⏸  プロセス {} に一時停止を要求しました", "⏸  Requested process {} to pause"),
+    ("▶  プロセス {} に再開を要求しました", "▶  Requested process {} to resume"),
+];
@@ -1,72 +1,440 @@
-use std::io::{self, Write};
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{BasicHistory, Confirm, Input, Select};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 
-use crate::quality::QualityPreset;
+use crate::cli::Cli;
+use crate::collision::CollisionPolicy;
+use crate::config::Config;
+use crate::dedupe::DedupeAction;
+use crate::i18n::{self, Lang};
+use crate::paths;
+use crate::quality::QualityProfile;
+use crate::youtube_url::{MixedUrl, PlaylistChoice};
+use crate::t;
+
+/// 1回の入力の結果
+enum Prompt<T> {
+    /// 入力値
+    Answer(T),
+    /// 前の質問に戻る（Esc）
+    Back,
+    /// 中止（URLを空欄のままEnter、または "q"）
+    Cancel,
+}
+
+/// インタラクティブモードの質問
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Url,
+    Quality,
+    Playlist,
+    Subtitle,
+}
+
+/// ダウンロード完了後のメニューの項目
+#[derive(Debug, Clone, Copy)]
+enum MenuItem {
+    OpenFolder,
+    Play,
+    Another,
+    ChangeQuality,
+    Quit,
+}
+
+/// ダウンロード完了後の選択
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfterDownload {
+    /// 別のURLをダウンロード（質問をやり直した設定で）
+    Another,
+    /// 同じURLを別の品質でダウンロードし直す（保存済みのファイルは上書き）
+    Redownload,
+    /// 終了
+    Quit,
+}
 
 /// インタラクティブモードでユーザー入力を取得
 pub struct InteractiveMode;
 
 impl InteractiveMode {
-    /// URLを入力
-    pub fn ask_url() -> io::Result<String> {
-        println!("\n📺 YouTubeのURLを入力してください:");
-        println!("   例: https://www.youtube.com/watch?v=dQw4w9WgXcQ");
-        print!("\nURL: ");
-        io::stdout().flush()?;
-
-        let mut url = String::new();
-        io::stdin().read_line(&mut url)?;
-        Ok(url.trim().to_string())
-    }
-
-    /// 品質プリセットを選択
-    pub fn ask_quality() -> io::Result<QualityPreset> {
-        println!("\n🎬 ダウンロード品質を選択してください:");
-        println!("   1. 最高画質（4K対応）- デフォルト");
-        println!("   2. 最高音質（MP3抽出）");
-        println!("   3. 最低画質（プレビュー用）");
-        println!("   4. 最小容量（容量優先）");
-        print!("\n選択 [1-4, Enter=1]: ");
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let choice = input.trim();
-
-        let quality = match choice {
-            "2" => QualityPreset::MaxAudio,
-            "3" => QualityPreset::MinVideo,
-            "4" => QualityPreset::MinSize,
-            _ => QualityPreset::MaxVideo, // デフォルト or "1"
+    /// 質問を順に行い、回答をCLI設定に反映
+    ///
+    /// 選択肢ではEscで前の質問に戻り、URL入力を空欄のままEnterすると中止します。
+    /// 中止した場合は `false` を返します。
+    pub fn run(cli: &mut Cli, profiles: &[QualityProfile]) -> io::Result<bool> {
+        if !Term::stderr().is_term() {
+            return Err(io::Error::other(
+                t!("端末から実行されていません。URLを引数で指定してください"),
+            ));
+        }
+        println!("{}", t!("   （Esc: 前の質問に戻る / URLを空欄でEnter: 中止）"));
+
+        let theme = ColorfulTheme::default();
+        let mut url_history = BasicHistory::new().max_entries(8).no_duplicates(true);
+        let mut history: Vec<Step> = Vec::new();
+        let mut step = Step::Url;
+
+        loop {
+            let next = match step {
+                Step::Url => match Self::ask_url(&theme, &mut url_history)? {
+                    Prompt::Answer(url) => {
+                        cli.url = vec![url];
+                        Some(Step::Quality)
+                    }
+                    Prompt::Back => continue,
+                    Prompt::Cancel => return Ok(false),
+                },
+                Step::Quality => match Self::ask_quality(&theme, profiles, &cli.quality)? {
+                    Prompt::Answer(quality) => {
+                        cli.quality = quality;
+                        // プレイリスト内の動画のURLなら、動画のみかプレイリスト全体か確認
+                        if cli.url.iter().any(|url| MixedUrl::parse(url).is_some()) {
+                            Some(Step::Playlist)
+                        } else {
+                            Some(Step::Subtitle)
+                        }
+                    }
+                    Prompt::Back => None,
+                    Prompt::Cancel => return Ok(false),
+                },
+                Step::Playlist => match Self::ask_playlist(&theme, cli.playlist)? {
+                    Prompt::Answer(choice) => {
+                        cli.playlist = choice == PlaylistChoice::WholeList;
+                        cli.this_video_only = choice == PlaylistChoice::ThisVideo;
+                        Some(Step::Subtitle)
+                    }
+                    Prompt::Back => None,
+                    Prompt::Cancel => return Ok(false),
+                },
+                Step::Subtitle => {
+                    match Self::confirm(&theme, t!("💬 字幕もダウンロードしますか？"), cli.download_subtitle)? {
+                        Prompt::Answer(subtitle) => {
+                            cli.download_subtitle = subtitle;
+                            return Ok(true);
+                        }
+                        Prompt::Back => None,
+                        Prompt::Cancel => return Ok(false),
+                    }
+                }
+            };
+
+            match next {
+                Some(next) => {
+                    history.push(step);
+                    step = next;
+                }
+                None => {
+                    if let Some(previous) = history.pop() {
+                        step = previous;
+                    }
+                }
+            }
+        }
+    }
+
+    /// ダウンロード完了後のメニュー（フォルダを開く・再生は選択後にメニューへ戻る）
+    ///
+    /// 別のURL・品質の変更を選んだ場合は、回答をCLI設定に反映して返します。
+    /// 端末でない場合は終了します。
+    pub fn after_download(
+        cli: &mut Cli,
+        profiles: &[QualityProfile],
+        files: &[PathBuf],
+        output_dir: &Path,
+    ) -> io::Result<AfterDownload> {
+        if !Term::stderr().is_term() {
+            return Ok(AfterDownload::Quit);
+        }
+        let theme = ColorfulTheme::default();
+        // 移動・削除された（クラウドへ転送した）ファイルは再生できない
+        let playable = files.iter().rev().find(|file| file.exists());
+
+        loop {
+            let mut items = vec![(MenuItem::OpenFolder, t!("📁 保存先のフォルダを開く"))];
+            if playable.is_some() {
+                items.push((MenuItem::Play, t!("▶ ダウンロードしたファイルを再生")));
+            }
+            items.extend([
+                (MenuItem::Another, t!("📺 別のURLをダウンロード")),
+                (MenuItem::ChangeQuality, t!("🎬 品質を変えてダウンロードし直す")),
+                (MenuItem::Quit, t!("終了")),
+            ]);
+            let labels: Vec<&str> = items.iter().map(|(_, label)| *label).collect();
+
+            let selection = Select::with_theme(&theme)
+                .with_prompt(t!("次の操作"))
+                .items(&labels)
+                .default(0)
+                .interact_opt()
+                .map_err(into_io_error)?;
+            let Some(index) = selection else {
+                return Ok(AfterDownload::Quit);
+            };
+
+            match items[index].0 {
+                MenuItem::OpenFolder => open(output_dir),
+                MenuItem::Play => {
+                    if let Some(file) = playable {
+                        open(file);
+                    }
+                }
+                MenuItem::Another => {
+                    return Ok(if Self::run(cli, profiles)? {
+                        AfterDownload::Another
+                    } else {
+                        AfterDownload::Quit
+                    });
+                }
+                MenuItem::ChangeQuality => {
+                    // Escの場合はメニューに戻る
+                    if let Prompt::Answer(quality) = Self::ask_quality(&theme, profiles, &cli.quality)? {
+                        cli.quality = quality;
+                        return Ok(AfterDownload::Redownload);
+                    }
+                }
+                MenuItem::Quit => return Ok(AfterDownload::Quit),
+            }
+        }
+    }
+
+    /// 表示する言語を決めて設定（`--lang` / YTDL_LANG > 設定ファイルの `lang` > 初回の確認 > 環境変数 LANG）
+    ///
+    /// 設定ファイルに `lang` がなく端末から実行した場合は、最初に一度だけ言語を選んでもらい設定ファイルに保存します。
+    /// 設定ファイルを読み込めなかった場合（`config` がNone）は確認しません。
+    pub fn init_lang(specified: Option<Lang>, config: Option<&Config>) {
+        if let Some(lang) = specified.or(config.and_then(|config| config.lang)) {
+            i18n::set_lang(lang);
+            return;
+        }
+        let Some(lang) = config.and_then(|_| Self::ask_lang()) else {
+            i18n::set_lang(Lang::from_env());
+            return;
         };
+        i18n::set_lang(lang);
+        match Config::save_lang(lang) {
+            Ok(path) => println!("{}", t!("表示言語を保存しました: {}（一覧: ytdl lang list）", path.display())),
+            Err(e) => eprintln!("{}", t!("警告: 表示言語を保存できません: {}", e)),
+        }
+    }
 
-        Ok(quality)
+    /// 表示する言語を選択（端末でない場合・Escの場合はNone）
+    ///
+    /// 言語が決まる前に表示するため、質問は各言語で併記します（`t!` は使えない）。
+    fn ask_lang() -> Option<Lang> {
+        if !Term::stderr().is_term() || !io::stdin().is_terminal() {
+            return None;
+        }
+        let names: Vec<&str> = Lang::ALL.iter().map(|lang| lang.native_name()).collect();
+        let default = Lang::ALL.iter().position(|lang| *lang == Lang::from_env()).unwrap_or(0);
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("表示言語 / Display language")
+            .items(&names)
+            .default(default)
+            .interact_opt()
+            .ok()
+            .flatten()
+            .map(|index| Lang::ALL[index])
     }
 
-    /// プレイリストかどうか確認
-    pub fn ask_playlist() -> io::Result<bool> {
-        // URLにplaylist=が含まれているか自動判定するので、ここでは確認のみ
-        println!("\n📋 プレイリスト全体をダウンロードしますか？");
-        print!("   [y/N]: ");
-        io::stdout().flush()?;
+    /// 途中までダウンロードしたファイルの続きから再開するか確認（端末でない場合は再開）
+    pub fn ask_resume(count: usize, size: &str) -> bool {
+        if !Term::stderr().is_term() {
+            return true;
+        }
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!(
+                "途中までダウンロードしたファイルがあります（{}件、{}）。続きから再開しますか？",
+                i18n::number(count), size
+            ))
+            .default(true)
+            .interact_opt()
+            .ok()
+            .flatten()
+            .unwrap_or(true)
+    }
+
+    /// 履歴ではダウンロード済みでもファイルが削除されている動画をダウンロードし直すか確認（端末でない場合はしない）
+    pub fn ask_redownload_missing(count: usize) -> bool {
+        if !Term::stderr().is_term() {
+            return false;
+        }
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("ファイルが削除されている{}件をダウンロードし直しますか？", i18n::number(count)))
+            .default(false)
+            .interact_opt()
+            .ok()
+            .flatten()
+            .unwrap_or(false)
+    }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let choice = input.trim().to_lowercase();
+    /// 重複ファイルの処理方法を選択（端末でない場合は何もしない）
+    pub fn ask_dedupe_action() -> DedupeAction {
+        if !Term::stderr().is_term() {
+            return DedupeAction::Keep;
+        }
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("重複の処理"))
+            .items(&[t!("そのまま"), t!("ハードリンクにする（容量を節約）"), t!("重複を削除")])
+            .default(0)
+            .interact_opt();
 
-        Ok(matches!(choice.as_str(), "y" | "yes" | "はい"))
+        match selection {
+            Ok(Some(1)) => DedupeAction::HardLink,
+            Ok(Some(2)) => DedupeAction::Delete,
+            _ => DedupeAction::Keep,
+        }
     }
 
-    /// 字幕をダウンロードするか確認
-    pub fn ask_subtitle() -> io::Result<bool> {
-        println!("\n💬 字幕もダウンロードしますか？");
-        print!("   [y/N]: ");
-        io::stdout().flush()?;
+    /// 保存先に同じ名前のファイルがある場合の扱いを選択（端末でない場合は番号を付ける）
+    pub fn ask_collision(existing: &Path) -> CollisionPolicy {
+        if !Term::stderr().is_term() {
+            return CollisionPolicy::Rename;
+        }
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("同じ名前のファイルがあります: {}", existing.display()))
+            .items(&[t!("番号を付けて保存"), t!("上書き"), t!("スキップ")])
+            .default(0)
+            .interact_opt();
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let choice = input.trim().to_lowercase();
+        match selection {
+            Ok(Some(1)) => CollisionPolicy::Overwrite,
+            Ok(Some(2)) => CollisionPolicy::Skip,
+            _ => CollisionPolicy::Rename,
+        }
+    }
+
+    /// URLを入力（URLでない入力は再入力、↑↓で入力履歴）
+    fn ask_url(
+        theme: &ColorfulTheme,
+        url_history: &mut BasicHistory,
+    ) -> io::Result<Prompt<String>> {
+        println!("{}", t!("\n   例: https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        let input: String = Input::with_theme(theme)
+            .with_prompt(t!("📺 YouTubeのURL"))
+            .allow_empty(true)
+            .history_with(url_history)
+            .validate_with(|input: &String| -> Result<(), &str> {
+                let input = input.trim();
+                if input.is_empty() || input == "q" || normalize_url(input).is_some() {
+                    Ok(())
+                } else {
+                    Err(t!("URLの形式ではありません（https:// で始まるURLを入力してください）"))
+                }
+            })
+            .interact_text()
+            .map_err(into_io_error)?;
 
-        Ok(matches!(choice.as_str(), "y" | "yes" | "はい"))
+        let input = input.trim();
+        Ok(match input {
+            // Enterのみの場合は中止
+            "" | "q" => Prompt::Cancel,
+            _ => normalize_url(input).map_or(Prompt::Back, Prompt::Answer),
+        })
     }
 
+    /// 品質プリセットを選択（選択したプリセット名を返す）
+    fn ask_quality(
+        theme: &ColorfulTheme,
+        profiles: &[QualityProfile],
+        current: &str,
+    ) -> io::Result<Prompt<String>> {
+        let items: Vec<&str> = profiles.iter().map(|p| p.display_description()).collect();
+        let default = profiles.iter().position(|p| p.name == current).unwrap_or(0);
+
+        let selection = Select::with_theme(theme)
+            .with_prompt(t!("🎬 ダウンロード品質"))
+            .items(&items)
+            .default(default)
+            .interact_opt()
+            .map_err(into_io_error)?;
+
+        Ok(match selection {
+            Some(index) => Prompt::Answer(profiles[index].name.clone()),
+            None => Prompt::Back,
+        })
+    }
+
+    /// プレイリスト内の動画のURLの扱いを選択
+    fn ask_playlist(theme: &ColorfulTheme, whole_list: bool) -> io::Result<Prompt<PlaylistChoice>> {
+        let selection = Select::with_theme(theme)
+            .with_prompt(t!("📋 プレイリスト内の動画です"))
+            .items(&[t!("この動画のみ"), t!("プレイリスト全体")])
+            .default(usize::from(whole_list))
+            .interact_opt()
+            .map_err(into_io_error)?;
+
+        Ok(match selection {
+            Some(0) => Prompt::Answer(PlaylistChoice::ThisVideo),
+            Some(_) => Prompt::Answer(PlaylistChoice::WholeList),
+            None => Prompt::Back,
+        })
+    }
+
+    /// y/N形式の確認
+    fn confirm(theme: &ColorfulTheme, prompt: &str, default: bool) -> io::Result<Prompt<bool>> {
+        let answer = Confirm::with_theme(theme)
+            .with_prompt(prompt)
+            .default(default)
+            .interact_opt()
+            .map_err(into_io_error)?;
+
+        Ok(match answer {
+            Some(answer) => Prompt::Answer(answer),
+            None => Prompt::Back,
+        })
+    }
+}
+
+/// 既定のアプリで開く（失敗した場合は警告のみ）
+fn open(path: &Path) {
+    if let Err(e) = paths::open_with_default_app(path) {
+        eprintln!("{}", t!("警告: {}", e));
+    }
+}
+
+fn into_io_error(error: dialoguer::Error) -> io::Error {
+    match error {
+        dialoguer::Error::IO(e) => e,
+    }
+}
+
+/// 入力されたURLを検証して正規化（スキーム省略時は https:// を補う）
+fn normalize_url(input: &str) -> Option<String> {
+    if input.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let url = if input.starts_with("http://") || input.starts_with("https://") {
+        input.to_string()
+    } else {
+        format!("https://{}", input)
+    };
+
+    let host = url.split_once("://")?.1.split(['/', '?', '#']).next()?;
+    if host.contains('.') && !host.starts_with('.') && !host.ends_with('.') {
+        Some(url)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://www.youtube.com/watch?v=abc").as_deref(),
+            Some("https://www.youtube.com/watch?v=abc")
+        );
+        assert_eq!(
+            normalize_url("youtu.be/abc").as_deref(),
+            Some("https://youtu.be/abc")
+        );
+        assert_eq!(normalize_url("1"), None);
+        assert_eq!(normalize_url("hello world"), None);
+        assert_eq!(normalize_url("https://"), None);
+    }
 }
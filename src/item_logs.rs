@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::error::{Result, YtdlError};
+use crate::paths;
+use crate::redact;
+use crate::t;
+
+/// この期間より古いログは削除
+const RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// ログファイルの拡張子
+const EXTENSION: &str = "log";
+
+/// 動画ごとのyt-dlpの出力（`ytdl logs <ID>` で表示）
+///
+/// 動画IDごとに1つのファイルへ、最後にダウンロードした時の出力を保存します。
+/// 1回のyt-dlpでプレイリスト全体をダウンロードした場合も、動画の開始ごとに振り分けます。
+pub struct ItemLogs {
+    dir: PathBuf,
+    files: HashMap<String, BufWriter<File>>,
+    /// 出力中の動画のID
+    current: Option<String>,
+    /// 最初の動画が始まる前の出力（その動画のログの先頭に書く）
+    pending: Vec<String>,
+}
+
+impl ItemLogs {
+    /// ログの保存先を作成（古いログは削除）
+    pub fn open() -> Result<Self> {
+        let dir = logs_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        remove_old_logs(&dir);
+        Ok(Self {
+            dir,
+            files: HashMap::new(),
+            current: None,
+            pending: Vec::new(),
+        })
+    }
+
+    /// 動画の処理を開始（以降の出力はこの動画のログに書く）
+    pub fn start(&mut self, id: &str) {
+        self.current = Some(id.to_string());
+        let pending = std::mem::take(&mut self.pending);
+        for line in pending {
+            self.write(id, &line);
+        }
+    }
+
+    /// 動画の処理が始まったか
+    pub fn has_started(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// yt-dlpの出力の1行を、処理中の動画のログに書く
+    pub fn line(&mut self, line: &str) {
+        match self.current.clone() {
+            Some(id) => self.write(&id, line),
+            None => self.pending.push(line.to_string()),
+        }
+    }
+
+    /// 指定した動画のログに書く（エラー行など、動画IDが分かる場合）
+    pub fn line_for(&mut self, id: &str, line: &str) {
+        // 開始前に失敗した動画（動画情報を取得できない場合など）
+        if self.current.is_none() {
+            self.start(id);
+        }
+        self.write(id, line);
+    }
+
+    fn write(&mut self, id: &str, line: &str) {
+        if !self.files.contains_key(id) {
+            // 前回のダウンロードのログは上書き
+            match File::create(self.dir.join(file_name(id))) {
+                Ok(file) => {
+                    self.files.insert(id.to_string(), BufWriter::new(file));
+                }
+                Err(e) => {
+                    tracing::warn!("ログを作成できません: {}: {}", id, e);
+                    return;
+                }
+            }
+        }
+        if let Some(file) = self.files.get_mut(id) {
+            let _ = writeln!(file, "{}", redact::redact_line(line));
+        }
+    }
+}
+
+/// ログの保存先（データディレクトリの "logs"）
+pub fn logs_dir() -> Result<PathBuf> {
+    Ok(paths::data_dir()?.join("logs"))
+}
+
+/// 動画のログを読み込む
+pub fn read(id: &str) -> Result<String> {
+    let path = logs_dir()?.join(file_name(id));
+    if !path.exists() {
+        return Err(YtdlError::Other(t!(
+            "動画 {} のログはありません（{}日より前のログは削除されます）",
+            id,
+            RETENTION.as_secs() / 86400
+        )));
+    }
+    Ok(std::fs::read_to_string(path)?)
+}
+
+/// 保存されているログの動画IDと更新日時（新しい順）
+pub fn list() -> Result<Vec<(String, SystemTime)>> {
+    let dir = logs_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut logs: Vec<(String, SystemTime)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != EXTENSION {
+                return None;
+            }
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((id, modified))
+        })
+        .collect();
+    logs.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    Ok(logs)
+}
+
+/// ログのファイル名（動画IDに使われない文字は "_" にする）
+fn file_name(id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}.{}", id, EXTENSION)
+}
+
+/// 保存期間を過ぎたログを削除（失敗しても無視）
+fn remove_old_logs(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let expired = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > RETENTION);
+        if expired {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("dQw4w9WgXcQ"), "dQw4w9WgXcQ.log");
+        assert_eq!(file_name("a_b-c"), "a_b-c.log");
+        assert_eq!(file_name("../etc/passwd"), "___etc_passwd.log");
+    }
+}
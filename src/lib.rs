@@ -0,0 +1,85 @@
+//! YouTube動画一括ダウンローダー（yt-dlpのラッパー）
+//!
+//! `ytdl` / `ytdl-simple` の本体です。他のRustプログラムからは
+//! [`DownloadRequest`] でダウンロードを組み立てて実行できます。
+//!
+//! ```no_run
+//! use ytdl::{DownloadRequest, QualityPreset};
+//!
+//! DownloadRequest::new("https://www.youtube.com/watch?v=dQw4w9WgXcQ")
+//!     .quality(QualityPreset::MaxAudio)
+//!     .output_dir("downloads")
+//!     .on_progress(|progress| println!("{:.1}%", progress.percent))
+//!     .run()?;
+//! # Ok::<(), ytdl::YtdlError>(())
+//! ```
+//!
+//! [`DownloadRequest::spawn`] でバックグラウンドで実行すると、
+//! [`DownloadHandle::cancel`] で実行中のyt-dlpごと中止できます。
+
+pub mod album;
+pub mod archive;
+pub mod auto_tune;
+pub mod availability;
+pub mod cancel;
+pub mod chunks;
+pub mod cli;
+pub mod collision;
+pub mod commands;
+pub mod config;
+pub mod cookie_detector;
+pub mod crash_report;
+pub mod dedupe;
+pub mod dock_badge;
+pub mod encode;
+pub mod error;
+pub mod events;
+pub mod filename;
+pub mod heatmap;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod interactive;
+pub mod item_logs;
+pub mod logging;
+pub mod metadata;
+pub mod mqtt;
+pub mod notification;
+pub mod paths;
+pub mod pause;
+pub mod placeholder;
+pub mod progress_parser;
+pub mod progress_style;
+pub mod quality;
+pub mod queue;
+pub mod rclone;
+pub mod redact;
+pub mod report;
+pub mod request;
+pub mod resume;
+pub mod retry_policy;
+pub mod routing;
+pub mod shutdown;
+pub mod silence;
+pub mod site;
+pub mod sleep_inhibitor;
+pub mod status_socket;
+pub mod storyboard;
+pub mod subtitles;
+pub mod system_monitor;
+pub mod taskbar;
+pub mod template;
+pub mod toast;
+pub mod updater;
+pub mod url_files;
+pub mod warnings;
+pub mod watch_dir;
+pub mod youtube_url;
+pub mod ytdlp_wrapper;
+
+pub use cancel::DownloadHandle;
+pub use error::{Result, YtdlError};
+pub use progress_parser::ProgressInfo;
+pub use quality::{AudioFormat, AudioQuality, QualityPreset};
+pub use request::DownloadRequest;
+pub use ytdlp_wrapper::YtdlpWrapper;
@@ -0,0 +1,299 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use dialoguer::console::{pad_str, Alignment};
+use tracing::field::Field;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::{self, FmtSpan, Writer};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// ローテーションで残す古いログファイルの数（"<名前>.1" 〜 "<名前>.5"）
+const LOG_KEEP: usize = 5;
+
+/// 処理段階ごとのログ出力を初期化
+///
+/// 起動・更新確認・メタデータ取得・ダウンロード・後処理などの各段階を
+/// `tracing` のspanとして記録し、終了時に所要時間（time.busy）を出力します。
+/// `-vv` で標準エラー出力に、`--log-file` でファイルに出力します。
+/// ファイルにはyt-dlpの出力（進捗の行を除く）も時刻付きで記録し、
+/// `log_max_size` バイトを超えたら古いログを "<名前>.1" に移します。
+/// `timings` がtrueなら段階ごとの所要時間を集計し、[`print_timings`] で表示できます。
+pub fn init(verbosity: u8, log_file: Option<&Path>, log_max_size: u64, timings: bool) -> Result<()> {
+    let stderr_level = if verbosity >= 2 {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::OFF
+    };
+    let stderr_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_target(false)
+        .with_filter(stderr_level);
+
+    let file_layer = match log_file {
+        Some(path) => {
+            let file = RotatingFile::open(path, log_max_size).map_err(|e| {
+                YtdlError::Other(t!("ログファイルを開けません: {}: {}", path.display(), e))
+            })?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    // 標準エラー出力用（色付き）の整形結果を共有しないよう、別の整形方法を使う
+                    .fmt_fields(format::debug_fn(plain_field).delimited(" "))
+                    .with_span_events(FmtSpan::CLOSE)
+                    .with_target(false)
+                    // yt-dlpの出力（TRACE）もファイルにのみ記録
+                    .with_filter(LevelFilter::TRACE),
+            )
+        }
+        None => None,
+    };
+
+    let timing_layer = timings.then(|| {
+        TimingLayer {
+            timings: TIMINGS.get_or_init(Timings::new),
+        }
+    });
+
+    tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer)
+        .with(timing_layer)
+        .init();
+    Ok(())
+}
+
+/// 一定のサイズを超えたら古いログを "<名前>.1" 〜 "<名前>.5" に移すログファイル
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_size: u64) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+        })
+    }
+
+    /// 古いログの番号を1つずつずらし、新しいファイルに切り替える
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for i in (1..LOG_KEEP).rev() {
+            let _ = std::fs::rename(rotated_path(&self.path, i), rotated_path(&self.path, i + 1));
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// ローテーションした `n` 番目のログファイル（"<名前>.<n>"）
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// `--timings` の集計結果
+static TIMINGS: OnceLock<Timings> = OnceLock::new();
+
+/// 処理段階ごとの所要時間（最初に実行された順）
+struct Timings {
+    started: Instant,
+    stages: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Timings {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            stages: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn add(&self, name: &'static str, elapsed: Duration) {
+        let Ok(mut stages) = self.stages.lock() else {
+            return;
+        };
+        match stages.iter_mut().find(|(stage, _)| *stage == name) {
+            Some((_, total)) => *total += elapsed,
+            None => stages.push((name, elapsed)),
+        }
+    }
+}
+
+/// 処理段階ごとの所要時間の内訳を表示（`--timings` 指定時のみ）
+pub fn print_timings() {
+    let Some(timings) = TIMINGS.get() else {
+        return;
+    };
+    let Ok(stages) = timings.stages.lock() else {
+        return;
+    };
+
+    // 全角文字を含むため、表示幅で揃える
+    let print_line = |label: &str, elapsed: Duration| {
+        println!(
+            "  {} {:>8.1}s",
+            pad_str(label, 24, Alignment::Left, None),
+            elapsed.as_secs_f64()
+        );
+    };
+
+    println!("{}", t!("\n⏱  処理時間の内訳:"));
+    for (name, elapsed) in stages.iter() {
+        print_line(stage_label(name), *elapsed);
+    }
+    print_line(t!("合計"), timings.started.elapsed());
+}
+
+/// span名に対応する表示名
+fn stage_label(name: &str) -> &str {
+    match name {
+        "startup" => t!("その他の起動処理"),
+        "update_check" => t!("yt-dlpの更新"),
+        "ytdlp_check" => t!("yt-dlpの確認"),
+        "cookie_probe" => t!("Cookieの確認"),
+        "metadata_probe" => t!("メタデータ取得"),
+        "download" => t!("ダウンロード"),
+        "postprocess" => t!("後処理（結合・変換）"),
+        "upload" => t!("クラウド転送"),
+        _ => name,
+    }
+}
+
+/// spanごとの実行時間（子spanの時間を除く）を集計するレイヤー
+struct TimingLayer {
+    timings: &'static Timings,
+}
+
+/// spanの実行時間の記録
+struct SpanTiming {
+    /// 実行中の区間の開始時刻
+    entered: Option<Instant>,
+    /// 実行時間の合計
+    busy: Duration,
+    /// 子spanの実行時間の合計
+    children: Duration,
+}
+
+impl<S> tracing_subscriber::Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            // 表示順を開始順にするため、この時点で登録しておく
+            self.timings.add(span.name(), Duration::ZERO);
+            span.extensions_mut().insert(SpanTiming {
+                entered: None,
+                busy: Duration::ZERO,
+                children: Duration::ZERO,
+            });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                timing.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                if let Some(entered) = timing.entered.take() {
+                    timing.busy += entered.elapsed();
+                }
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some((busy, children)) = span
+            .extensions()
+            .get::<SpanTiming>()
+            .map(|timing| (timing.busy, timing.children))
+        else {
+            return;
+        };
+
+        // 親spanの時間からは、この段階の時間を差し引く
+        if let Some(parent) = span.parent() {
+            if let Some(timing) = parent.extensions_mut().get_mut::<SpanTiming>() {
+                timing.children += busy;
+            }
+        }
+        self.timings.add(span.name(), busy.saturating_sub(children));
+    }
+}
+
+/// 色を付けずにフィールドを書き出す（メッセージは名前を省略）
+fn plain_field(writer: &mut Writer<'_>, field: &Field, value: &dyn std::fmt::Debug) -> std::fmt::Result {
+    if field.name() == "message" {
+        write!(writer, "{:?}", value)
+    } else {
+        write!(writer, "{}={:?}", field, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("ytdl-test-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ytdl.log");
+
+        let mut file = RotatingFile::open(&path, 10).unwrap();
+        for line in ["first\n", "second\n", "third\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap(), "second\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 2)).unwrap(), "first\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
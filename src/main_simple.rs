@@ -1,27 +1,31 @@
-mod cli;
-mod cookie_detector;
-mod error;
-mod interactive;
-mod progress_parser;
-mod quality;
-mod updater;
-mod ytdlp_wrapper;
-
-use clap::Parser;
-use cli::Cli;
-use error::Result;
-use interactive::InteractiveMode;
-use updater::Updater;
-use ytdlp_wrapper::YtdlpWrapper;
+use ytdl::cli::{App, Cli};
+use ytdl::collision::CollisionPolicy;
+use ytdl::config::Config;
+use ytdl::cookie_detector::CookieDetector;
+use ytdl::error::{self, Result};
+use ytdl::interactive::{AfterDownload, InteractiveMode};
+use ytdl::quality::QualityProfile;
+use ytdl::updater::Updater;
+use ytdl::ytdlp_wrapper::YtdlpWrapper;
+use ytdl::t;
+use ytdl::{
+    archive, availability, commands, logging, paths, pause, progress_parser, queue, rclone, shutdown,
+    system_monitor, url_files, watch_dir, youtube_url,
+};
 
 /// メインエントリポイント
 fn main() {
     // エラーが発生した場合の終了コードを設定
-    std::process::exit(match run() {
+    let result = run();
+
+    // --timings: 処理段階ごとの所要時間
+    logging::print_timings();
+
+    std::process::exit(match result {
         Ok(_) => 0,
         Err(e) => {
-            eprintln!("\nエラー: {}", e);
-            eprintln!("\nEnterキーを押して終了...");
+            eprintln!("{}", t!("\nエラー: {}", e));
+            eprintln!("{}", t!("\nEnterキーを押して終了..."));
             let mut input = String::new();
             let _ = std::io::stdin().read_line(&mut input);
             1
@@ -31,104 +35,210 @@ fn main() {
 
 /// 実際の処理を実行
 fn run() -> Result<()> {
-    // CLIの引数をパース
-    let mut cli = Cli::parse();
+    // CLIの引数をパース（ダウンロード以外のサブコマンドはここで実行して終了）
+    let (app, mut specified) = App::parse_specified();
+    // 設定ファイルの読み込み（表示する言語を決めてから、失敗した場合はデフォルト設定で続行）
+    let config = Config::load();
+    InteractiveMode::init_lang(app.lang, config.as_ref().ok());
+    let mut cli = match app.into_download() {
+        Ok(cli) => cli,
+        Err(command) => return commands::run(command),
+    };
+    let config = config.unwrap_or_else(|e| {
+        eprintln!("{}", t!("警告: {}", e));
+        Config::default()
+    });
+    // [defaults] と --profile のオプションを反映（コマンドライン引数が優先）
+    config.apply_defaults(&mut cli, &mut specified)?;
 
     // バナー表示
     print_banner();
+    progress_parser::set_si_units(cli.si_units);
+
+    // 処理段階ごとのログ（-vv / --log-file / --timings）
+    logging::init(cli.verbose, cli.log_file.as_deref(), cli.log_max_size, cli.timings)?;
+    let startup = tracing::info_span!("startup").entered();
+
+    // 外部からの一時停止/再開（SIGUSR1/SIGUSR2）
+    pause::install_signal_handlers();
+    shutdown::install_handler(std::time::Duration::from_secs(cli.shutdown_grace));
+
+    // 起動元のプロセスが終了したら一緒に終了する
+    if let Some(pid) = cli.parent_pid {
+        shutdown::watch_parent(pid)?;
+    }
 
     // yt-dlp自動更新
-    println!("🔄 yt-dlpを最新版に更新中...");
+    println!("{}", t!("🔄 yt-dlpを最新版に更新中..."));
+    let update_span = tracing::info_span!("update_check").entered();
     if let Err(e) = Updater::update_ytdlp() {
-        eprintln!("警告: yt-dlp更新失敗: {}", e);
-        eprintln!("続行します...\n");
+        tracing::warn!("yt-dlp更新失敗: {}", e);
+        eprintln!("{}", t!("警告: yt-dlp更新失敗: {}", e));
+        eprintln!("{}", t!("続行します...\n"));
     }
+    update_span.exit();
 
     // yt-dlpが利用可能かチェック
-    println!("\n📦 yt-dlpの確認中...");
-    YtdlpWrapper::check_ytdlp_available()?;
+    println!("{}", t!("\n📦 yt-dlpの確認中..."));
+    tracing::info_span!("ytdlp_check").in_scope(YtdlpWrapper::check_ytdlp_available)?;
+
+    // クラウド転送する場合はrcloneを確認（--s3-dest もrcloneで転送する）
+    if cli.upload_dest().is_some() && !cli.dry_run {
+        let option = if cli.s3_dest.is_some() { "--s3-dest" } else { "--rclone-dest" };
+        rclone::RcloneUploader::check_available(option)?;
+    }
 
-    // Simple版: デフォルトでCookie無効（明示的に--cookiesが指定された場合のみ有効）
-    let args: Vec<String> = std::env::args().collect();
-    let has_cookies_arg = args.iter().any(|arg| arg.starts_with("--cookies") || arg == "-c");
+    // Simple版: デフォルトでCookie無効（--cookies / YTDL_COOKIES / 設定ファイルで指定された場合のみ有効）
+    let has_cookies_arg = specified.contains("cookie_browser") || specified.contains("cookies_file");
 
     if cli.no_cookies || !has_cookies_arg {
         cli.cookie_browser = None;
     }
+    if cli.no_cookies {
+        cli.cookies_file = None;
+    }
+
+    // cookies.txtを使う場合はブラウザのCookieを読まない
+    if cli.cookies_file.is_some() {
+        cli.cookie_browser = None;
+    }
+
+    // キーチェーン/キーリングの事前確認（macOS/Linux）
+    if let Some(browser) = &cli.cookie_browser {
+        let _span = tracing::info_span!("cookie_probe", browser = browser.as_str()).entered();
+        if let Some(fallback) = CookieDetector::from_str(browser)
+            .ok()
+            .and_then(|detector| detector.preflight_keyring())
+        {
+            cli.cookie_browser = Some(fallback);
+        }
+    }
 
-    // 出力ディレクトリのデフォルト設定（exeと同じフォルダ）
+    // 出力ディレクトリのデフォルト設定（exeと同じフォルダ、書き込めなければダウンロードフォルダ）
     if cli.output_dir.is_none() {
-        cli.output_dir = Some(
-            std::env::current_exe()
-                .ok()
-                .and_then(|path| path.parent().map(|p| p.to_path_buf()))
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-        );
+        let (dir, fallback_reason) = paths::default_output_dir();
+        if let Some(reason) = fallback_reason {
+            println!("{}", t!("📁 {}、{} に保存します", reason, dir.display()));
+        }
+        cli.output_dir = Some(dir);
     }
 
     // アーカイブファイルのデフォルト設定
     if cli.download_archive.is_none() && !cli.no_archive {
         let archive_path = cli.output_dir.as_ref()
-            .map(|dir| dir.join("downloaded.txt"))
-            .unwrap_or_else(|| std::path::PathBuf::from("downloaded.txt"));
+            .map(|dir| dir.join(archive::DEFAULT_FILE_NAME))
+            .unwrap_or_else(|| std::path::PathBuf::from(archive::DEFAULT_FILE_NAME));
         cli.download_archive = Some(archive_path);
     }
 
-    // インタラクティブモード
-    if cli.url.is_none() && !cli.non_interactive {
-        println!("\n🎮 インタラクティブモードで起動しました");
-
-        // URL入力
-        let url = InteractiveMode::ask_url()
-            .map_err(|e| error::YtdlError::Other(format!("入力エラー: {}", e)))?;
+    // URLファイル（.url / .webloc / テキスト）が指定された場合はURLを取り出す
+    cli.url = url_files::expand_inputs(&cli.url)?;
 
-        if url.is_empty() {
-            eprintln!("エラー: URLが入力されませんでした");
-            std::process::exit(1);
+    // ダウンロードキューのURL（ytdl queue run。実行中の追加・並べ替えはダウンロード時に反映）
+    if cli.run_queue {
+        cli.url = queue::Queue::open()?.urls();
+        if cli.url.is_empty() {
+            println!("{}", t!("📋 キューは空です（ytdl queue add <URL> で追加）"));
+            return Ok(());
         }
-        cli.url = Some(url);
+    }
 
-        // 品質選択
-        cli.quality = InteractiveMode::ask_quality()
-            .map_err(|e| error::YtdlError::Other(format!("入力エラー: {}", e)))?;
+    // 起動処理はここまで（インタラクティブモードの入力待ちは含めない）
+    drop(startup);
 
-        // プレイリストか確認（URLに"playlist"が含まれている場合のみ）
-        if cli.url.as_ref().unwrap().contains("playlist") {
-            cli.playlist = InteractiveMode::ask_playlist()
-                .map_err(|e| error::YtdlError::Other(format!("入力エラー: {}", e)))?;
+    // フォルダ監視（ytdl watch-dir。URLは監視フォルダから受け取り、終了するまで戻らない）
+    if let Some(dir) = cli.watch_dir.clone() {
+        if cli.respect_metered {
+            system_monitor::start_power_monitor();
         }
+        return watch_dir::watch(&prepare_download(cli, &config), &dir);
+    }
+
+    // インタラクティブモード
+    let interactive = cli.watch_until_available.is_none()
+        && cli.url.is_empty()
+        && !cli.non_interactive;
+    if cli.watch_until_available.is_some() {
+        // ダウンロードできるまで確認するURLは --watch-until-available で受け取る
+    } else if interactive {
+        println!("{}", t!("\n🎮 インタラクティブモードで起動しました"));
 
-        // 字幕確認
-        cli.download_subtitle = InteractiveMode::ask_subtitle()
-            .map_err(|e| error::YtdlError::Other(format!("入力エラー: {}", e)))?;
-    } else if cli.url.is_none() {
-        eprintln!("エラー: URLを指定してください");
+        let completed = InteractiveMode::run(&mut cli, &QualityProfile::all(&config))
+            .map_err(|e| error::YtdlError::Other(t!("入力エラー: {}", e)))?;
+        if !completed {
+            println!("{}", t!("\nキャンセルしました"));
+            return Ok(());
+        }
+    } else if cli.url.is_empty() {
+        eprintln!("{}", t!("エラー: URLを指定してください"));
         std::process::exit(1);
     }
 
-    // 設定の妥当性チェック
-    if let Err(e) = cli.validate() {
-        eprintln!("設定エラー: {}", e);
-        std::process::exit(1);
+    // バッテリー駆動・従量制接続の監視
+    if cli.respect_metered && !cli.dry_run {
+        system_monitor::start_power_monitor();
     }
 
-    // 設定を表示
-    println!();
-    cli.display_config();
-    println!();
+    // インタラクティブモードでは、終了を選ぶまで続けてダウンロードできる
+    let mut redownload = false;
+    loop {
+        let mut run_cli = cli.clone();
 
-    // ダウンロード実行
-    let wrapper = YtdlpWrapper::new(cli);
-    wrapper.download()?;
+        // 品質を変えてダウンロードし直す場合は、アーカイブ済みでも保存済みのファイルを上書き
+        if redownload {
+            run_cli.download_archive = None;
+            run_cli.on_collision = Some(CollisionPolicy::Overwrite);
+        }
 
-    // 完了メッセージ
-    println!("\n✅ すべてのダウンロードが完了しました！");
-    println!("📁 ファイルはexeと同じフォルダに保存されています\n");
+        let wrapper = prepare_download(run_cli, &config);
+
+        // ドライラン（一覧表示のみ）
+        if wrapper.is_dry_run() {
+            return wrapper.dry_run();
+        }
+
+        // ダウンロードキュー（キューが空になるまで戻らない）
+        if cli.run_queue {
+            return queue::run(&wrapper);
+        }
+
+        // ダウンロードできるまで確認（ダウンロードするか、終了するまで戻らない）
+        if let Some(url) = &cli.watch_until_available {
+            return availability::watch(&wrapper, url, cli.poll);
+        }
+
+        // ダウンロード実行（インタラクティブモードでは失敗してもメニューを表示）
+        let output_dir = cli.output_dir.clone().unwrap_or_default();
+        match wrapper.download() {
+            Ok(()) => {
+                println!("{}", t!("\n✅ すべてのダウンロードが完了しました！"));
+                println!("{}", t!("📁 保存先: {}\n", output_dir.display()));
+            }
+            Err(e) if interactive && !shutdown::is_requested() => eprintln!("{}", t!("\nエラー: {}", e)),
+            Err(e) => return Err(e),
+        }
+
+        if !interactive {
+            break;
+        }
+        let next = InteractiveMode::after_download(
+            &mut cli,
+            &QualityProfile::all(&config),
+            &wrapper.downloaded_files(),
+            &output_dir,
+        )
+        .map_err(|e| error::YtdlError::Other(t!("入力エラー: {}", e)))?;
+        match next {
+            AfterDownload::Another => redownload = false,
+            AfterDownload::Redownload => redownload = true,
+            AfterDownload::Quit => return Ok(()),
+        }
+    }
 
     // Windows環境では終了前に待機
     #[cfg(target_os = "windows")]
     {
-        println!("Enterキーを押して終了...");
+        println!("{}", t!("Enterキーを押して終了..."));
         let mut input = String::new();
         let _ = std::io::stdin().read_line(&mut input);
     }
@@ -136,14 +246,49 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+/// URLを確定し、設定を検証・表示してyt-dlpラッパーを作成
+fn prepare_download(mut cli: Cli, config: &Config) -> YtdlpWrapper {
+    // プレイリスト内の動画のURL（watch?v=...&list=...）は動画のみ、またはプレイリスト全体のURLにする
+    if cli.url.iter().any(|url| youtube_url::MixedUrl::parse(url).is_some()) {
+        let choice = cli.playlist_choice().unwrap_or_else(|| {
+            println!("{}", t!("💡 プレイリスト内の動画のURLです。この動画のみダウンロードします（全体は --whole-list）"));
+            youtube_url::PlaylistChoice::ThisVideo
+        });
+        cli.url = youtube_url::resolve_urls(&cli.url, choice);
+    }
+
+    // 設定の妥当性チェック
+    if let Err(e) = cli.validate() {
+        eprintln!("{}", t!("設定エラー: {}", e));
+        std::process::exit(1);
+    }
+
+    // 品質プリセットの解決
+    let profile = match cli.quality_profile(config) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("{}", t!("設定エラー: {}", e));
+            std::process::exit(1);
+        }
+    };
+
+    // 設定を表示
+    println!();
+    cli.display_config(&profile);
+    println!();
+
+    YtdlpWrapper::new(cli, profile, config.hooks.clone()).with_retry_policy(config.retry.clone())
+}
+
 /// バナーを表示
 fn print_banner() {
-    println!(
-        r#"
-╔═══════════════════════════════════════════════════╗
-║   YouTube Batch Downloader (Simple)               ║
-║   シンプル版 - Cookie不要                         ║
-╚═══════════════════════════════════════════════════╝
-"#
-    );
+    // 枠の内側の幅に合わせて、訳の長さ（全角は2文字分）に応じて空白で埋める
+    let tagline = t!("シンプル版 - Cookie不要");
+    let padding = 48usize.saturating_sub(dialoguer::console::measure_text_width(tagline));
+    println!();
+    println!("╔═══════════════════════════════════════════════════╗");
+    println!("║   YouTube Batch Downloader (Simple)               ║");
+    println!("║   {}{}║", tagline, " ".repeat(padding));
+    println!("╚═══════════════════════════════════════════════════╝");
+    println!();
 }
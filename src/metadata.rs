@@ -0,0 +1,380 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// yt-dlpの `--dump-json` が出力する動画情報（必要な項目のみ）
+#[derive(Debug, Clone, Deserialize)]
+pub struct VideoInfo {
+    /// 動画ID
+    pub id: String,
+    /// タイトル
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 動画ページのURL
+    #[serde(default)]
+    pub webpage_url: Option<String>,
+    /// 選択されたフォーマットの拡張子（結合する場合は結合後）
+    #[serde(default)]
+    pub ext: Option<String>,
+    /// 保存先のファイル名（`-o` のテンプレートを展開したもの。拡張子は後処理前）
+    #[serde(default)]
+    pub filename: Option<String>,
+    /// 再生時間（秒）
+    #[serde(default)]
+    pub duration: Option<f64>,
+    /// アップロード者
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// チャンネル名
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// チャンネルID（"UC..."）
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// チャンネルのURL
+    #[serde(default)]
+    pub channel_url: Option<String>,
+    /// アップロード者のID（YouTubeではハンドル "@name"）
+    #[serde(default)]
+    pub uploader_id: Option<String>,
+    /// アップロード者のURL（"https://www.youtube.com/@name"）
+    #[serde(default)]
+    pub uploader_url: Option<String>,
+    /// アップロード日（YYYYMMDD形式）
+    #[serde(default)]
+    pub upload_date: Option<String>,
+    /// 再生回数
+    #[serde(default)]
+    pub view_count: Option<u64>,
+    /// ライセンス（例: "Creative Commons Attribution license (reuse allowed)"）
+    #[serde(default)]
+    pub license: Option<String>,
+    /// 選択されたフォーマットのファイルサイズ（バイト）
+    #[serde(default)]
+    pub filesize: Option<u64>,
+    /// 選択されたフォーマットの推定ファイルサイズ（バイト）
+    #[serde(default)]
+    pub filesize_approx: Option<u64>,
+    /// 映像+音声を結合する場合の個別フォーマット
+    #[serde(default)]
+    pub requested_formats: Option<Vec<FormatInfo>>,
+    /// 利用可能なすべてのフォーマット
+    #[serde(default)]
+    pub formats: Option<Vec<FormatInfo>>,
+    /// 字幕（言語ごとの形式の一覧）
+    #[serde(default)]
+    pub subtitles: HashMap<String, Vec<SubtitleTrack>>,
+    /// 自動生成の字幕（言語ごとの形式の一覧）
+    #[serde(default)]
+    pub automatic_captions: HashMap<String, Vec<SubtitleTrack>>,
+}
+
+/// 字幕の形式
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubtitleTrack {
+    /// 形式（vtt、srt、assなど）
+    pub ext: String,
+}
+
+/// 個別フォーマットの情報
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormatInfo {
+    /// フォーマットID
+    #[serde(default)]
+    pub format_id: Option<String>,
+    /// 拡張子
+    #[serde(default)]
+    pub ext: Option<String>,
+    /// 映像コーデック（音声のみの場合は "none"）
+    #[serde(default)]
+    pub vcodec: Option<String>,
+    /// 音声コーデック（映像のみの場合は "none"）
+    #[serde(default)]
+    pub acodec: Option<String>,
+    /// 解像度（幅）
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// 解像度（高さ）
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// フレームレート
+    #[serde(default)]
+    pub fps: Option<f64>,
+    /// 平均ビットレート（kbps）
+    #[serde(default)]
+    pub tbr: Option<f64>,
+    /// ファイルサイズ（バイト）
+    #[serde(default)]
+    pub filesize: Option<u64>,
+    /// 推定ファイルサイズ（バイト）
+    #[serde(default)]
+    pub filesize_approx: Option<u64>,
+}
+
+impl FormatInfo {
+    /// ファイルサイズ（不明な場合は推定値）
+    pub fn size_estimate(&self) -> Option<u64> {
+        self.filesize.or(self.filesize_approx)
+    }
+
+    /// 映像を含むか
+    pub fn has_video(&self) -> bool {
+        self.vcodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+
+    /// 音声を含むか
+    pub fn has_audio(&self) -> bool {
+        self.acodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+
+    /// ファイルサイズ（不明な場合はビットレートと再生時間から推定）
+    fn size_or_bitrate_estimate(&self, duration: Option<f64>) -> Option<u64> {
+        self.size_estimate()
+            .or_else(|| Some((self.tbr? * 1000.0 / 8.0 * duration?) as u64))
+    }
+}
+
+impl VideoInfo {
+    /// `--dump-json` の1行をパース
+    pub fn from_json_line(line: &str) -> Result<Self> {
+        serde_json::from_str(line)
+            .map_err(|e| YtdlError::Other(t!("動画情報のパースに失敗: {}", e)))
+    }
+
+    /// ダウンロードサイズの推定値（バイト）
+    ///
+    /// 映像と音声を結合する場合は各フォーマットの合計を返します。
+    pub fn size_estimate(&self) -> Option<u64> {
+        if let Some(formats) = &self.requested_formats {
+            let sizes: Option<Vec<u64>> = formats.iter().map(|f| f.size_estimate()).collect();
+            if let Some(sizes) = sizes {
+                return Some(sizes.iter().sum());
+            }
+        }
+        self.filesize.or(self.filesize_approx)
+    }
+
+    /// サイズ上限に収まる最良のフォーマットを選択
+    ///
+    /// 映像+音声の組み合わせ（または結合済みフォーマット）のうち、
+    /// 合計サイズが上限以下で解像度・ビットレートが最も高いものを選びます。
+    /// `audio_only` の場合は音声のみのフォーマットから選びます。
+    /// 戻り値はyt-dlpの `-f` に渡すフォーマット指定と推定サイズです。
+    pub fn best_format_under(&self, max_bytes: u64, audio_only: bool) -> Option<(String, u64)> {
+        let formats = self.formats.as_deref()?;
+        let size = |f: &FormatInfo| f.size_or_bitrate_estimate(self.duration);
+
+        // (フォーマット指定, サイズ, 高さ, ビットレート)
+        let mut candidates: Vec<(String, u64, u32, f64)> = Vec::new();
+        let audio: Vec<&FormatInfo> = formats
+            .iter()
+            .filter(|f| f.has_audio() && !f.has_video() && f.format_id.is_some())
+            .collect();
+
+        if audio_only {
+            for a in &audio {
+                if let Some(a_size) = size(a) {
+                    candidates.push((a.format_id.clone()?, a_size, 0, a.tbr.unwrap_or(0.0)));
+                }
+            }
+        } else {
+            for f in formats.iter().filter(|f| f.has_video() && f.format_id.is_some()) {
+                let Some(f_size) = size(f) else { continue };
+                let f_id = f.format_id.as_deref()?;
+                let height = f.height.unwrap_or(0);
+                let tbr = f.tbr.unwrap_or(0.0);
+
+                if f.has_audio() {
+                    candidates.push((f_id.to_string(), f_size, height, tbr));
+                } else {
+                    for a in &audio {
+                        let Some(a_size) = size(a) else { continue };
+                        candidates.push((
+                            format!("{}+{}", f_id, a.format_id.as_deref()?),
+                            f_size + a_size,
+                            height,
+                            tbr + a.tbr.unwrap_or(0.0),
+                        ));
+                    }
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|(_, size, _, _)| *size <= max_bytes)
+            .max_by(|a, b| (a.2, a.3).partial_cmp(&(b.2, b.3)).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(format, size, _, _)| (format, size))
+    }
+
+    /// 指定したチャンネルの動画か（`--own-channel`）
+    ///
+    /// `channel` はハンドル（"@name" または "name"）、チャンネルID（"UC..."）、
+    /// チャンネルのURLのいずれか。ハンドルの大文字・小文字は区別しません。
+    pub fn is_from_channel(&self, channel: &str) -> bool {
+        let expected = normalize_channel(channel);
+        [
+            &self.uploader_id,
+            &self.channel_id,
+            &self.uploader_url,
+            &self.channel_url,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|value| normalize_channel(value) == expected)
+    }
+
+    /// クリエイティブ・コモンズのライセンスか（`--only-creative-commons`。不明な場合はfalse）
+    pub fn is_creative_commons(&self) -> bool {
+        self.license.as_deref().is_some_and(|license| {
+            let license = license.to_lowercase();
+            license.contains("creative commons") || license.starts_with("cc-") || license.starts_with("cc ")
+        })
+    }
+
+    /// タイトル（不明な場合は動画ID）
+    pub fn display_title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.id)
+    }
+
+    /// アップロード日を "YYYY-MM-DD" 形式で取得
+    pub fn upload_date_str(&self) -> Option<String> {
+        self.upload_date.as_deref().and_then(format_upload_date)
+    }
+}
+
+/// チャンネルの指定を比較用にそろえる（URLの末尾・"@" を除き、小文字にする）
+fn normalize_channel(channel: &str) -> String {
+    let channel = channel.trim().trim_end_matches('/');
+    let last = channel.rsplit('/').next().unwrap_or(channel);
+    last.trim_start_matches('@').to_lowercase()
+}
+
+/// `--metadata-lang` の言語コード（例: "ja"、"en"、"pt-BR"）を検証
+pub fn parse_metadata_lang(input: &str) -> std::result::Result<String, String> {
+    let valid = !input.is_empty()
+        && input.len() <= 16
+        && input.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(input.to_string())
+    } else {
+        Err(t!("言語コードが不正です: {}（例: ja、en、pt-BR）", input))
+    }
+}
+
+/// 翻訳されたタイトル・説明文を取得させるyt-dlpの引数（`--extractor-args` の値）
+pub fn metadata_lang_arg(lang: &str) -> String {
+    format!("youtube:lang={}", lang)
+}
+
+/// "YYYYMMDD" を "YYYY-MM-DD" に変換
+fn format_upload_date(date: &str) -> Option<String> {
+    if date.len() != 8 || !date.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_video_info() {
+        let line = r#"{"id": "abc123", "title": "テスト動画", "upload_date": "20240102", "filesize_approx": 1048576}"#;
+        let info = VideoInfo::from_json_line(line).unwrap();
+        assert_eq!(info.id, "abc123");
+        assert_eq!(info.display_title(), "テスト動画");
+        assert_eq!(info.upload_date_str().as_deref(), Some("2024-01-02"));
+        assert_eq!(info.size_estimate(), Some(1048576));
+    }
+
+    #[test]
+    fn test_size_estimate_merged_formats() {
+        let line = r#"{"id": "x", "filesize_approx": 1, "requested_formats": [
+            {"filesize": 1000},
+            {"filesize_approx": 200}
+        ]}"#;
+        let info = VideoInfo::from_json_line(line).unwrap();
+        assert_eq!(info.size_estimate(), Some(1200));
+    }
+
+    #[test]
+    fn test_best_format_under() {
+        let line = r#"{"id": "x", "duration": 100, "formats": [
+            {"format_id": "sb0", "vcodec": "none", "acodec": "none"},
+            {"format_id": "140", "vcodec": "none", "acodec": "mp4a", "tbr": 128, "filesize": 1600000},
+            {"format_id": "251", "vcodec": "none", "acodec": "opus", "tbr": 160, "filesize": 2000000},
+            {"format_id": "18", "vcodec": "avc1", "acodec": "mp4a", "height": 360, "tbr": 500, "filesize": 6000000},
+            {"format_id": "136", "vcodec": "avc1", "acodec": "none", "height": 720, "tbr": 2000, "filesize": 25000000},
+            {"format_id": "137", "vcodec": "avc1", "acodec": "none", "height": 1080, "tbr": 4000},
+            {"format_id": "135", "vcodec": "avc1", "acodec": "none", "height": 480, "tbr": 1000, "filesize_approx": 12500000}
+        ]}"#;
+        let info = VideoInfo::from_json_line(line).unwrap();
+
+        // 1080p（ビットレートから50MB推定）+ 音声 が収まる
+        assert_eq!(
+            info.best_format_under(100_000_000, false),
+            Some(("137+251".to_string(), 52_000_000))
+        );
+        // 720p + 高音質音声は上限を超えるため、低音質音声に落とす
+        assert_eq!(
+            info.best_format_under(26_700_000, false),
+            Some(("136+140".to_string(), 26_600_000))
+        );
+        // 映像のみのフォーマットが収まらなければ結合済みフォーマット
+        assert_eq!(
+            info.best_format_under(7_000_000, false),
+            Some(("18".to_string(), 6_000_000))
+        );
+        assert_eq!(info.best_format_under(1_000_000, false), None);
+        assert_eq!(
+            info.best_format_under(1_800_000, true),
+            Some(("140".to_string(), 1_600_000))
+        );
+    }
+
+    #[test]
+    fn test_is_from_channel() {
+        let line = r#"{"id": "x", "uploader_id": "@OurLab", "channel_id": "UCabc123",
+            "channel_url": "https://www.youtube.com/channel/UCabc123"}"#;
+        let info = VideoInfo::from_json_line(line).unwrap();
+        assert!(info.is_from_channel("@ourlab"));
+        assert!(info.is_from_channel("OurLab"));
+        assert!(info.is_from_channel("https://www.youtube.com/@OurLab/"));
+        assert!(info.is_from_channel("UCabc123"));
+        assert!(!info.is_from_channel("@OtherLab"));
+
+        // チャンネルの情報がなければ一致しない
+        let info = VideoInfo::from_json_line(r#"{"id": "y"}"#).unwrap();
+        assert!(!info.is_from_channel("@OurLab"));
+    }
+
+    #[test]
+    fn test_is_creative_commons() {
+        let info = |license: &str| {
+            VideoInfo::from_json_line(&format!(r#"{{"id": "x", "license": "{}"}}"#, license)).unwrap()
+        };
+        assert!(info("Creative Commons Attribution license (reuse allowed)").is_creative_commons());
+        assert!(info("CC BY-SA 4.0").is_creative_commons());
+        assert!(!info("Standard YouTube License").is_creative_commons());
+        assert!(!VideoInfo::from_json_line(r#"{"id": "x"}"#).unwrap().is_creative_commons());
+    }
+
+    #[test]
+    fn test_parse_metadata_lang() {
+        assert_eq!(parse_metadata_lang("pt-BR").as_deref(), Ok("pt-BR"));
+        assert!(parse_metadata_lang("ja,en").is_err());
+        assert!(parse_metadata_lang("").is_err());
+        assert_eq!(metadata_lang_arg("ja"), "youtube:lang=ja");
+    }
+
+    #[test]
+    fn test_format_upload_date() {
+        assert_eq!(format_upload_date("20231231").as_deref(), Some("2023-12-31"));
+        assert_eq!(format_upload_date("2023"), None);
+        assert_eq!(format_upload_date("abcdefgh"), None);
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// MQTTのデフォルトポート
+const DEFAULT_PORT: u16 = 1883;
+
+/// 接続・送受信のタイムアウト
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// MQTT（3.1.1, QoS 0）でダウンロード状態を配信するパブリッシャー
+///
+/// Home Assistantなどのダッシュボードに表示するための最小限の実装です。
+/// 受信（SUBSCRIBE）やQoS 1以上には対応していません。
+/// 並列ダウンロードのスレッドから同時に配信してもパケットが混ざらないよう、送信は1つずつ行います。
+pub struct MqttPublisher {
+    stream: Mutex<TcpStream>,
+    topic_prefix: String,
+}
+
+impl MqttPublisher {
+    /// ブローカーに接続（"host"、"host:port"、"[IPv6アドレス]:port"、またはIPv6アドレスのみ）
+    pub fn connect(broker: &str, topic_prefix: &str) -> Result<Self> {
+        let (host, port) = split_host_port(broker)
+            .ok_or_else(|| YtdlError::Other(t!("MQTTブローカーの指定が不正です: {}", broker)))?;
+        let connect_error =
+            |e: std::io::Error| YtdlError::Other(t!("MQTTブローカー({})に接続できません: {}", broker, e));
+        let addrs: Vec<SocketAddr> = (host, port).to_socket_addrs().map_err(connect_error)?.collect();
+
+        // 名前解決で複数のアドレスが返った場合は、接続できるまで順に試す
+        let mut last_error = std::io::Error::from(std::io::ErrorKind::AddrNotAvailable);
+        let mut connected = None;
+        for addr in &addrs {
+            match TcpStream::connect_timeout(addr, TIMEOUT) {
+                Ok(stream) => {
+                    connected = Some(stream);
+                    break;
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        let mut stream = connected.ok_or_else(|| connect_error(last_error))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let client_id = format!("ytdl-{}", std::process::id());
+        stream.write_all(&encode_connect(&client_id))?;
+
+        // CONNACK: [0x20, 0x02, flags, return_code]
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack)?;
+        if connack[0] != 0x20 || connack[3] != 0 {
+            return Err(YtdlError::Other(t!(
+                "MQTTブローカーが接続を拒否しました（コード: {}）",
+                connack[3]
+            )));
+        }
+
+        Ok(Self {
+            stream: Mutex::new(stream),
+            topic_prefix: topic_prefix.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// プレフィックス配下のトピックにメッセージを配信
+    pub fn publish(&self, topic: &str, payload: &str, retain: bool) -> Result<()> {
+        let full_topic = format!("{}/{}", self.topic_prefix, topic);
+        let mut stream = self.stream.lock().unwrap_or_else(|e| e.into_inner());
+        stream.write_all(&encode_publish(&full_topic, payload.as_bytes(), retain))?;
+        Ok(())
+    }
+
+    /// 動画ごとのトピック（"<プレフィックス>/<動画ID>/<トピック>"）にメッセージを配信
+    pub fn publish_item(&self, video_id: &str, topic: &str, payload: &str, retain: bool) -> Result<()> {
+        self.publish(&format!("{}/{}", topic_level(video_id), topic), payload, retain)
+    }
+}
+
+impl Drop for MqttPublisher {
+    fn drop(&mut self) {
+        // DISCONNECT
+        let stream = self.stream.get_mut().unwrap_or_else(|e| e.into_inner());
+        let _ = stream.write_all(&[0xE0, 0x00]);
+    }
+}
+
+/// ブローカーの指定をホストとポートに分ける（ポートが不正な場合はNone）
+///
+/// IPv6アドレスは "[::1]:1883" のように括弧で囲むとポートを指定できます。
+/// 括弧がなくコロンを2つ以上含む場合はIPv6アドレスのみとみなします。
+fn split_host_port(broker: &str) -> Option<(&str, u16)> {
+    if let Some(rest) = broker.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        return match rest.strip_prefix(':') {
+            Some(port) => Some((host, port.parse().ok()?)),
+            None if rest.is_empty() => Some((host, DEFAULT_PORT)),
+            None => None,
+        };
+    }
+    match broker.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => Some((host, port.parse().ok()?)),
+        _ => Some((broker, DEFAULT_PORT)),
+    }
+}
+
+/// 動画IDをトピックの1階層として使える文字列にする（区切りやワイルドカードの文字を置き換える）
+fn topic_level(id: &str) -> String {
+    id.replace(['/', '+', '#'], "_")
+}
+
+/// 可変長の残りバイト数をエンコード
+fn encode_remaining_length(mut len: usize, buf: &mut Vec<u8>) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+/// 長さ付きUTF-8文字列をエンコード
+fn encode_str(s: &str, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// CONNECTパケットを生成（クリーンセッション、キープアライブ無効）
+fn encode_connect(client_id: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    encode_str("MQTT", &mut body);
+    body.push(0x04); // プロトコルレベル 3.1.1
+    body.push(0x02); // Clean Session
+    body.extend_from_slice(&0u16.to_be_bytes()); // キープアライブ無効
+    encode_str(client_id, &mut body);
+
+    let mut packet = vec![0x10];
+    encode_remaining_length(body.len(), &mut packet);
+    packet.extend(body);
+    packet
+}
+
+/// PUBLISHパケット（QoS 0）を生成
+fn encode_publish(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    encode_str(topic, &mut body);
+    body.extend_from_slice(payload);
+
+    let mut packet = vec![if retain { 0x31 } else { 0x30 }];
+    encode_remaining_length(body.len(), &mut packet);
+    packet.extend(body);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_remaining_length() {
+        let mut buf = Vec::new();
+        encode_remaining_length(0, &mut buf);
+        assert_eq!(buf, vec![0x00]);
+
+        let mut buf = Vec::new();
+        encode_remaining_length(127, &mut buf);
+        assert_eq!(buf, vec![0x7F]);
+
+        let mut buf = Vec::new();
+        encode_remaining_length(321, &mut buf);
+        assert_eq!(buf, vec![0xC1, 0x02]);
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(split_host_port("localhost"), Some(("localhost", 1883)));
+        assert_eq!(split_host_port("localhost:1884"), Some(("localhost", 1884)));
+        assert_eq!(split_host_port("::1"), Some(("::1", 1883)));
+        assert_eq!(split_host_port("fe80::1:2"), Some(("fe80::1:2", 1883)));
+        assert_eq!(split_host_port("[::1]"), Some(("::1", 1883)));
+        assert_eq!(split_host_port("[::1]:1884"), Some(("::1", 1884)));
+        assert_eq!(split_host_port("localhost:port"), None);
+        assert_eq!(split_host_port("[::1"), None);
+    }
+
+    #[test]
+    fn test_topic_level() {
+        assert_eq!(topic_level("dQw4w9WgXcQ"), "dQw4w9WgXcQ");
+        assert_eq!(topic_level("a/b+c#"), "a_b_c_");
+    }
+
+    #[test]
+    fn test_encode_publish() {
+        let packet = encode_publish("a/b", b"hi", true);
+        assert_eq!(packet, vec![0x31, 0x07, 0x00, 0x03, b'a', b'/', b'b', b'h', b'i']);
+    }
+}
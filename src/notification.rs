@@ -0,0 +1,48 @@
+use std::process::{Command, Stdio};
+
+/// 通知を表示するPowerShellのスクリプト（文言は環境変数で受け取る）
+const WINDOWS_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+$template = [Windows.UI.Notifications.ToastTemplateType]::ToastText02
+$xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent($template)
+$texts = $xml.GetElementsByTagName('text')
+$texts.Item(0).AppendChild($xml.CreateTextNode($env:YTDL_NOTIFY_TITLE)) | Out-Null
+$texts.Item(1).AppendChild($xml.CreateTextNode($env:YTDL_NOTIFY_MESSAGE)) | Out-Null
+$app = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe'
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($app).Show([Windows.UI.Notifications.ToastNotification]::new($xml))
+"#;
+
+/// デスクトップに通知を表示（失敗した場合・未対応のOSでは何もしない）
+///
+/// 対応状況: Windows（PowerShellのトースト通知）、macOS（osascript）、Linux（notify-send）
+pub fn show(title: &str, message: &str) {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("powershell");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command", WINDOWS_SCRIPT]);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("osascript");
+        cmd.args([
+            "-e",
+            r#"display notification (system attribute "YTDL_NOTIFY_MESSAGE") with title (system attribute "YTDL_NOTIFY_TITLE")"#,
+        ]);
+        cmd
+    } else if cfg!(target_os = "linux") {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["--app-name=ytdl", title, message]);
+        cmd
+    } else {
+        return;
+    };
+    // 文言に引用符などが含まれてもスクリプトとして解釈されないよう、環境変数で渡す
+    let result = cmd
+        .env("YTDL_NOTIFY_TITLE", title)
+        .env("YTDL_NOTIFY_MESSAGE", message)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if let Err(e) = result {
+        tracing::debug!("通知を表示できません: {}", e);
+    }
+}
@@ -0,0 +1,183 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// アプリケーション名（データディレクトリ名）
+const APP_DIR_NAME: &str = "ytdl";
+
+/// アプリケーションのデータディレクトリを取得
+///
+/// - Windows: %APPDATA%\ytdl
+/// - macOS: ~/Library/Application Support/ytdl
+/// - Linux: $XDG_DATA_HOME/ytdl（未設定なら ~/.local/share/ytdl）
+pub fn data_dir() -> Result<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = env::var("APPDATA").map_err(|_| {
+            YtdlError::Other(t!("APPDATA環境変数が設定されていません").to_string())
+        })?;
+        Ok(PathBuf::from(appdata).join(APP_DIR_NAME))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Ok(home_dir()?
+            .join("Library/Application Support")
+            .join(APP_DIR_NAME))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        match env::var("XDG_DATA_HOME") {
+            Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir).join(APP_DIR_NAME)),
+            _ => Ok(home_dir()?.join(".local/share").join(APP_DIR_NAME)),
+        }
+    }
+}
+
+/// アプリケーションの設定ディレクトリを取得
+///
+/// - Windows: %APPDATA%\ytdl
+/// - macOS: ~/Library/Application Support/ytdl
+/// - Linux: $XDG_CONFIG_HOME/ytdl（未設定なら ~/.config/ytdl）
+pub fn config_dir() -> Result<PathBuf> {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        data_dir()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        match env::var("XDG_CONFIG_HOME") {
+            Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir).join(APP_DIR_NAME)),
+            _ => Ok(home_dir()?.join(".config").join(APP_DIR_NAME)),
+        }
+    }
+}
+
+/// 設定ファイルのパス（YTDL_CONFIG で変更可能）
+pub fn config_file() -> Result<PathBuf> {
+    match env::var_os("YTDL_CONFIG") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(config_dir()?.join("config.toml")),
+    }
+}
+
+/// ダウンロード先のデフォルトを取得（exeと同じフォルダ）
+///
+/// exeのフォルダに書き込めない場合（Program Files、/usr/local/bin、macOSのApp Translocationなど）は
+/// OSのダウンロードフォルダを使用し、2番目の値にフォールバックした理由を返します。
+pub fn default_output_dir() -> (PathBuf, Option<String>) {
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."));
+    if is_writable(&exe_dir) {
+        return (exe_dir, None);
+    }
+
+    match downloads_dir() {
+        Ok(downloads) => {
+            let reason = t!("{} に書き込めないため", exe_dir.display());
+            (downloads.join(APP_DIR_NAME), Some(reason))
+        }
+        Err(_) => (exe_dir, None),
+    }
+}
+
+/// ファイル・フォルダをOSの既定のアプリで開く（終了は待たない）
+///
+/// - Windows: エクスプローラー
+/// - macOS: open
+/// - Linux: xdg-open
+pub fn open_with_default_app(path: &Path) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    std::process::Command::new(program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| YtdlError::ProcessError(t!("{}を起動できません: {}", program, e)))
+}
+
+/// OSのダウンロードフォルダを取得
+///
+/// - Windows: %USERPROFILE%\Downloads
+/// - macOS: ~/Downloads
+/// - Linux: $XDG_DOWNLOAD_DIR（user-dirs.dirs）、未設定なら ~/Downloads
+pub fn downloads_dir() -> Result<PathBuf> {
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if let Some(dir) = xdg_download_dir() {
+            return Ok(dir);
+        }
+    }
+    Ok(home_dir()?.join("Downloads"))
+}
+
+/// user-dirs.dirs の XDG_DOWNLOAD_DIR（例: XDG_DOWNLOAD_DIR="$HOME/ダウンロード"）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn xdg_download_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("XDG_DOWNLOAD_DIR") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+
+    let home = home_dir().ok()?;
+    let config = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".config"),
+    };
+    let content = std::fs::read_to_string(config.join("user-dirs.dirs")).ok()?;
+    content.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("XDG_DOWNLOAD_DIR=")?.trim_matches('"');
+        Some(match value.strip_prefix("$HOME") {
+            Some(rest) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(value),
+        })
+    })
+}
+
+/// ディレクトリにファイルを作成できるか（存在しない場合は作成できるか）
+fn is_writable(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(format!(".ytdl-write-test-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// ホームディレクトリを取得
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> Result<PathBuf> {
+    env::var("HOME")
+        .map(PathBuf::from)
+        .map_err(|_| YtdlError::Other(t!("HOME環境変数が設定されていません").to_string()))
+}
+
+/// ホームディレクトリを取得
+#[cfg(target_os = "windows")]
+fn home_dir() -> Result<PathBuf> {
+    env::var("USERPROFILE")
+        .map(PathBuf::from)
+        .map_err(|_| YtdlError::Other(t!("USERPROFILE環境変数が設定されていません").to_string()))
+}
+
+/// 取得済みcookies.txtの保存先
+pub fn stored_cookies_file() -> Result<PathBuf> {
+    Ok(data_dir()?.join("cookies.txt"))
+}
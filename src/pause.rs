@@ -0,0 +1,451 @@
+use std::process::Command;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// 一時停止の理由（ビットフラグ）。いずれかが立っている間は停止する
+static PAUSE_REASONS: AtomicU8 = AtomicU8::new(0);
+
+/// 実行中のyt-dlpプロセスID
+static ACTIVE_CHILDREN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// 外部からの一時停止/再開要求の受付を開始
+///
+/// Unix: SIGUSR1 で一時停止、SIGUSR2 で再開します。
+/// Windows: 名前付きパイプ `\\.\pipe\ytdl-<PID>` に "pause" / "resume" の行を書き込むと一時停止・再開します。
+/// どちらも `ytdl pause <PID>` / `ytdl resume <PID>` で送れます。
+/// 実行中のyt-dlpプロセス（とその子プロセスのffmpeg・aria2cなど）を停止/再開するため、
+/// ダウンロードを中断せずに帯域を一時的に空けられます。
+///
+/// yt-dlpは別のプロセスグループで起動するため、端末のCtrl+C（SIGINT）やSIGHUPは届きません。
+/// 受け取った場合はyt-dlpを終了させてから、このプロセスも終了します。
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGUSR1, SIGUSR2};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = match Signals::new([SIGUSR1, SIGUSR2, SIGINT, SIGHUP]) {
+        Ok(signals) => signals,
+        Err(e) => {
+            eprintln!("{}", t!("警告: シグナルハンドラを登録できません: {}", e));
+            return;
+        }
+    };
+
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            match signal {
+                SIGUSR1 => pause(PauseReason::User),
+                SIGUSR2 => resume(PauseReason::User),
+                SIGINT | SIGHUP => {
+                    terminate_children();
+                    std::process::exit(128 + signal);
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// 外部からの一時停止/再開要求の受付を開始（名前付きパイプに書き込まれた行を処理）
+#[cfg(windows)]
+pub fn install_signal_handlers() {
+    use std::io::{BufRead, BufReader};
+
+    std::thread::spawn(|| loop {
+        match accept_pipe_client() {
+            Ok(client) => {
+                for line in BufReader::new(client).lines().map_while(std::result::Result::ok) {
+                    match line.trim() {
+                        "pause" => pause(PauseReason::User),
+                        "resume" => resume(PauseReason::User),
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", t!("警告: 一時停止の要求を受け付けられません: {}", e));
+                return;
+            }
+        }
+    });
+}
+
+/// 外部からの一時停止/再開要求の受付を開始（Unix・Windows以外では未対応）
+#[cfg(not(any(unix, windows)))]
+pub fn install_signal_handlers() {}
+
+/// 一時停止/再開の要求を受け付ける名前付きパイプ（Windows）
+#[cfg(windows)]
+fn pipe_name(pid: u32) -> String {
+    format!(r"\\.\pipe\ytdl-{}", pid)
+}
+
+/// Windows: 名前付きパイプを作成し、接続されるまで待つ（ローカルの接続のみ受け付ける）
+#[cfg(windows)]
+fn accept_pipe_client() -> std::io::Result<std::fs::File> {
+    use std::ffi::{c_void, OsStr};
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::FromRawHandle;
+
+    const PIPE_ACCESS_INBOUND: u32 = 0x0000_0001;
+    const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x0000_0008;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const BUFFER_SIZE: u32 = 512;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+    const INVALID_HANDLE_VALUE: isize = -1;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security_attributes: *const c_void,
+        ) -> isize;
+        fn ConnectNamedPipe(pipe: isize, overlapped: *mut c_void) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    let name: Vec<u16> = OsStr::new(&pipe_name(std::process::id()))
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    // SAFETY: 作成したハンドルは接続に失敗したら閉じ、成功したらFileに渡して閉じさせる
+    unsafe {
+        let pipe = CreateNamedPipeW(
+            name.as_ptr(),
+            PIPE_ACCESS_INBOUND,
+            PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+            0,
+            std::ptr::null(),
+        );
+        if pipe == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+        if ConnectNamedPipe(pipe, std::ptr::null_mut()) == 0 {
+            let error = std::io::Error::last_os_error();
+            if error.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                CloseHandle(pipe);
+                return Err(error);
+            }
+        }
+        Ok(std::fs::File::from_raw_handle(pipe as _))
+    }
+}
+
+/// 実行中の別の ytdl に一時停止（`paused` がtrue）/再開を要求（`ytdl pause` / `ytdl resume`）
+#[cfg(unix)]
+pub fn request(pid: u32, paused: bool) -> Result<()> {
+    let pid = libc::pid_t::try_from(pid).map_err(|_| YtdlError::Other(t!("プロセスIDが不正です: {}", pid)))?;
+    let signal = if paused { libc::SIGUSR1 } else { libc::SIGUSR2 };
+    // SAFETY: kill(2) はプロセスIDとシグナル番号を受け取るだけで、メモリには触れない
+    if unsafe { libc::kill(pid, signal) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// 実行中の別の ytdl に一時停止（`paused` がtrue）/再開を要求（`ytdl pause` / `ytdl resume`）
+#[cfg(windows)]
+pub fn request(pid: u32, paused: bool) -> Result<()> {
+    use std::io::Write;
+
+    let mut pipe = std::fs::OpenOptions::new()
+        .write(true)
+        .open(pipe_name(pid))
+        .map_err(|e| YtdlError::Other(t!("プロセス {} に接続できません: {}", pid, e)))?;
+    writeln!(pipe, "{}", if paused { "pause" } else { "resume" })?;
+    Ok(())
+}
+
+/// 実行中の別の ytdl に一時停止/再開を要求（Unix・Windows以外では未対応）
+#[cfg(not(any(unix, windows)))]
+pub fn request(_pid: u32, _paused: bool) -> Result<()> {
+    Err(YtdlError::Other(t!("このOSでは外部からの一時停止に対応していません").to_string()))
+}
+
+/// 一時停止の理由
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+pub enum PauseReason {
+    /// ユーザーによる要求（シグナル）
+    User = 0b01,
+    /// バッテリー駆動・従量制接続
+    Power = 0b10,
+}
+
+/// 実行中のダウンロードを一時停止
+pub fn pause(reason: PauseReason) {
+    let previous = PAUSE_REASONS.fetch_or(reason as u8, Ordering::SeqCst);
+    if previous == 0 {
+        for pid in active_children() {
+            send_signal(pid, Signal::Stop);
+        }
+        eprintln!("{}", t!("\n⏸  ダウンロードを一時停止しました"));
+    }
+}
+
+/// 一時停止したダウンロードを再開（他の理由で停止中の場合は停止を継続）
+pub fn resume(reason: PauseReason) {
+    let previous = PAUSE_REASONS.fetch_and(!(reason as u8), Ordering::SeqCst);
+    if previous == reason as u8 {
+        for pid in active_children() {
+            send_signal(pid, Signal::Continue);
+        }
+        eprintln!("{}", t!("\n▶  ダウンロードを再開しました"));
+    }
+}
+
+/// 一時停止中か（いずれかの理由で停止している）
+pub fn is_paused() -> bool {
+    PAUSE_REASONS.load(Ordering::SeqCst) != 0
+}
+
+/// yt-dlpを起動する前の設定（Unixでは独自のプロセスグループで起動）
+///
+/// yt-dlpが起動するffmpeg・aria2cなども同じプロセスグループに入るため、
+/// 一時停止・終了のシグナルをまとめて送れます。
+pub fn prepare_child(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// yt-dlpプロセスを登録（一時停止中ならすぐに停止）
+///
+/// [`prepare_child`] で設定してから起動したプロセスを登録します。
+pub fn register_child(pid: u32) {
+    if let Ok(mut children) = ACTIVE_CHILDREN.lock() {
+        children.push(pid);
+    }
+    if is_paused() {
+        send_signal(pid, Signal::Stop);
+    }
+}
+
+/// 終了したyt-dlpプロセスの登録を解除
+pub fn unregister_child(pid: u32) {
+    if let Ok(mut children) = ACTIVE_CHILDREN.lock() {
+        children.retain(|&p| p != pid);
+    }
+}
+
+/// 実行中のyt-dlpプロセスがあるか
+pub fn has_active_children() -> bool {
+    !active_children().is_empty()
+}
+
+/// 実行中のyt-dlpプロセスを子プロセスも含めて終了（一時停止中でも終了できるよう再開してから送る）
+pub fn terminate_children() {
+    for pid in active_children() {
+        terminate_child(pid);
+    }
+}
+
+/// yt-dlpプロセスを子プロセスも含めて終了（一時停止中でも終了できるよう再開してから送る）
+pub fn terminate_child(pid: u32) {
+    send_signal(pid, Signal::Terminate);
+    send_signal(pid, Signal::Continue);
+}
+
+/// 実行中のyt-dlpプロセスを子プロセスも含めて強制終了（終了要求に応じない場合）
+pub fn kill_children() {
+    for pid in active_children() {
+        send_signal(pid, Signal::Kill);
+    }
+}
+
+fn active_children() -> Vec<u32> {
+    ACTIVE_CHILDREN
+        .lock()
+        .map(|children| children.clone())
+        .unwrap_or_default()
+}
+
+/// プロセスに送る制御シグナル
+#[derive(Debug, Clone, Copy)]
+enum Signal {
+    Stop,
+    Continue,
+    Terminate,
+    Kill,
+}
+
+/// Unix: yt-dlpのプロセスグループ全体に送る（[`prepare_child`] でプロセスID = グループID）
+///
+/// 終了もグループに送るため、yt-dlpが起動したffmpeg・aria2cが残りません。
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: Signal) {
+    let Ok(pgid) = libc::pid_t::try_from(pid) else {
+        return;
+    };
+    let sig = match signal {
+        Signal::Stop => libc::SIGSTOP,
+        Signal::Continue => libc::SIGCONT,
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    // SAFETY: kill(2) はプロセスIDとシグナル番号を受け取るだけで、メモリには触れない
+    unsafe {
+        libc::kill(-pgid, sig);
+    }
+}
+
+/// Windows: 一時停止・再開はyt-dlpとその子プロセスをすべて中断・再開し、終了は子プロセスも含めて終了
+#[cfg(windows)]
+fn send_signal(pid: u32, signal: Signal) {
+    const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> isize;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSuspendProcess(handle: isize) -> i32;
+        fn NtResumeProcess(handle: isize) -> i32;
+    }
+
+    if let Signal::Terminate | Signal::Kill = signal {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+        return;
+    }
+    for pid in process_tree(pid) {
+        // SAFETY: 開いたハンドルは中断・再開にのみ使い、必ず閉じる
+        unsafe {
+            let handle = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+            if handle == 0 {
+                continue;
+            }
+            match signal {
+                Signal::Stop => NtSuspendProcess(handle),
+                _ => NtResumeProcess(handle),
+            };
+            CloseHandle(handle);
+        }
+    }
+}
+
+/// Windows: プロセスとその子孫のプロセスID（yt-dlpが起動したffmpeg・aria2cなど）
+#[cfg(windows)]
+fn process_tree(root: u32) -> Vec<u32> {
+    const TH32CS_SNAPPROCESS: u32 = 0x0000_0002;
+    const INVALID_HANDLE_VALUE: isize = -1;
+
+    #[repr(C)]
+    struct ProcessEntry32W {
+        size: u32,
+        usage: u32,
+        process_id: u32,
+        default_heap_id: usize,
+        module_id: u32,
+        threads: u32,
+        parent_process_id: u32,
+        priority_class_base: i32,
+        flags: u32,
+        exe_file: [u16; 260],
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateToolhelp32Snapshot(flags: u32, pid: u32) -> isize;
+        fn Process32FirstW(snapshot: isize, entry: *mut ProcessEntry32W) -> i32;
+        fn Process32NextW(snapshot: isize, entry: *mut ProcessEntry32W) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    // (プロセスID, 親のプロセスID) の一覧
+    let mut processes = Vec::new();
+    // SAFETY: スナップショットのハンドルは一覧の取得にのみ使い、必ず閉じる
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot != INVALID_HANDLE_VALUE {
+            let mut entry: ProcessEntry32W = std::mem::zeroed();
+            entry.size = std::mem::size_of::<ProcessEntry32W>() as u32;
+            let mut found = Process32FirstW(snapshot, &mut entry) != 0;
+            while found {
+                processes.push((entry.process_id, entry.parent_process_id));
+                found = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+        }
+    }
+
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        for &(pid, ppid) in &processes {
+            if ppid == parent && pid != parent && !tree.contains(&pid) {
+                tree.push(pid);
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+/// その他のOSでは一時停止・再開・終了はできない
+#[cfg(not(any(unix, windows)))]
+fn send_signal(_pid: u32, _signal: Signal) {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_to_exited_process() {
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(request(pid, true).is_err());
+        assert!(request(u32::MAX, false).is_err());
+    }
+
+    #[test]
+    fn test_terminate_child_stops_process_group() {
+        use std::io::{BufRead, BufReader};
+        use std::process::Stdio;
+
+        // yt-dlpが起動するffmpegの代わりに、子プロセス（sleep）を起動して待つシェル
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 & echo $!; wait"]).stdout(Stdio::piped());
+        prepare_child(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let sleep_pid = line.trim().to_string();
+
+        terminate_child(child.id());
+        child.wait().unwrap();
+
+        // 終了したプロセスは /proc から消えるか、回収されるまでゾンビ（Z）になる
+        let stopped = (0..50).any(|_| {
+            let stat = std::fs::read_to_string(format!("/proc/{}/stat", sleep_pid)).unwrap_or_default();
+            if stat.is_empty() || stat.rsplit(')').next().is_some_and(|rest| rest.trim_start().starts_with('Z')) {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            false
+        });
+        assert!(stopped, "子プロセスが終了していません: {}", sleep_pid);
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::Result;
+
+/// 視聴できない動画の代わりに置くファイルの拡張子
+pub const EXTENSION: &str = "unavailable.json";
+
+/// 視聴できない動画の代わりに置くファイル（`--unavailable-placeholders`）
+///
+/// 削除・非公開などでダウンロードできなかった動画について、保存先に `<動画ID>.unavailable.json` を作成し、
+/// シリーズのアーカイブの欠けを人にもプログラムにもわかるようにします。
+/// 後でダウンロードできた場合は削除します。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnavailablePlaceholder {
+    pub id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// yt-dlpのエラーメッセージ
+    pub reason: String,
+    /// 確認した日（UTC、"YYYY-MM-DD"）
+    pub date: String,
+}
+
+impl UnavailablePlaceholder {
+    /// 今日の日付で作成
+    pub fn new(id: &str, title: Option<&str>, url: Option<&str>, reason: &str) -> Self {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            id: id.to_string(),
+            title: title.map(str::to_string),
+            url: url.map(str::to_string),
+            reason: reason.to_string(),
+            date: format_date(now),
+        }
+    }
+
+    /// 保存先に書き出す（書き出したファイルを返す）
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = path(dir, &self.id);
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json + "\n")?;
+        Ok(path)
+    }
+}
+
+/// 動画の代わりに置くファイルのパス
+pub fn path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", id.replace(['/', '\\'], "_"), EXTENSION))
+}
+
+/// ダウンロードできた動画の代わりに置いたファイルを削除（あった場合はtrue）
+pub fn remove(dir: &Path, id: &str) -> bool {
+    std::fs::remove_file(path(dir, id)).is_ok()
+}
+
+/// UNIX時刻を "YYYY-MM-DD"（UTC）にする
+fn format_date(seconds: u64) -> String {
+    // 1970-01-01からの日数を年月日に変換（グレゴリオ暦）
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_704_153_600), "2024-01-02");
+    }
+
+    #[test]
+    fn test_write_and_remove() {
+        let dir = std::env::temp_dir().join(format!("ytdl-placeholder-{}", std::process::id()));
+        let placeholder =
+            UnavailablePlaceholder::new("abc123", Some("第3話"), None, "Private video");
+        let path = placeholder.write(&dir).unwrap();
+        assert_eq!(path, dir.join("abc123.unavailable.json"));
+
+        let saved: UnavailablePlaceholder =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, placeholder);
+        assert!(remove(&dir, "abc123"));
+        assert!(!remove(&dir, "abc123"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
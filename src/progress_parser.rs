@@ -1,7 +1,10 @@
 use regex::Regex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::LazyLock;
 
 use crate::error::{Result, YtdlError};
+use crate::i18n::{self, Lang};
+use crate::t;
 
 /// yt-dlpの進捗情報
 #[derive(Debug, Clone)]
@@ -16,35 +19,61 @@ pub struct ProgressInfo {
     pub speed: Option<f64>,
     /// 残り時間（秒）
     pub eta: Option<u64>,
+    /// ダウンロード済みフラグメント番号（DASH/HLS）
+    pub fragment_index: Option<u32>,
+    /// フラグメント総数（DASH/HLS、ライブ配信などでは不明）
+    pub fragment_count: Option<u32>,
 }
 
 impl ProgressInfo {
+    /// JSONで表す（MQTT配信・`--progress-format json` 用）
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "percent": self.percent,
+            "downloaded_bytes": self.downloaded_bytes,
+            "total_bytes": self.total_bytes,
+            "speed": self.speed,
+            "eta": self.eta,
+            "fragment_index": self.fragment_index,
+            "fragment_count": self.fragment_count,
+        })
+    }
+
     /// ダウンロード済みサイズを人間が読める形式で取得
     pub fn downloaded_size_str(&self) -> String {
         self.downloaded_bytes
             .map(format_bytes)
-            .unwrap_or_else(|| "不明".to_string())
+            .unwrap_or_else(|| t!("不明").to_string())
     }
 
     /// 総サイズを人間が読める形式で取得
     pub fn total_size_str(&self) -> String {
         self.total_bytes
             .map(format_bytes)
-            .unwrap_or_else(|| "不明".to_string())
+            .unwrap_or_else(|| t!("不明").to_string())
     }
 
     /// ダウンロード速度を人間が読める形式で取得
     pub fn speed_str(&self) -> String {
         self.speed
             .map(|s| format!("{}/s", format_bytes(s as u64)))
-            .unwrap_or_else(|| "不明".to_string())
+            .unwrap_or_else(|| t!("不明").to_string())
     }
 
     /// 残り時間を人間が読める形式で取得
     pub fn eta_str(&self) -> String {
         self.eta
             .map(format_duration)
-            .unwrap_or_else(|| "不明".to_string())
+            .unwrap_or_else(|| t!("不明").to_string())
+    }
+
+    /// フラグメントの進捗を取得（例: "frag 5/40"、総数不明なら "frag 5"）
+    pub fn fragment_str(&self) -> Option<String> {
+        let index = self.fragment_index?;
+        Some(match self.fragment_count {
+            Some(count) => format!("frag {}/{}", index, count),
+            None => format!("frag {}", index),
+        })
     }
 }
 
@@ -53,6 +82,12 @@ pub struct ProgressParser {
     // yt-dlpの進捗出力パターン
     // 例: [download]  45.2% of 123.45MiB at 1.23MiB/s ETA 00:42
     download_regex: LazyLock<Regex>,
+    // フラグメントの進捗（DASH/HLS）
+    // 例: (frag 5/40)、ライブ配信では (frag 5/?)
+    fragment_regex: LazyLock<Regex>,
+    // 総サイズ不明時の進捗（ライブ配信など）
+    // 例: [download]   5.00MiB at  1.00MiB/s (00:00:05) (frag 3/?)
+    unknown_total_regex: LazyLock<Regex>,
 }
 
 impl Default for ProgressParser {
@@ -66,7 +101,16 @@ impl ProgressParser {
         Self {
             download_regex: LazyLock::new(|| {
                 Regex::new(
-                    r"\[download\]\s+(?P<percent>[\d.]+)%\s+of\s+(?P<total>[\d.]+)(?P<total_unit>[KMG]iB)(?:\s+at\s+(?P<speed>[\d.]+)(?P<speed_unit>[KMG]iB)/s)?(?:\s+ETA\s+(?P<eta>\d+:\d+))?"
+                    r"\[download\]\s+(?P<percent>[\d.]+)%\s+of\s+~?\s*(?P<total>[\d.]+)(?P<total_unit>[KMG]iB)(?:\s+at\s+(?P<speed>[\d.]+)(?P<speed_unit>[KMG]?i?B)/s)?(?:\s+ETA\s+(?P<eta>\d+(?::\d+)+))?"
+                ).expect("正規表現のコンパイルに失敗")
+            }),
+            fragment_regex: LazyLock::new(|| {
+                Regex::new(r"\(frag\s+(?P<index>\d+)/(?P<count>\d+|\?)\)")
+                    .expect("正規表現のコンパイルに失敗")
+            }),
+            unknown_total_regex: LazyLock::new(|| {
+                Regex::new(
+                    r"\[download\]\s+(?P<downloaded>[\d.]+)(?P<downloaded_unit>[KMG]?i?B)\s+at\s+(?P<speed>[\d.]+)(?P<speed_unit>[KMG]?i?B)/s"
                 ).expect("正規表現のコンパイルに失敗")
             }),
         }
@@ -86,7 +130,7 @@ impl ProgressParser {
                 .name("percent")
                 .and_then(|m| m.as_str().parse::<f64>().ok())
                 .ok_or_else(|| {
-                    YtdlError::ProgressParseError("進捗率のパースに失敗".to_string())
+                    YtdlError::ProgressParseError(t!("進捗率のパースに失敗").to_string())
                 })?;
 
             let total_bytes = caps
@@ -111,17 +155,69 @@ impl ProgressParser {
                 .name("eta")
                 .and_then(|m| parse_time_str(m.as_str()));
 
+            let (fragment_index, fragment_count) = self.parse_fragments(line);
+
             return Ok(Some(ProgressInfo {
                 percent,
                 downloaded_bytes,
                 total_bytes,
                 speed,
                 eta,
+                fragment_index,
+                fragment_count,
+            }));
+        }
+
+        // 総サイズ不明（ライブ配信・HLS音声など）の場合はフラグメント数から進捗を推定
+        if let Some(caps) = self.unknown_total_regex.captures(line) {
+            let downloaded_bytes = caps
+                .name("downloaded")
+                .and_then(|m| m.as_str().parse::<f64>().ok())
+                .and_then(|val| {
+                    caps.name("downloaded_unit")
+                        .map(|unit| parse_size(val, unit.as_str()))
+                });
+
+            let speed = caps
+                .name("speed")
+                .and_then(|m| m.as_str().parse::<f64>().ok())
+                .and_then(|val| {
+                    caps.name("speed_unit")
+                        .map(|unit| parse_size(val, unit.as_str()) as f64)
+                });
+
+            let (fragment_index, fragment_count) = self.parse_fragments(line);
+            let percent = match (fragment_index, fragment_count) {
+                (Some(index), Some(count)) if count > 0 => {
+                    (index as f64 / count as f64 * 100.0).min(100.0)
+                }
+                _ => 0.0,
+            };
+
+            return Ok(Some(ProgressInfo {
+                percent,
+                downloaded_bytes,
+                total_bytes: None,
+                speed,
+                eta: None,
+                fragment_index,
+                fragment_count,
             }));
         }
 
         Ok(None)
     }
+
+    /// フラグメントの進捗をパース（総数が "?" の場合は総数なし）
+    fn parse_fragments(&self, line: &str) -> (Option<u32>, Option<u32>) {
+        match self.fragment_regex.captures(line) {
+            Some(caps) => (
+                caps.name("index").and_then(|m| m.as_str().parse().ok()),
+                caps.name("count").and_then(|m| m.as_str().parse().ok()),
+            ),
+            None => (None, None),
+        }
+    }
 }
 
 /// サイズ文字列をバイト数にパース（例: "123.45", "MiB" -> バイト数）
@@ -135,30 +231,109 @@ fn parse_size(value: f64, unit: &str) -> u64 {
     (value * multiplier) as u64
 }
 
-/// 時間文字列をパース（例: "01:23" -> 83秒）
+/// サイズ指定をバイト数にパース（例: "100M" -> 104857600、"1.5GB" -> 1500000000）
+///
+/// K/M/G と KiB/MiB/GiB は1024単位、KB/MB/GB は1000単位です。単位なしはバイト数。
+pub fn parse_byte_size(input: &str) -> std::result::Result<u64, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| t!("サイズの指定が不正です: {}", input))?;
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KIB" => 1024.0,
+        "M" | "MIB" => 1024.0 * 1024.0,
+        "G" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        "KB" => 1000.0,
+        "MB" => 1000.0 * 1000.0,
+        "GB" => 1000.0 * 1000.0 * 1000.0,
+        _ => return Err(t!("サイズの単位が不正です: {}（K, M, G などを指定）", input)),
+    };
+
+    let bytes = (value * multiplier) as u64;
+    if bytes == 0 {
+        return Err(t!("サイズには0より大きい値を指定してください").to_string());
+    }
+    Ok(bytes)
+}
+
+/// ダウンロード先の出力行なら、そのファイル名を返す
+///
+/// 例: `[download] Destination: /videos/a.f137.mp4` -> "/videos/a.f137.mp4"
+pub fn destination(line: &str) -> Option<&str> {
+    line.trim().strip_prefix("[download] Destination: ")
+}
+
+/// yt-dlpの後処理（結合・音声変換・埋め込みなど）の出力行なら、その処理名を返す
+///
+/// 例: `[Merger] Merging formats into ...` -> "Merger"
+pub fn postprocessor_name(line: &str) -> Option<&str> {
+    const POSTPROCESSORS: &[&str] = &[
+        "Merger",
+        "ExtractAudio",
+        "VideoConvertor",
+        "VideoRemuxer",
+        "EmbedThumbnail",
+        "EmbedSubtitle",
+        "Metadata",
+        "ModifyChapters",
+        "SponsorBlock",
+        "SplitChapters",
+        "FixupM3u8",
+        "FixupM4a",
+        "FixupTimestamp",
+        "FixupDuration",
+        "ThumbnailsConvertor",
+    ];
+    let name = line.strip_prefix('[')?.split_once(']')?.0;
+    POSTPROCESSORS.contains(&name).then_some(name)
+}
+
+/// 時間文字列をパース（例: "01:23" -> 83秒、"1:02:03" -> 3723秒）
 fn parse_time_str(time_str: &str) -> Option<u64> {
     let parts: Vec<&str> = time_str.split(':').collect();
-    if parts.len() == 2 {
-        let minutes = parts[0].parse::<u64>().ok()?;
-        let seconds = parts[1].parse::<u64>().ok()?;
-        Some(minutes * 60 + seconds)
-    } else {
-        None
+    if !(2..=3).contains(&parts.len()) {
+        return None;
     }
+    parts
+        .iter()
+        .try_fold(0u64, |total, part| Some(total * 60 + part.parse::<u64>().ok()?))
+}
+
+/// サイズ表示に10進単位（MB/GB）を使うか（`--si-units`）
+static SI_UNITS: AtomicBool = AtomicBool::new(false);
+
+/// サイズ表示の単位を設定（true: MB/GB、false: MiB/GiB）
+pub fn set_si_units(enabled: bool) {
+    SI_UNITS.store(enabled, Ordering::Relaxed);
 }
 
-/// バイト数を人間が読める形式にフォーマット
-fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+/// バイト数を人間が読める形式にフォーマット（小数点は表示する言語に合わせる）
+pub fn format_bytes(bytes: u64) -> String {
+    format_bytes_in(bytes, SI_UNITS.load(Ordering::Relaxed), i18n::lang())
+}
+
+/// バイト数を指定した単位系・言語でフォーマット（`si` がtrueなら1000単位のMB/GB）
+fn format_bytes_in(bytes: u64, si: bool, lang: Lang) -> String {
+    let (base, units): (f64, &[&str]) = if si {
+        (1000.0, &["B", "KB", "MB", "GB", "TB"])
+    } else {
+        (1024.0, &["B", "KiB", "MiB", "GiB", "TiB"])
+    };
     let mut size = bytes as f64;
     let mut unit_index = 0;
 
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
+    while size >= base && unit_index < units.len() - 1 {
+        size /= base;
         unit_index += 1;
     }
 
-    format!("{:.2} {}", size, UNITS[unit_index])
+    format!("{} {}", lang.format_decimal(size, 2), units[unit_index])
 }
 
 /// 秒数を人間が読める形式にフォーマット
@@ -192,12 +367,55 @@ mod tests {
         assert!(info.eta.is_some());
     }
 
+    #[test]
+    fn test_parse_fragment_progress() {
+        let parser = ProgressParser::new();
+
+        let line = "[download]  12.5% of ~  50.00MiB at    1.23MiB/s ETA 00:30 (frag 5/40)";
+        let info = parser.parse(line).unwrap().unwrap();
+        assert_eq!(info.percent, 12.5);
+        assert!(info.total_bytes.is_some());
+        assert_eq!(info.eta, Some(30));
+        assert_eq!(info.fragment_index, Some(5));
+        assert_eq!(info.fragment_count, Some(40));
+        assert_eq!(info.fragment_str().as_deref(), Some("frag 5/40"));
+
+        // 総サイズ不明（ライブ配信）
+        let line = "[download]   5.00MiB at  500.00KiB/s (00:00:05) (frag 3/?)";
+        let info = parser.parse(line).unwrap().unwrap();
+        assert_eq!(info.percent, 0.0);
+        assert_eq!(info.downloaded_bytes, Some(5 * 1024 * 1024));
+        assert_eq!(info.fragment_index, Some(3));
+        assert_eq!(info.fragment_count, None);
+        assert_eq!(info.fragment_str().as_deref(), Some("frag 3"));
+
+        // 総サイズ不明でもフラグメント総数があれば進捗を推定
+        let line = "[download]   5.00MiB at  500.00KiB/s (00:00:05) (frag 10/40)";
+        let info = parser.parse(line).unwrap().unwrap();
+        assert_eq!(info.percent, 25.0);
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("100M"), Ok(100 * 1024 * 1024));
+        assert_eq!(parse_byte_size("1.5GB"), Ok(1_500_000_000));
+        assert_eq!(parse_byte_size("500KiB"), Ok(500 * 1024));
+        assert_eq!(parse_byte_size("2048"), Ok(2048));
+        assert!(parse_byte_size("0M").is_err());
+        assert!(parse_byte_size("10X").is_err());
+        assert!(parse_byte_size("M").is_err());
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(512), "512.00 B");
         assert_eq!(format_bytes(1024), "1.00 KiB");
         assert_eq!(format_bytes(1024 * 1024), "1.00 MiB");
         assert_eq!(format_bytes(1536 * 1024 * 1024), "1.50 GiB");
+        assert_eq!(format_bytes_in(1_500_000_000, true, Lang::Ja), "1.50 GB");
+        assert_eq!(format_bytes_in(1024 * 1024, true, Lang::Ja), "1.05 MB");
+        assert_eq!(format_bytes_in(999, true, Lang::En), "999.00 B");
+        assert_eq!(format_bytes_in(1023 * 1024, false, Lang::En), "1,023.00 KiB");
     }
 
     #[test]
@@ -207,10 +425,28 @@ mod tests {
         assert_eq!(format_duration(3661), "61:01");
     }
 
+    #[test]
+    fn test_postprocessor_name() {
+        assert_eq!(
+            postprocessor_name("[Merger] Merging formats into \"a.mp4\""),
+            Some("Merger")
+        );
+        assert_eq!(postprocessor_name("[ExtractAudio] Destination: a.mp3"), Some("ExtractAudio"));
+        assert_eq!(postprocessor_name("[download]  45.2% of 10.00MiB"), None);
+        assert_eq!(postprocessor_name("[youtube] abc: Downloading webpage"), None);
+    }
+
+    #[test]
+    fn test_destination() {
+        assert_eq!(destination("[download] Destination: /videos/a b.f137.mp4\n"), Some("/videos/a b.f137.mp4"));
+        assert_eq!(destination("[download]  45.2% of 10.00MiB"), None);
+    }
+
     #[test]
     fn test_parse_time_str() {
         assert_eq!(parse_time_str("01:30"), Some(90));
         assert_eq!(parse_time_str("00:42"), Some(42));
+        assert_eq!(parse_time_str("1:02:03"), Some(3723));
         assert_eq!(parse_time_str("invalid"), None);
     }
 }
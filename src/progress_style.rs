@@ -0,0 +1,185 @@
+use clap::ValueEnum;
+use dialoguer::console::Term;
+use indicatif::ProgressStyle;
+use std::time::Duration;
+
+use crate::progress_parser::ProgressInfo;
+use crate::t;
+
+/// この幅（桁数）未満の端末では速度・残り時間を省略
+const NARROW_WIDTH: u16 = 60;
+
+/// この幅（桁数）以上の端末では40桁のバーを表示
+const WIDE_WIDTH: u16 = 100;
+
+/// `--progress plain` で進捗を出力する間隔
+pub const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 進捗の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ProgressFormat {
+    /// 進捗バー
+    #[default]
+    Bar,
+
+    /// 数秒ごとに1行の進捗を出力（CIのログ向け）
+    Plain,
+
+    /// 進捗は出力せず、動画ごとの開始・完了のみ表示
+    #[value(name = "none")]
+    Quiet,
+
+    /// 進捗ごとに1行のJSONを標準出力に出力（GUI・スクリプト向け）
+    Json,
+}
+
+impl ProgressFormat {
+    /// 進捗バーを表示するか
+    pub fn shows_bars(self) -> bool {
+        self == ProgressFormat::Bar
+    }
+}
+
+/// 進捗バーの表示スタイル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ProgressTheme {
+    /// サイズ・速度・残り時間をすべて表示
+    #[default]
+    Detailed,
+
+    /// 進捗率とサイズのみ
+    Minimal,
+
+    /// ASCII文字のみ（絵文字・罫線を表示できない端末向け）
+    Ascii,
+}
+
+impl ProgressTheme {
+    /// 進捗バーのスタイル（`color` はバーの色、例: "cyan/blue"）
+    ///
+    /// バーの長さは端末の幅に合わせて調整します。
+    pub fn bar_style(self, color: &str) -> ProgressStyle {
+        let bar_width = bar_width(terminal_width());
+        let template = match self {
+            ProgressTheme::Detailed => {
+                format!("{{spinner:.green}} [{{bar:{}.{}}}] {{percent}}% | {{msg}}", bar_width, color)
+            }
+            ProgressTheme::Minimal => "{percent:>3}% {msg}".to_string(),
+            ProgressTheme::Ascii => format!("{{spinner}} [{{bar:{}}}] {{percent}}% | {{msg}}", bar_width),
+        };
+
+        let style = ProgressStyle::default_bar()
+            .template(&template)
+            .expect("Progress template invalid")
+            .progress_chars("#>-");
+        match self {
+            ProgressTheme::Ascii => style.tick_chars("|/-\\ "),
+            _ => style,
+        }
+    }
+
+    /// プレイリスト全体の進捗バーのスタイル（残り時間は全体の進み具合から推定）
+    pub fn overall_style(self) -> ProgressStyle {
+        let bar_width = bar_width(terminal_width());
+        let template = match self {
+            ProgressTheme::Detailed => {
+                format!("{} [{{bar:{}.green/white}}] {{percent}}% | {{msg}} | ETA {{eta}}", t!("全体"), bar_width)
+            }
+            ProgressTheme::Minimal => format!("{} {{percent:>3}}% {{msg}}", t!("全体")),
+            ProgressTheme::Ascii => format!("[{{bar:{}}}] {{percent}}% | {{msg}} | ETA {{eta}}", bar_width),
+        };
+
+        ProgressStyle::default_bar()
+            .template(&template)
+            .expect("Progress template invalid")
+            .progress_chars("#>-")
+    }
+
+    /// ダウンロード中の表示メッセージ
+    pub fn message(self, progress: &ProgressInfo) -> String {
+        self.message_for_width(progress, terminal_width())
+    }
+
+    /// 端末の幅に合わせたメッセージ（狭い端末では速度・残り時間を省略）
+    fn message_for_width(self, progress: &ProgressInfo, width: Option<u16>) -> String {
+        let mut message = format!(
+            "{} / {}",
+            progress.downloaded_size_str(),
+            progress.total_size_str()
+        );
+        let narrow = width.is_some_and(|width| width < NARROW_WIDTH);
+        if self == ProgressTheme::Minimal || narrow {
+            return message;
+        }
+
+        message.push_str(&format!(" | {} | ETA {}", progress.speed_str(), progress.eta_str()));
+        if let Some(fragments) = progress.fragment_str() {
+            message.push_str(&format!(" | {}", fragments));
+        }
+        message
+    }
+}
+
+/// 端末の幅（端末でない場合はNone）
+fn terminal_width() -> Option<u16> {
+    Term::stderr().size_checked().map(|(_, cols)| cols)
+}
+
+/// 端末の幅に合わせたバーの長さ
+fn bar_width(width: Option<u16>) -> u16 {
+    match width {
+        Some(width) if width < NARROW_WIDTH => 10,
+        Some(width) if width < WIDE_WIDTH => 20,
+        _ => 40,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress() -> ProgressInfo {
+        ProgressInfo {
+            percent: 50.0,
+            downloaded_bytes: Some(1024 * 1024),
+            total_bytes: Some(2 * 1024 * 1024),
+            speed: Some(1024.0),
+            eta: Some(42),
+            fragment_index: Some(5),
+            fragment_count: Some(10),
+        }
+    }
+
+    #[test]
+    fn test_message_for_width() {
+        let progress = progress();
+        assert_eq!(
+            ProgressTheme::Detailed.message_for_width(&progress, Some(120)),
+            "1.00 MiB / 2.00 MiB | 1.00 KiB/s | ETA 00:42 | frag 5/10"
+        );
+        assert_eq!(
+            ProgressTheme::Detailed.message_for_width(&progress, Some(50)),
+            "1.00 MiB / 2.00 MiB"
+        );
+        assert_eq!(
+            ProgressTheme::Minimal.message_for_width(&progress, None),
+            "1.00 MiB / 2.00 MiB"
+        );
+    }
+
+    #[test]
+    fn test_bar_style_templates() {
+        for theme in ProgressTheme::value_variants() {
+            theme.bar_style("cyan/blue");
+            theme.overall_style();
+        }
+    }
+
+    #[test]
+    fn test_bar_width() {
+        assert_eq!(bar_width(Some(40)), 10);
+        assert_eq!(bar_width(Some(80)), 20);
+        assert_eq!(bar_width(Some(160)), 40);
+        assert_eq!(bar_width(None), 40);
+    }
+}
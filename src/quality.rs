@@ -1,32 +1,58 @@
 use clap::ValueEnum;
+use serde::Deserialize;
 
-/// ダウンロード品質プリセット
-#[derive(Debug, Clone, Copy, ValueEnum)]
+use crate::config::Config;
+use crate::t;
+
+/// ダウンロード品質プリセット（組み込み）
+#[derive(Debug, Clone, Copy)]
 pub enum QualityPreset {
     /// 最高画質（4K対応、ベスト動画+ベスト音声）
-    #[value(name = "max-video")]
     MaxVideo,
 
+    /// バランス（1080p以下の最高画質、4Kほど容量を使わない）
+    Balanced,
+
     /// 最高音質（音声のみ抽出、mp3変換）
-    #[value(name = "max-audio")]
     MaxAudio,
 
     /// 最低画質（プレビュー用、低解像度）
-    #[value(name = "min-video")]
     MinVideo,
 
     /// 最小容量（容量優先、品質は最低限）
-    #[value(name = "min-size")]
     MinSize,
 }
 
 impl QualityPreset {
+    /// すべての組み込みプリセット（メニューの表示順）
+    pub const ALL: [QualityPreset; 5] = [
+        QualityPreset::MaxVideo,
+        QualityPreset::Balanced,
+        QualityPreset::MaxAudio,
+        QualityPreset::MinVideo,
+        QualityPreset::MinSize,
+    ];
+
+    /// `--quality` で指定する名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            QualityPreset::MaxVideo => "max-video",
+            QualityPreset::Balanced => "balanced",
+            QualityPreset::MaxAudio => "max-audio",
+            QualityPreset::MinVideo => "min-video",
+            QualityPreset::MinSize => "min-size",
+        }
+    }
+
     /// yt-dlpのフォーマット指定文字列を生成
-    pub fn to_ytdlp_format(&self) -> String {
+    pub fn to_ytdlp_format(self) -> String {
         match self {
             // 最高画質: ベストビデオ+ベストオーディオ、または単体でベスト
             QualityPreset::MaxVideo => "bestvideo+bestaudio/best".to_string(),
 
+            // バランス: 1080p以下のベストビデオ+ベストオーディオ
+            QualityPreset::Balanced => "bestvideo[height<=1080]+bestaudio/best".to_string(),
+
             // 最高音質: ベストオーディオのみ（後でmp3に変換）
             QualityPreset::MaxAudio => "bestaudio".to_string(),
 
@@ -46,14 +72,430 @@ impl QualityPreset {
     /// 説明文を取得
     pub fn description(&self) -> &str {
         match self {
-            QualityPreset::MaxVideo => "最高画質（4K対応）",
-            QualityPreset::MaxAudio => "最高音質（音声のみ）",
-            QualityPreset::MinVideo => "最低画質（プレビュー用）",
-            QualityPreset::MinSize => "最小容量",
+            QualityPreset::MaxVideo => t!("最高画質（4K対応）"),
+            QualityPreset::Balanced => t!("バランス（1080p）"),
+            QualityPreset::MaxAudio => t!("最高音質（音声のみ）"),
+            QualityPreset::MinVideo => t!("最低画質（プレビュー用）"),
+            QualityPreset::MinSize => t!("最小容量"),
+        }
+    }
+
+    /// プリセットごとの後処理のデフォルト
+    fn default_postprocess(&self) -> Vec<PostprocessStep> {
+        match self {
+            QualityPreset::MaxVideo | QualityPreset::Balanced => {
+                vec![PostprocessStep::EmbedMetadata, PostprocessStep::EmbedChapters]
+            }
+            QualityPreset::MaxAudio => {
+                vec![PostprocessStep::EmbedThumbnail, PostprocessStep::EmbedMetadata]
+            }
+            QualityPreset::MinVideo | QualityPreset::MinSize => Vec::new(),
+        }
+    }
+
+    /// 品質プロファイルに変換
+    pub fn profile(self) -> QualityProfile {
+        QualityProfile {
+            name: self.name().to_string(),
+            format: self.to_ytdlp_format(),
+            description: self.description().to_string(),
+            extract_audio: self.needs_audio_extraction(),
+            audio_format: self.needs_audio_extraction().then(|| "mp3".to_string()),
+            container: None,
+            postprocess: self.default_postprocess(),
+        }
+    }
+}
+
+/// 音声抽出時の音質（ビットレート）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum AudioQuality {
+    /// 最高品質（VBR最高、mp3では約245kbps）
+    #[default]
+    #[value(name = "best")]
+    Best,
+
+    /// 320kbps（mp3の最高ビットレート）
+    #[value(name = "320k")]
+    Kbps320,
+
+    /// 192kbps
+    #[value(name = "192k")]
+    Kbps192,
+
+    /// 128kbps（音楽の持ち歩き向け）
+    #[value(name = "128k")]
+    Kbps128,
+
+    /// 96kbps（トーク・ポッドキャスト向け）
+    #[value(name = "96k")]
+    Kbps96,
+
+    /// VBRのレベル2（mp3では約190kbps）
+    #[value(name = "v2")]
+    Vbr2,
+
+    /// VBRのレベル5（mp3では約130kbps、トーク向け）
+    #[value(name = "v5")]
+    Vbr5,
+}
+
+impl AudioQuality {
+    /// yt-dlpの `--audio-quality` に渡す値
+    pub fn to_ytdlp_arg(self) -> &'static str {
+        match self {
+            AudioQuality::Best => "0",
+            AudioQuality::Kbps320 => "320K",
+            AudioQuality::Kbps192 => "192K",
+            AudioQuality::Kbps128 => "128K",
+            AudioQuality::Kbps96 => "96K",
+            AudioQuality::Vbr2 => "2",
+            AudioQuality::Vbr5 => "5",
+        }
+    }
+}
+
+/// 音声抽出時の形式（`--audio-format`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    /// MP3（再生できる機器が多い）
+    Mp3,
+    /// M4A（AAC）
+    M4a,
+    /// Opus
+    Opus,
+    /// FLAC（可逆圧縮）
+    Flac,
+    /// WAV（非圧縮）
+    Wav,
+    /// 元の形式のまま（再エンコードによる劣化がない）
+    Best,
+}
+
+impl AudioFormat {
+    /// yt-dlpの `--audio-format` に渡す値
+    pub fn to_ytdlp_arg(self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Best => "best",
+        }
+    }
+}
+
+/// 映像付きダウンロードのコンテナ形式（`--container`）
+///
+/// 結合（`--merge-output-format`）と再格納（`--remux-video`）の両方に使い、
+/// yt-dlpが選ぶ形式ではなく常に同じ形式で保存します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Container {
+    /// MP4（再生できる機器が多い）
+    Mp4,
+    /// MKV（ほとんどのコーデック・字幕を格納できる）
+    Mkv,
+    /// WebM（VP9・AV1とOpusのみ）
+    Webm,
+}
+
+impl Container {
+    /// yt-dlpの `--merge-output-format` / `--remux-video` に渡す値
+    pub fn to_ytdlp_arg(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Mkv => "mkv",
+            Container::Webm => "webm",
+        }
+    }
+}
+
+/// 解像度の上限（`--resolution`）
+///
+/// max-video では4Kが選ばれることが多いため、1080pなどに抑える場合に指定します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(try_from = "u32")]
+pub enum Resolution {
+    /// 2160p（4K）
+    #[value(name = "2160")]
+    P2160,
+    /// 1440p
+    #[value(name = "1440")]
+    P1440,
+    /// 1080p
+    #[value(name = "1080")]
+    P1080,
+    /// 720p
+    #[value(name = "720")]
+    P720,
+    /// 480p
+    #[value(name = "480")]
+    P480,
+}
+
+impl Resolution {
+    /// 高さ（ピクセル）
+    pub fn height(self) -> u32 {
+        match self {
+            Resolution::P2160 => 2160,
+            Resolution::P1440 => 1440,
+            Resolution::P1080 => 1080,
+            Resolution::P720 => 720,
+            Resolution::P480 => 480,
+        }
+    }
+
+    /// yt-dlpのフォーマットの条件
+    pub fn filter(self) -> String {
+        format!("[height<={}]", self.height())
+    }
+}
+
+impl TryFrom<u32> for Resolution {
+    type Error = String;
+
+    /// 設定ファイルでは `resolution = 1080` のように数値で指定
+    fn try_from(height: u32) -> Result<Self, Self::Error> {
+        match height {
+            2160 => Ok(Resolution::P2160),
+            1440 => Ok(Resolution::P1440),
+            1080 => Ok(Resolution::P1080),
+            720 => Ok(Resolution::P720),
+            480 => Ok(Resolution::P480),
+            _ => Err(t!("不明な解像度: {}（使用可能: 2160, 1440, 1080, 720, 480）", height)),
+        }
+    }
+}
+
+/// 映像のコーデックの指定（`--vcodec`）
+///
+/// 古いテレビなどで再生できないAV1を避ける場合や、容量の小さいAV1を優先する場合に指定します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoCodec {
+    /// AV1（容量が小さい）
+    Av1,
+    /// VP9
+    Vp9,
+    /// H.264（再生できる機器が多い）
+    H264,
+}
+
+impl VideoCodec {
+    /// yt-dlpのフォーマットの条件
+    pub fn filter(self) -> &'static str {
+        match self {
+            VideoCodec::Av1 => "[vcodec^=av01]",
+            VideoCodec::Vp9 => "[vcodec~='^(vp9|vp09)']",
+            VideoCodec::H264 => "[vcodec^=avc1]",
+        }
+    }
+
+    /// フォーマット指定の映像にコーデックの条件を付ける（該当がなければ元の指定を使う）
+    ///
+    /// 括弧やカンマを含む複雑な指定は変更しません。
+    pub fn apply(self, format: &str) -> String {
+        match filter_video_selectors(format, self.filter()) {
+            Some(filtered) => format!("{}/{}", filtered, format),
+            None => format.to_string(),
+        }
+    }
+}
+
+/// フォーマット指定の映像の選択（bestvideo・bestなど）に条件を付ける
+///
+/// 括弧やカンマを含む複雑な指定と、映像の選択を含まない指定はNoneを返します。
+fn filter_video_selectors(format: &str, filter: &str) -> Option<String> {
+    if format.contains(['(', ',']) {
+        return None;
+    }
+    let filtered = format
+        .split('/')
+        .map(|alternative| {
+            alternative
+                .split('+')
+                .map(|selector| {
+                    let name = selector.split('[').next().unwrap_or_default();
+                    if VIDEO_SELECTORS.contains(&name) {
+                        format!("{}{}", selector, filter)
+                    } else {
+                        selector.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("+")
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (filtered != format).then_some(filtered)
+}
+
+/// 映像を含むフォーマットの指定（コーデックの条件を付けるもの）
+const VIDEO_SELECTORS: &[&str] = &[
+    "bestvideo", "worstvideo", "bv", "bv*", "wv", "wv*", "best", "worst", "b", "b*", "w", "w*",
+];
+
+/// ダウンロード後の後処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PostprocessStep {
+    /// サムネイルをファイルに埋め込む
+    EmbedThumbnail,
+    /// タイトル・投稿者などのメタデータを埋め込む
+    EmbedMetadata,
+    /// チャプターを埋め込む
+    EmbedChapters,
+    /// 字幕を埋め込む
+    EmbedSubs,
+    /// SponsorBlockのスポンサー区間を削除
+    RemoveSponsors,
+}
+
+impl PostprocessStep {
+    /// 対応するyt-dlpのオプション
+    pub fn ytdlp_args(&self) -> &'static [&'static str] {
+        match self {
+            PostprocessStep::EmbedThumbnail => &["--embed-thumbnail"],
+            PostprocessStep::EmbedMetadata => &["--embed-metadata"],
+            PostprocessStep::EmbedChapters => &["--embed-chapters"],
+            PostprocessStep::EmbedSubs => &["--embed-subs"],
+            PostprocessStep::RemoveSponsors => &["--sponsorblock-remove", "sponsor"],
         }
     }
 }
 
+/// 品質プロファイル
+///
+/// 組み込みプリセットのほか、設定ファイルの `[presets.<名前>]` で
+/// 独自のプロファイルを定義できます。
+///
+/// ```toml
+/// [presets.podcast]
+/// format = "bestaudio"
+/// description = "ポッドキャスト用（m4a）"
+/// extract_audio = true
+/// audio_format = "m4a"
+/// postprocess = ["embed-metadata"]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QualityProfile {
+    /// プロファイル名（`--quality` で指定する名前）
+    #[serde(skip)]
+    pub name: String,
+    /// yt-dlpのフォーマット指定文字列
+    pub format: String,
+    /// 説明文
+    #[serde(default)]
+    pub description: String,
+    /// 音声のみを抽出するか
+    #[serde(default)]
+    pub extract_audio: bool,
+    /// 音声抽出時の形式（未指定ならmp3）
+    #[serde(default)]
+    pub audio_format: Option<String>,
+    /// 保存するコンテナ形式（mp4、mkvなど、結合・再格納に使用）
+    #[serde(default)]
+    pub container: Option<String>,
+    /// 後処理
+    #[serde(default)]
+    pub postprocess: Vec<PostprocessStep>,
+}
+
+impl QualityProfile {
+    /// 利用可能なすべてのプロファイル（組み込み → 設定ファイルの順）
+    ///
+    /// 設定ファイルで組み込みと同じ名前を定義した場合は上書きします。
+    pub fn all(config: &Config) -> Vec<QualityProfile> {
+        let mut profiles: Vec<QualityProfile> =
+            QualityPreset::ALL.iter().map(|preset| preset.profile()).collect();
+
+        for (name, profile) in &config.presets {
+            let profile = QualityProfile {
+                name: name.clone(),
+                ..profile.clone()
+            };
+            match profiles.iter_mut().find(|p| p.name == *name) {
+                Some(existing) => *existing = profile,
+                None => profiles.push(profile),
+            }
+        }
+        profiles
+    }
+
+    /// 名前からプロファイルを取得
+    pub fn resolve(name: &str, config: &Config) -> Result<QualityProfile, String> {
+        let profiles = Self::all(config);
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        let message = t!(
+            "不明な品質プリセット: {}（使用可能: {}）",
+            name,
+            names.join(", ")
+        );
+        profiles
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or(message)
+    }
+
+    /// 説明文（未設定なら名前）
+    pub fn display_description(&self) -> &str {
+        if self.description.is_empty() {
+            &self.name
+        } else {
+            &self.description
+        }
+    }
+
+    /// 解像度の上限を適用（音声のみのプロファイルは変更しない）
+    ///
+    /// フォーマット指定の映像の選択に `[height<=N]` の条件を付けます（`--vcodec` と同じ方法）。
+    /// 条件を付けられない指定（括弧・カンマを含む、映像の選択がない）の場合はエラーです。
+    pub fn with_resolution(mut self, resolution: Resolution) -> Result<Self, String> {
+        if self.extract_audio {
+            return Ok(self);
+        }
+        let Some(format) = filter_video_selectors(&self.format, &resolution.filter()) else {
+            return Err(t!(
+                "品質 '{}' のフォーマット（{}）には解像度の上限を指定できません",
+                self.name,
+                self.format
+            ));
+        };
+        self.format = format;
+        self.description = t!("{}、{}p以下", self.display_description(), resolution.height());
+        Ok(self)
+    }
+
+    /// 映像のコーデックを指定（音声のみのプロファイルは変更しない）
+    pub fn with_vcodec(mut self, codec: VideoCodec) -> Self {
+        if !self.extract_audio {
+            self.format = codec.apply(&self.format);
+        }
+        self
+    }
+
+    /// 音声抽出時の形式を指定
+    pub fn with_audio_format(mut self, format: AudioFormat) -> Self {
+        self.audio_format = Some(format.to_ytdlp_arg().to_string());
+        self
+    }
+
+    /// コンテナ形式を指定
+    pub fn with_container(mut self, container: Container) -> Self {
+        self.container = Some(container.to_ytdlp_arg().to_string());
+        self
+    }
+
+    /// 音声抽出時の形式
+    pub fn audio_format(&self) -> &str {
+        self.audio_format.as_deref().unwrap_or("mp3")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +506,10 @@ mod tests {
             QualityPreset::MaxVideo.to_ytdlp_format(),
             "bestvideo+bestaudio/best"
         );
+        assert_eq!(
+            QualityPreset::Balanced.to_ytdlp_format(),
+            "bestvideo[height<=1080]+bestaudio/best"
+        );
         assert_eq!(QualityPreset::MaxAudio.to_ytdlp_format(), "bestaudio");
         assert_eq!(
             QualityPreset::MinVideo.to_ytdlp_format(),
@@ -75,8 +521,130 @@ mod tests {
     #[test]
     fn test_audio_extraction_flag() {
         assert!(!QualityPreset::MaxVideo.needs_audio_extraction());
+        assert!(!QualityPreset::Balanced.needs_audio_extraction());
         assert!(QualityPreset::MaxAudio.needs_audio_extraction());
         assert!(!QualityPreset::MinVideo.needs_audio_extraction());
         assert!(!QualityPreset::MinSize.needs_audio_extraction());
     }
+
+    #[test]
+    fn test_audio_quality_args() {
+        assert_eq!(AudioQuality::default().to_ytdlp_arg(), "0");
+        assert_eq!(AudioQuality::Kbps192.to_ytdlp_arg(), "192K");
+        assert_eq!(AudioQuality::Kbps96.to_ytdlp_arg(), "96K");
+        assert_eq!(AudioQuality::Kbps320.to_ytdlp_arg(), "320K");
+        assert_eq!(AudioQuality::Vbr5.to_ytdlp_arg(), "5");
+    }
+
+    #[test]
+    fn test_resolution() {
+        let profile = QualityPreset::MaxVideo.profile().with_resolution(Resolution::P1080).unwrap();
+        assert_eq!(profile.format, "bestvideo[height<=1080]+bestaudio/best[height<=1080]");
+
+        let profile = QualityPreset::MinSize.profile().with_resolution(Resolution::P480).unwrap();
+        assert_eq!(profile.format, "worst[ext=mp4][height<=480]");
+
+        // 音声のみのプロファイルは変更しない
+        let audio = QualityPreset::MaxAudio.profile().with_resolution(Resolution::P720).unwrap();
+        assert_eq!(audio.format, "bestaudio");
+
+        // 条件を付けられない指定はエラー
+        let mut complex = QualityPreset::MaxVideo.profile();
+        complex.format = "(bv+ba),ba".to_string();
+        assert!(complex.with_resolution(Resolution::P720).is_err());
+
+        assert_eq!(Resolution::try_from(1440), Ok(Resolution::P1440));
+        assert!(Resolution::try_from(1000).is_err());
+    }
+
+    #[test]
+    fn test_audio_format() {
+        let audio = QualityPreset::MaxAudio.profile();
+        assert_eq!(audio.audio_format(), "mp3");
+        assert_eq!(audio.clone().with_audio_format(AudioFormat::Best).audio_format(), "best");
+        assert_eq!(audio.with_audio_format(AudioFormat::Flac).audio_format(), "flac");
+    }
+
+    #[test]
+    fn test_container() {
+        let profile = QualityPreset::Balanced.profile();
+        assert_eq!(profile.container, None);
+        assert_eq!(profile.with_container(Container::Mkv).container.as_deref(), Some("mkv"));
+    }
+
+    #[test]
+    fn test_vcodec() {
+        assert_eq!(
+            VideoCodec::H264.apply("bestvideo[height<=1080]+bestaudio/best[height<=1080]"),
+            "bestvideo[height<=1080][vcodec^=avc1]+bestaudio/best[height<=1080][vcodec^=avc1]\
+             /bestvideo[height<=1080]+bestaudio/best[height<=1080]"
+        );
+        assert_eq!(
+            VideoCodec::Av1.apply("worst[ext=mp4]"),
+            "worst[ext=mp4][vcodec^=av01]/worst[ext=mp4]"
+        );
+        assert_eq!(VideoCodec::Vp9.apply("bestaudio"), "bestaudio");
+        assert_eq!(VideoCodec::Vp9.apply("(bv+ba),ba"), "(bv+ba),ba");
+
+        let audio = QualityPreset::MaxAudio.profile().with_vcodec(VideoCodec::H264);
+        assert_eq!(audio.format, "bestaudio");
+    }
+
+    #[test]
+    fn test_resolve_builtin_and_custom() {
+        let config: Config = toml::from_str(
+            r#"
+            [presets.podcast]
+            format = "bestaudio"
+            extract_audio = true
+            audio_format = "m4a"
+            postprocess = ["embed-metadata"]
+
+            [presets.max-video]
+            format = "bestvideo[height<=1080]+bestaudio"
+            container = "mkv"
+            "#,
+        )
+        .unwrap();
+
+        let audio = QualityProfile::resolve("max-audio", &config).unwrap();
+        assert!(audio.extract_audio);
+        assert_eq!(audio.audio_format(), "mp3");
+
+        let podcast = QualityProfile::resolve("podcast", &config).unwrap();
+        assert_eq!(podcast.name, "podcast");
+        assert_eq!(podcast.audio_format(), "m4a");
+        assert_eq!(podcast.postprocess, vec![PostprocessStep::EmbedMetadata]);
+        assert_eq!(podcast.display_description(), "podcast");
+
+        // 組み込みの上書き（表示順は維持）
+        let profiles = QualityProfile::all(&config);
+        assert_eq!(profiles[0].name, "max-video");
+        assert_eq!(profiles[0].container.as_deref(), Some("mkv"));
+        assert_eq!(profiles.len(), 6);
+
+        assert!(QualityProfile::resolve("unknown", &config).is_err());
+    }
+
+    #[test]
+    fn test_custom_preset_with_resolution() {
+        let config: Config = toml::from_str(
+            r#"
+            [presets.archive]
+            format = "bestvideo[vcodec^=avc1]+bestaudio[ext=m4a]/best"
+            description = "保存用"
+            container = "mp4"
+            "#,
+        )
+        .unwrap();
+
+        // フォーマットは置き換えず、映像の選択に条件を付ける
+        let profile = QualityProfile::resolve("archive", &config)
+            .unwrap()
+            .with_resolution(Resolution::P720)
+            .unwrap();
+        assert_eq!(profile.format, "bestvideo[vcodec^=avc1][height<=720]+bestaudio[ext=m4a]/best[height<=720]");
+        assert!(profile.description.starts_with("保存用"));
+        assert_eq!(profile.container.as_deref(), Some("mp4"));
+    }
 }
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, YtdlError};
+use crate::i18n;
+use crate::paths;
+use crate::shutdown;
+use crate::t;
+use crate::ytdlp_wrapper::YtdlpWrapper;
+
+/// キューのファイル名（データディレクトリに作成）
+const FILE_NAME: &str = "queue.json";
+
+/// ダウンロードキューの1件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub url: String,
+    /// 追加した日時（UNIX時間）
+    pub added: u64,
+    /// 前回失敗した場合のエラー
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// ダウンロードキュー（`ytdl queue`）
+///
+/// データディレクトリの "queue.json" に保存するため、再起動しても残ります。
+/// 位置は1から数えます（`ytdl queue list` の番号）。
+#[derive(Debug)]
+pub struct Queue {
+    path: PathBuf,
+    entries: Vec<QueueEntry>,
+}
+
+impl Queue {
+    /// データディレクトリのキューを読み込む
+    pub fn open() -> Result<Self> {
+        Self::load(&paths::data_dir()?.join(FILE_NAME))
+    }
+
+    /// キューを読み込む（ファイルがなければ空）
+    pub fn load(path: &Path) -> Result<Self> {
+        let entries = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| {
+                YtdlError::Other(t!("キューを読み込めません: {}: {}", path.display(), e))
+            })?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// 保存先のファイル
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[QueueEntry] {
+        &self.entries
+    }
+
+    /// キューのURL（先頭から順に）
+    pub fn urls(&self) -> Vec<String> {
+        self.entries.iter().map(|entry| entry.url.clone()).collect()
+    }
+
+    /// 末尾にURLを追加（追加済みのURLは追加せずfalseを返す）
+    pub fn add(&mut self, url: &str) -> bool {
+        if self.entries.iter().any(|entry| entry.url == url) {
+            return false;
+        }
+        let added = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.entries.push(QueueEntry {
+            url: url.to_string(),
+            added,
+            error: None,
+        });
+        true
+    }
+
+    /// 指定した位置の動画を別の位置へ移動
+    pub fn move_to(&mut self, from: usize, to: usize) -> Result<()> {
+        let from = self.index(from)?;
+        let to = self.index(to)?;
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        Ok(())
+    }
+
+    /// 指定した位置の動画を削除（削除したものを返す）
+    pub fn remove(&mut self, positions: &[usize]) -> Result<Vec<QueueEntry>> {
+        let mut indices = positions
+            .iter()
+            .map(|&position| self.index(position))
+            .collect::<Result<Vec<usize>>>()?;
+        // 後ろから削除して位置がずれないようにする
+        indices.sort_unstable();
+        indices.dedup();
+        let mut removed: Vec<QueueEntry> = indices
+            .into_iter()
+            .rev()
+            .map(|index| self.entries.remove(index))
+            .collect();
+        removed.reverse();
+        Ok(removed)
+    }
+
+    /// すべて削除（削除した件数を返す）
+    pub fn clear(&mut self) -> usize {
+        std::mem::take(&mut self.entries).len()
+    }
+
+    /// 保存（書きかけのファイルを残さないよう、一時ファイルに書いてから置き換える）
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| YtdlError::Other(t!("キューを保存できません: {}", e)))?;
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+
+    /// 位置（1から）を添字にする
+    fn index(&self, position: usize) -> Result<usize> {
+        if position == 0 || position > self.entries.len() {
+            return Err(YtdlError::Other(t!(
+                "位置 {} の動画はありません（キューは{}件）",
+                position,
+                i18n::number(self.entries.len())
+            )));
+        }
+        Ok(position - 1)
+    }
+}
+
+/// キューのURLを先頭から順にダウンロード（`ytdl queue run`）
+///
+/// 完了したURLはキューから削除し、失敗したURLはエラーを記録して残します（次回の実行でやり直す）。
+/// ダウンロード中に追加・並べ替えられた場合も反映するよう、1件ごとにキューを読み込み直します。
+/// 終了要求（Ctrl+C / SIGTERM）を受け取った場合、残りは次回の実行で処理します。
+pub fn run(wrapper: &YtdlpWrapper) -> Result<()> {
+    let mut attempted = HashSet::new();
+    let (mut completed, mut failed) = (0, 0);
+    loop {
+        if shutdown::is_requested() {
+            println!("{}", t!("⏸  残りのURLは次回の `ytdl queue run` で処理します"));
+            break;
+        }
+        let queue = Queue::open()?;
+        let remaining = queue
+            .entries()
+            .iter()
+            .filter(|entry| !attempted.contains(&entry.url))
+            .count();
+        let Some(entry) = queue.entries().iter().find(|entry| !attempted.contains(&entry.url)) else {
+            break;
+        };
+        let url = entry.url.clone();
+        attempted.insert(url.clone());
+
+        println!("{}", t!("\n📥 {}（キューの残り: {}件）", url, i18n::number(remaining)));
+        let result = wrapper.download_urls(std::slice::from_ref(&url));
+        if result.is_err() && shutdown::is_requested() {
+            continue;
+        }
+
+        // ダウンロード中の変更を消さないよう、読み込み直してから更新
+        let mut queue = Queue::open()?;
+        let position = queue.entries.iter().position(|entry| entry.url == url);
+        match (&result, position) {
+            (Ok(()), Some(index)) => {
+                queue.entries.remove(index);
+                completed += 1;
+            }
+            (Err(e), Some(index)) => {
+                eprintln!("❌ {}: {}", url, e);
+                queue.entries[index].error = Some(e.to_string());
+                failed += 1;
+            }
+            // ダウンロード中にキューから削除された
+            (_, None) => {}
+        }
+        queue.save()?;
+    }
+
+    println!("{}", t!("\n📋 キュー: 完了 {}件 / 失敗 {}件", i18n::number(completed), i18n::number(failed)));
+    if failed > 0 {
+        Err(YtdlError::DownloadFailed(t!(
+            "{}件のURLのダウンロードに失敗しました（キューに残しています）",
+            i18n::number(failed)
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_edit_and_reload() {
+        let root = std::env::temp_dir().join(format!("ytdl-test-queue-{}", std::process::id()));
+        let path = root.join(FILE_NAME);
+
+        let mut queue = Queue::load(&path).unwrap();
+        assert!(queue.entries().is_empty());
+        for url in ["a", "b", "c", "d"] {
+            assert!(queue.add(url));
+        }
+        assert!(!queue.add("b"));
+
+        queue.move_to(4, 1).unwrap();
+        assert_eq!(queue.urls(), ["d", "a", "b", "c"]);
+        let removed = queue.remove(&[3, 1]).unwrap();
+        assert_eq!(removed.iter().map(|entry| entry.url.as_str()).collect::<Vec<_>>(), ["d", "b"]);
+        assert!(queue.remove(&[3]).is_err());
+        queue.save().unwrap();
+
+        // 再起動後も残る
+        let queue = Queue::load(&path).unwrap();
+        assert_eq!(queue.urls(), ["a", "c"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
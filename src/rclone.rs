@@ -0,0 +1,211 @@
+use indicatif::ProgressBar;
+use regex::Regex;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+
+use crate::error::{Result, YtdlError};
+use crate::progress_style::ProgressTheme;
+use crate::t;
+
+/// rcloneの転送状況（--stats-one-line）
+/// 例: "2024/01/01 12:00:00 NOTICE:   10.000 MiB / 100.000 MiB, 10%, 5.000 MiB/s, ETA 18s"
+static STATS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?P<done>[\d.]+\s*[KMGT]?i?B)\s*/\s*(?P<total>[\d.]+\s*[KMGT]?i?B),\s*(?P<percent>[\d.]+)%(?:,\s*(?P<speed>[\d.]+\s*[KMGT]?i?B/s))?(?:,\s*ETA\s*(?P<eta>\S+))?",
+    )
+    .expect("正規表現のコンパイルに失敗")
+});
+
+/// rcloneによるクラウドストレージへの転送
+pub struct RcloneUploader {
+    /// 転送先（例: "gdrive:YouTube"）
+    dest: String,
+    /// 転送後にローカルのファイルを削除するか
+    remove_local: bool,
+    /// 進捗バーの表示スタイル
+    theme: ProgressTheme,
+}
+
+impl RcloneUploader {
+    /// 新しい転送設定を作成
+    pub fn new(dest: &str, remove_local: bool, theme: ProgressTheme) -> Self {
+        Self {
+            dest: dest.to_string(),
+            remove_local,
+            theme,
+        }
+    }
+
+    /// rcloneが利用可能かチェック（`option` は転送先を指定したオプション。見つからない場合のメッセージに使用）
+    pub fn check_available(option: &str) -> Result<()> {
+        let output = Command::new("rclone")
+            .arg("version")
+            .output()
+            .map_err(|_| {
+                YtdlError::UploadFailed(t!(
+                    "{} での転送にはrcloneが必要ですが、見つかりません。rcloneをインストールしてください（https://rclone.org/install/）",
+                    option
+                ))
+            })?;
+
+        if output.status.success() {
+            let version = String::from_utf8_lossy(&output.stdout);
+            if let Some(first_line) = version.lines().next() {
+                println!("{}", first_line.trim());
+            }
+            Ok(())
+        } else {
+            Err(YtdlError::UploadFailed(t!("rcloneを実行できません").to_string()))
+        }
+    }
+
+    /// ダウンロード済みのファイルを転送
+    ///
+    /// `relative` は転送先での相対パス（振り分け先のフォルダ構成を維持するため）。
+    pub fn upload(&self, file: &Path, relative: &Path) -> Result<()> {
+        // ダウンロード結果の確認（空のファイルは転送しない）
+        let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        if size == 0 {
+            return Err(YtdlError::UploadFailed(t!(
+                "ファイルが見つからないか空です: {}",
+                file.display()
+            )));
+        }
+
+        let dest = remote_path(&self.dest, relative);
+        let mut child = Command::new("rclone")
+            .arg(if self.remove_local { "moveto" } else { "copyto" })
+            .arg(file)
+            .arg(&dest)
+            .args(["--stats", "1s", "--stats-one-line", "--stats-log-level", "NOTICE"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| YtdlError::ProcessError(t!("rcloneの起動失敗: {}", e)))?;
+
+        let pb = ProgressBar::new(100);
+        pb.set_style(self.theme.bar_style("magenta/blue"));
+        pb.set_message(t!("{} へ転送中", dest));
+
+        // 転送状況以外の出力（エラーメッセージ）は保持しておく
+        let mut messages = Vec::new();
+        if let Some(stderr) = child.stderr.take() {
+            for line in BufReader::new(stderr).lines().map_while(std::result::Result::ok) {
+                match parse_stats_line(&line) {
+                    Some((percent, message)) => {
+                        pb.set_position(percent as u64);
+                        pb.set_message(message);
+                    }
+                    None => messages.push(line),
+                }
+            }
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| YtdlError::ProcessError(e.to_string()))?;
+        if status.success() {
+            pb.finish_with_message(t!("{} へ転送しました", dest));
+            Ok(())
+        } else {
+            pb.abandon_with_message(t!("転送失敗"));
+            Err(YtdlError::UploadFailed(format!(
+                "{}: {}",
+                file.display(),
+                messages.last().map(String::as_str).unwrap_or(t!("rcloneがエラーで終了しました"))
+            )))
+        }
+    }
+}
+
+/// `s3://bucket/prefix` をrcloneのS3接続文字列に変換（`--s3-dest` はrcloneのS3バックエンドの簡易指定）
+///
+/// 認証情報は環境変数（AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY など）または
+/// ~/.aws の設定から読み込みます。`endpoint` を指定するとS3互換ストレージ
+/// （MinIO、Cloudflare R2など）を使用します。
+/// 大きなファイルはrcloneが自動的にマルチパートでアップロードし、
+/// 再実行時は転送済みで同一のファイルをスキップします。
+pub fn s3_remote(dest: &str, endpoint: Option<&str>) -> std::result::Result<String, String> {
+    let path = dest
+        .strip_prefix("s3://")
+        .ok_or_else(|| t!("転送先は s3://bucket/prefix の形式で指定してください: {}", dest))?;
+    let bucket = path.split('/').next().unwrap_or_default();
+    if bucket.is_empty() {
+        return Err(t!("バケット名がありません: {}", dest));
+    }
+
+    let options = match endpoint {
+        Some(endpoint) => format!("provider=Other,endpoint=\"{}\"", endpoint.replace('"', "\"\"")),
+        None => "provider=AWS".to_string(),
+    };
+    Ok(format!(":s3,{},env_auth=true:{}", options, path.trim_end_matches('/')))
+}
+
+/// 転送先のパスを組み立てる（"remote:" 直下と "remote:path" の両方に対応）
+fn remote_path(dest: &str, relative: &Path) -> String {
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    if dest.ends_with(':') || dest.ends_with('/') {
+        format!("{}{}", dest, relative)
+    } else {
+        format!("{}/{}", dest, relative)
+    }
+}
+
+/// rcloneの転送状況の1行をパース（進捗率と表示用メッセージ）
+fn parse_stats_line(line: &str) -> Option<(f64, String)> {
+    let caps = STATS_REGEX.captures(line)?;
+    let percent = caps.name("percent")?.as_str().parse::<f64>().ok()?;
+
+    let mut message = t!("転送 {} / {}", &caps["done"], &caps["total"]);
+    if let Some(speed) = caps.name("speed") {
+        message.push_str(&format!(" | {}", speed.as_str()));
+    }
+    if let Some(eta) = caps.name("eta") {
+        message.push_str(&format!(" | ETA {}", eta.as_str()));
+    }
+    Some((percent, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_path() {
+        assert_eq!(remote_path("gdrive:", Path::new("a.mp4")), "gdrive:a.mp4");
+        assert_eq!(
+            remote_path("gdrive:YouTube", Path::new("Music/a.mp3")),
+            "gdrive:YouTube/Music/a.mp3"
+        );
+        assert_eq!(remote_path("s3:bucket/", Path::new("a.mp4")), "s3:bucket/a.mp4");
+    }
+
+    #[test]
+    fn test_s3_remote() {
+        assert_eq!(
+            s3_remote("s3://archive/youtube/", None).as_deref(),
+            Ok(":s3,provider=AWS,env_auth=true:archive/youtube")
+        );
+        assert_eq!(
+            s3_remote("s3://archive", Some("https://minio.local:9000")).as_deref(),
+            Ok(":s3,provider=Other,endpoint=\"https://minio.local:9000\",env_auth=true:archive")
+        );
+        assert!(s3_remote("archive/youtube", None).is_err());
+        assert!(s3_remote("s3:///youtube", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_stats_line() {
+        crate::i18n::set_lang(crate::i18n::Lang::Ja);
+        let line = "2024/01/01 12:00:00 NOTICE:   10.000 MiB / 100.000 MiB, 10%, 5.000 MiB/s, ETA 18s";
+        let (percent, message) = parse_stats_line(line).unwrap();
+        assert_eq!(percent, 10.0);
+        assert_eq!(message, "転送 10.000 MiB / 100.000 MiB | 5.000 MiB/s | ETA 18s");
+
+        let line = "2024/01/01 12:00:00 NOTICE: 0 B / 0 B, -, 0 B/s, ETA -";
+        assert!(parse_stats_line(line).is_none());
+        assert!(parse_stats_line("ERROR : a.mp4: Failed to copy").is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use regex::Regex;
+use std::process::Command;
+use std::sync::LazyLock;
+
+/// 伏せた値の代わりに表示する文字列
+pub const REDACTED: &str = "<redacted>";
+
+/// 次の引数（値）を伏せるyt-dlpのオプション（Cookieのファイルはパスにユーザー名を含むため）
+const SECRET_OPTIONS: &[&str] = &[
+    "--cookies",
+    "--username",
+    "--password",
+    "--video-password",
+    "--ap-username",
+    "--ap-password",
+    "--twofactor",
+    "--client-certificate-password",
+];
+
+/// 文字列中のトークン（`--extractor-args` のPO Token・visitor data）
+static TOKEN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(po_token|visitor_data)(\s*[=:]\s*)[^;,'\x22\s]+")
+        .expect("正規表現が不正です")
+});
+
+/// 認証ヘッダー（`--add-header`）の値
+static HEADER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(authorization|cookie)(\s*:\s*)[^'\x22]+")
+        .expect("正規表現が不正です")
+});
+
+/// コマンドを1行にする（秘密の値は "<redacted>" に置き換える）
+///
+/// `--print-command`・詳細ログ・不具合報告でコマンドを表示するときは必ずこれを通します。
+pub fn command_line(cmd: &Command) -> String {
+    let mut parts = vec![cmd.get_program().to_string_lossy().to_string()];
+    let mut hide_next = false;
+    for arg in cmd.get_args().map(|arg| arg.to_string_lossy()) {
+        let arg = if hide_next {
+            REDACTED.to_string()
+        } else {
+            redact_arg(&arg)
+        };
+        hide_next = SECRET_OPTIONS.contains(&arg.as_str());
+        parts.push(quote(&arg));
+    }
+    parts.join(" ")
+}
+
+/// 1つの引数を伏せる（"--password=..." の形と、トークンを含む値）
+fn redact_arg(arg: &str) -> String {
+    if let Some((option, _)) = arg.split_once('=') {
+        if SECRET_OPTIONS.contains(&option) {
+            return format!("{}={}", option, REDACTED);
+        }
+    }
+    redact_line(arg)
+}
+
+/// 出力やログの1行からトークンと認証ヘッダーの値を伏せる
+pub fn redact_line(line: &str) -> String {
+    let replace = |caps: &regex::Captures| format!("{}{}{}", &caps[1], &caps[2], REDACTED);
+    let line = TOKEN_REGEX.replace_all(line, replace);
+    HEADER_REGEX.replace_all(&line, replace).into_owned()
+}
+
+fn quote(arg: &str) -> String {
+    if arg.contains(' ') {
+        format!("\"{}\"", arg)
+    } else {
+        arg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        let mut cmd = Command::new("yt-dlp");
+        cmd.args(["--cookies", "/home/me/cookies.txt", "-o", "%(title)s x.%(ext)s", "URL"]);
+        assert_eq!(
+            command_line(&cmd),
+            "yt-dlp --cookies <redacted> -o \"%(title)s x.%(ext)s\" URL"
+        );
+    }
+
+    #[test]
+    fn test_command_line_inline_values() {
+        let mut cmd = Command::new("yt-dlp");
+        cmd.args([
+            "--password=hunter2",
+            "--extractor-args",
+            "youtube:player_client=web;po_token=web.gvs+ABC123",
+            "--add-header",
+            "Authorization: Bearer xyz",
+        ]);
+        assert_eq!(
+            command_line(&cmd),
+            "yt-dlp --password=<redacted> --extractor-args \
+             youtube:player_client=web;po_token=<redacted> --add-header \"Authorization: <redacted>\""
+        );
+    }
+
+    #[test]
+    fn test_redact_line() {
+        assert_eq!(
+            redact_line("[youtube] Using po_token=web.gvs+ABC for client web"),
+            "[youtube] Using po_token=<redacted> for client web"
+        );
+        assert_eq!(redact_line("[download] 50.0% of 10MiB"), "[download] 50.0% of 10MiB");
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// 実行結果のレポート（`--report`）
+///
+/// スクリプトから結果を確認できるよう、ダウンロードしたファイル・失敗したURLなどをまとめます。
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    /// "completed" または "failed"
+    pub status: &'static str,
+    /// 開始日時（UNIX時間）
+    pub started_at: u64,
+    /// 所要時間（秒）
+    pub duration_secs: f64,
+    pub urls: Vec<String>,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub files: Vec<ReportFile>,
+    /// 既存のファイルがあるためスキップしたファイル
+    pub skipped: Vec<PathBuf>,
+    pub failed: Vec<ReportFailure>,
+}
+
+/// ダウンロードしたファイル
+#[derive(Debug, Serialize)]
+pub struct ReportFile {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// 失敗したURLとその理由
+#[derive(Debug, Serialize)]
+pub struct ReportFailure {
+    pub url: String,
+    pub reason: String,
+}
+
+impl RunReport {
+    /// 実行結果からレポートを作成（ファイルのサイズはここで確認）
+    pub fn new(
+        urls: &[String],
+        started_at: u64,
+        duration: Duration,
+        files: &[PathBuf],
+        skipped: &[PathBuf],
+        failed: &[(String, String)],
+    ) -> Self {
+        let files: Vec<ReportFile> = files
+            .iter()
+            .map(|path| ReportFile {
+                path: path.clone(),
+                bytes: path.metadata().map(|meta| meta.len()).unwrap_or(0),
+            })
+            .collect();
+        Self {
+            status: if failed.is_empty() { "completed" } else { "failed" },
+            started_at,
+            duration_secs: duration.as_secs_f64(),
+            urls: urls.to_vec(),
+            file_count: files.len(),
+            total_bytes: files.iter().map(|file| file.bytes).sum(),
+            files,
+            skipped: skipped.to_vec(),
+            failed: failed
+                .iter()
+                .map(|(url, reason)| ReportFailure {
+                    url: url.clone(),
+                    reason: reason.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// 保存（拡張子が .csv ならCSV、それ以外はJSON）
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(self)
+                .map_err(|e| YtdlError::Other(t!("レポートを作成できません: {}", e)))?
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// 1行に1件のCSV（status: downloaded / skipped / failed）
+    ///
+    /// 合計・所要時間はJSONのみに含まれます。
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("status,target,bytes,reason\n");
+        let mut row = |fields: [&str; 4]| {
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        };
+        for file in &self.files {
+            row(["downloaded", &file.path.to_string_lossy(), &file.bytes.to_string(), ""]);
+        }
+        for file in &self.skipped {
+            row(["skipped", &file.to_string_lossy(), "", ""]);
+        }
+        for failure in &self.failed {
+            row(["failed", &failure.url, "", &failure.reason]);
+        }
+        csv
+    }
+}
+
+/// CSVの値（カンマ・引用符・改行を含む場合は引用符で囲む）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let report = RunReport::new(
+            &["https://example.com/a".to_string()],
+            0,
+            Duration::from_secs(3),
+            &[PathBuf::from("/nonexistent/Song, Live.mp3")],
+            &[],
+            &[("https://example.com/b".to_string(), "HTTP Error 403: \"Forbidden\"".to_string())],
+        );
+        assert_eq!(report.status, "failed");
+        assert_eq!(
+            report.to_csv(),
+            "status,target,bytes,reason\n\
+             downloaded,\"/nonexistent/Song, Live.mp3\",0,\n\
+             failed,https://example.com/b,,\"HTTP Error 403: \"\"Forbidden\"\"\"\n"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::cancel::DownloadHandle;
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::error::{Result, YtdlError};
+use crate::hooks::Hooks;
+use crate::progress_parser::ProgressInfo;
+use crate::progress_style::ProgressTheme;
+use crate::quality::{AudioFormat, AudioQuality, Container, QualityPreset, Resolution, VideoCodec};
+use crate::ytdlp_wrapper::{ProgressCallback, YtdlpWrapper};
+
+/// ダウンロードの指定（ライブラリとして使う場合の入口）
+///
+/// コマンドラインの引数・環境変数・設定ファイルは読み込みません。
+/// 指定しなかった項目はコマンドラインのデフォルトと同じですが、Cookieは使用しません。
+pub struct DownloadRequest {
+    cli: Cli,
+    progress_callback: Option<ProgressCallback>,
+}
+
+impl DownloadRequest {
+    /// ダウンロードするURLを指定して作成
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            cli: Cli {
+                url: vec![url.into()],
+                quality: QualityPreset::MaxVideo.name().to_string(),
+                retry_count: 3,
+                shutdown_grace: 8,
+                encode_jobs: 1,
+                parallel: 1,
+                mqtt_topic: "ytdl".to_string(),
+                progress_style: ProgressTheme::default(),
+                no_cookies: true,
+                non_interactive: true,
+                ..Default::default()
+            },
+            progress_callback: None,
+        }
+    }
+
+    /// ダウンロードするURLを追加
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.cli.url.push(url.into());
+        self
+    }
+
+    /// 品質プリセット
+    pub fn quality(mut self, preset: QualityPreset) -> Self {
+        self.cli.quality = preset.name().to_string();
+        self
+    }
+
+    /// 解像度の上限（例: `Resolution::P1080` で1080p以下）
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.cli.resolution = Some(resolution);
+        self
+    }
+
+    /// 映像のコーデック
+    pub fn vcodec(mut self, codec: VideoCodec) -> Self {
+        self.cli.vcodec = Some(codec);
+        self
+    }
+
+    /// 映像付きダウンロードのコンテナ形式
+    pub fn container(mut self, container: Container) -> Self {
+        self.cli.container = Some(container);
+        self
+    }
+
+    /// 音声抽出時の形式
+    pub fn audio_format(mut self, format: AudioFormat) -> Self {
+        self.cli.audio_format = Some(format);
+        self
+    }
+
+    /// 音声抽出時の音質
+    pub fn audio_quality(mut self, quality: AudioQuality) -> Self {
+        self.cli.audio_quality = quality;
+        self
+    }
+
+    /// 出力先ディレクトリ（未指定なら現在のディレクトリ）
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cli.output_dir = Some(dir.into());
+        self
+    }
+
+    /// ファイル名のテンプレート（yt-dlpの形式）
+    pub fn output_template(mut self, template: impl Into<String>) -> Self {
+        self.cli.output_template = Some(template.into());
+        self
+    }
+
+    /// プレイリスト全体をダウンロード
+    pub fn playlist(mut self, enabled: bool) -> Self {
+        self.cli.playlist = enabled;
+        self
+    }
+
+    /// プレイリストの動画を同時にダウンロードする本数（1なら1本ずつ）
+    pub fn parallel(mut self, jobs: usize) -> Self {
+        self.cli.parallel = jobs;
+        self
+    }
+
+    /// 字幕も保存
+    pub fn subtitles(mut self, enabled: bool) -> Self {
+        self.cli.download_subtitle = enabled;
+        self
+    }
+
+    /// 説明文・メタデータも保存
+    pub fn metadata(mut self, enabled: bool) -> Self {
+        self.cli.save_metadata = enabled;
+        self
+    }
+
+    /// ブラウザのCookieを使用（例: "chrome"、"firefox"）
+    pub fn cookies_from_browser(mut self, browser: impl Into<String>) -> Self {
+        self.cli.cookie_browser = Some(browser.into());
+        self.cli.no_cookies = false;
+        self
+    }
+
+    /// cookies.txt（Netscape形式）を使用
+    pub fn cookies_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cli.cookies_file = Some(path.into());
+        self
+    }
+
+    /// 帯域制限（例: "5M"）
+    pub fn rate_limit(mut self, rate: impl Into<String>) -> Self {
+        self.cli.rate_limit = Some(rate.into());
+        self
+    }
+
+    /// ダウンロード済みアーカイブファイル（記録済みの動画はスキップ）
+    pub fn download_archive(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cli.download_archive = Some(path.into());
+        self
+    }
+
+    /// 進捗を受け取る関数
+    pub fn on_progress(mut self, callback: impl Fn(&ProgressInfo) + Send + Sync + 'static) -> Self {
+        self.progress_callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// 指定内容を検証してyt-dlpラッパーを作成
+    pub fn build(self) -> Result<YtdlpWrapper> {
+        self.cli.validate().map_err(YtdlError::Config)?;
+        let profile = self
+            .cli
+            .quality_profile(&Config::default())
+            .map_err(YtdlError::Config)?;
+        let wrapper = YtdlpWrapper::new(self.cli, profile, Hooks::default());
+        Ok(match self.progress_callback {
+            Some(callback) => wrapper.with_progress_callback(callback),
+            None => wrapper,
+        })
+    }
+
+    /// ダウンロードを実行
+    pub fn run(self) -> Result<()> {
+        self.build()?.download()
+    }
+
+    /// バックグラウンドでダウンロードを開始（[`DownloadHandle::cancel`] で中止できる）
+    pub fn spawn(self) -> Result<DownloadHandle> {
+        Ok(self.build()?.spawn())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let request = DownloadRequest::new("https://www.youtube.com/watch?v=a")
+            .url("https://www.youtube.com/watch?v=b")
+            .quality(QualityPreset::MaxAudio)
+            .output_dir("downloads");
+        assert_eq!(request.cli.url.len(), 2);
+        assert_eq!(request.cli.quality, "max-audio");
+        assert_eq!(request.cli.output_dir, Some(PathBuf::from("downloads")));
+        assert_eq!(request.cli.retry_count, 3);
+        assert!(request.cli.no_cookies);
+        assert!(request.cli.cookie_browser.is_none());
+
+        let request = request.cookies_from_browser("firefox");
+        assert!(!request.cli.no_cookies);
+        assert!(request.build().is_ok());
+    }
+}
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+/// 探すフォルダの深さ（振り分けルール・アルバムのフォルダを含める）
+const MAX_DEPTH: usize = 3;
+
+/// 途中までダウンロードしたファイル（yt-dlpの ".part"）を探す
+///
+/// 振り分け先などのサブフォルダも探します。
+pub fn find_partial_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect(dir, MAX_DEPTH, &mut files);
+    files.sort();
+    files
+}
+
+/// ファイルの合計サイズ（バイト）
+pub fn total_size(files: &[PathBuf]) -> u64 {
+    files
+        .iter()
+        .filter_map(|file| file.metadata().ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+fn collect(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            if depth > 0 {
+                collect(&path, depth - 1, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "part") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_partial_files() {
+        let root = std::env::temp_dir().join(format!("ytdl-test-resume-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("Music")).unwrap();
+        std::fs::write(root.join("Lecture.f137.mp4.part"), b"abc").unwrap();
+        std::fs::write(root.join("Lecture.f137.mp4.part-Frag3"), b"d").unwrap();
+        std::fs::write(root.join("Music/Song.webm.part"), b"ef").unwrap();
+        std::fs::write(root.join("Done.mp4"), b"g").unwrap();
+
+        let files = find_partial_files(&root);
+        assert_eq!(
+            files,
+            vec![root.join("Lecture.f137.mp4.part"), root.join("Music/Song.webm.part")]
+        );
+        assert_eq!(total_size(&files), 5);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use serde::Deserialize;
+
+use crate::t;
+
+/// 403でやり直すときに順に切り替えるYouTubeのプレイヤークライアント
+const FALLBACK_CLIENTS: &[&str] = &["tv", "web_safari", "mweb"];
+
+/// 失敗の分類（やり直す回数を分けるため）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// 通信エラー・タイムアウト・サーバーエラー（一時的）
+    Network,
+    /// HTTP 403（プレイヤークライアントを変えると成功することがある）
+    Http403,
+    /// 年齢制限
+    AgeRestricted,
+    /// 削除済み・非公開などで視聴できない
+    Unavailable,
+    /// その他
+    Other,
+}
+
+impl ErrorClass {
+    /// yt-dlpのエラーメッセージを分類
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("403") && (message.contains("http error") || message.contains("forbidden")) {
+            ErrorClass::Http403
+        } else if message.contains("confirm your age")
+            || message.contains("age-restricted")
+            || message.contains("age restricted")
+            || message.contains("inappropriate for some users")
+        {
+            ErrorClass::AgeRestricted
+        } else if message.contains("video unavailable")
+            || message.contains("private video")
+            || message.contains("has been removed")
+            || message.contains("members-only")
+            || message.contains("video is not available")
+            || message.contains("not available in your country")
+        {
+            ErrorClass::Unavailable
+        } else if message.contains("timed out")
+            || message.contains("connection")
+            || message.contains("network")
+            || message.contains("name resolution")
+            || message.contains("getaddrinfo")
+            || message.contains("incompleteread")
+            || message.contains("unable to download")
+            || message.contains("http error 5")
+        {
+            ErrorClass::Network
+        } else {
+            ErrorClass::Other
+        }
+    }
+
+    /// 分類の説明
+    pub fn description(&self) -> &str {
+        match self {
+            ErrorClass::Network => t!("通信エラー"),
+            ErrorClass::Http403 => "HTTP 403",
+            ErrorClass::AgeRestricted => t!("年齢制限"),
+            ErrorClass::Unavailable => t!("視聴できない動画"),
+            ErrorClass::Other => t!("その他のエラー"),
+        }
+    }
+}
+
+/// 1つの分類のやり直し方
+///
+/// 設定ファイルでは回数だけ（`network = 5`）か、
+/// テーブル（`http_403 = { attempts = 2, switch_client = true }`）で指定します。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "RetryRuleConfig")]
+pub struct RetryRule {
+    /// やり直す回数（0でやり直さない）
+    pub attempts: usize,
+    /// やり直すたびにYouTubeのプレイヤークライアントを切り替える
+    pub switch_client: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RetryRuleConfig {
+    Attempts(usize),
+    Detailed {
+        attempts: usize,
+        #[serde(default)]
+        switch_client: bool,
+    },
+}
+
+impl From<RetryRuleConfig> for RetryRule {
+    fn from(config: RetryRuleConfig) -> Self {
+        match config {
+            RetryRuleConfig::Attempts(attempts) => Self {
+                attempts,
+                switch_client: false,
+            },
+            RetryRuleConfig::Detailed {
+                attempts,
+                switch_client,
+            } => Self {
+                attempts,
+                switch_client,
+            },
+        }
+    }
+}
+
+impl RetryRule {
+    /// `attempt` 回目（0始まり）のやり直しで使うプレイヤークライアント
+    pub fn player_client(&self, attempt: usize) -> Option<&'static str> {
+        self.switch_client
+            .then(|| FALLBACK_CLIENTS[attempt % FALLBACK_CLIENTS.len()])
+    }
+}
+
+/// 失敗した動画をやり直す回数（設定ファイルの `[retry]`）
+///
+/// 指定しなかった分類は `--retry-failed` の回数だけやり直します。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryPolicy {
+    pub network: Option<RetryRule>,
+    pub http_403: Option<RetryRule>,
+    pub age_restricted: Option<RetryRule>,
+    pub unavailable: Option<RetryRule>,
+    pub other: Option<RetryRule>,
+}
+
+impl RetryPolicy {
+    /// 分類のやり直し方（未設定なら `default_attempts` 回）
+    pub fn rule(&self, class: ErrorClass, default_attempts: usize) -> RetryRule {
+        let rule = match class {
+            ErrorClass::Network => self.network,
+            ErrorClass::Http403 => self.http_403,
+            ErrorClass::AgeRestricted => self.age_restricted,
+            ErrorClass::Unavailable => self.unavailable,
+            ErrorClass::Other => self.other,
+        };
+        rule.unwrap_or(RetryRule {
+            attempts: default_attempts,
+            switch_client: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(ErrorClass::classify("HTTP Error 403: Forbidden"), ErrorClass::Http403);
+        assert_eq!(
+            ErrorClass::classify("Sign in to confirm your age. This video may be inappropriate for some users."),
+            ErrorClass::AgeRestricted
+        );
+        assert_eq!(ErrorClass::classify("Video unavailable"), ErrorClass::Unavailable);
+        assert_eq!(
+            ErrorClass::classify("Unable to download webpage: <urlopen error timed out>"),
+            ErrorClass::Network
+        );
+        assert_eq!(ErrorClass::classify("Requested format is not available"), ErrorClass::Other);
+        assert_eq!(ErrorClass::classify("Postprocessing: Conversion failed!"), ErrorClass::Other);
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let policy: RetryPolicy = toml::from_str(
+            r#"
+            network = 5
+            http_403 = { attempts = 2, switch_client = true }
+            age_restricted = 0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.rule(ErrorClass::Network, 1).attempts, 5);
+        assert_eq!(policy.rule(ErrorClass::AgeRestricted, 1).attempts, 0);
+        assert_eq!(policy.rule(ErrorClass::Other, 1).attempts, 1);
+
+        let rule = policy.rule(ErrorClass::Http403, 1);
+        assert_eq!(rule.attempts, 2);
+        assert_eq!(rule.player_client(0), Some("tv"));
+        assert_eq!(rule.player_client(1), Some("web_safari"));
+        assert_eq!(policy.rule(ErrorClass::Network, 1).player_client(0), None);
+    }
+}
@@ -0,0 +1,214 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::metadata::VideoInfo;
+use crate::t;
+
+/// 比較演算子
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// 部分一致（大文字小文字を区別しない）
+    Contains,
+}
+
+/// 演算子の文字列表現（長いものから順に照合）
+const OPS: &[(&str, Op)] = &[
+    (">=", Op::Ge),
+    ("<=", Op::Le),
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("~=", Op::Contains),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+];
+
+/// メタデータに基づく出力先の振り分けルール
+///
+/// 書式: `<フィールド> <演算子> <値> -> <ディレクトリ>`
+/// 例: `duration > 60m -> Lectures/`、`uploader == X -> Music/X/`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RouteRule {
+    field: String,
+    op: Op,
+    value: String,
+    /// 振り分け先（出力先ディレクトリからの相対パス、または絶対パス）
+    pub dir: PathBuf,
+}
+
+impl TryFrom<String> for RouteRule {
+    type Error = String;
+
+    /// 設定ファイルでは `routes = ["duration > 60m -> Lectures/"]` のように文字列で指定
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        Self::parse(&rule)
+    }
+}
+
+impl RouteRule {
+    /// ルール文字列をパース
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let (condition, dir) = rule
+            .split_once("->")
+            .ok_or_else(|| t!("ルールに '->' がありません: {}", rule))?;
+
+        let dir = dir.trim();
+        if dir.is_empty() {
+            return Err(t!("振り分け先が空です: {}", rule));
+        }
+
+        let (pos, token, op) = OPS
+            .iter()
+            .filter_map(|(token, op)| condition.find(token).map(|pos| (pos, *token, *op)))
+            .min_by_key(|(pos, token, _)| (*pos, std::cmp::Reverse(token.len())))
+            .ok_or_else(|| t!("ルールに演算子がありません: {}", rule))?;
+
+        let field = condition[..pos].trim().to_lowercase();
+        let value = condition[pos + token.len()..].trim().to_string();
+
+        if !matches!(field.as_str(), "duration" | "uploader" | "channel" | "title") {
+            return Err(t!(
+                "未対応のフィールド: {}（duration, uploader, channel, title が使用可能）",
+                field
+            ));
+        }
+        if field == "duration" {
+            if parse_duration(&value).is_none() {
+                return Err(t!("再生時間の値が不正です: {}", value));
+            }
+        } else if matches!(op, Op::Gt | Op::Ge | Op::Lt | Op::Le) {
+            return Err(t!("{} には大小比較を使用できません", field));
+        }
+
+        Ok(Self {
+            field,
+            op,
+            value,
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    /// 動画情報がルールに一致するか判定
+    pub fn matches(&self, info: &VideoInfo) -> bool {
+        if self.field == "duration" {
+            let (Some(actual), Some(expected)) = (info.duration, parse_duration(&self.value)) else {
+                return false;
+            };
+            return match self.op {
+                Op::Eq => actual == expected,
+                Op::Ne => actual != expected,
+                Op::Gt => actual > expected,
+                Op::Ge => actual >= expected,
+                Op::Lt => actual < expected,
+                Op::Le => actual <= expected,
+                Op::Contains => false,
+            };
+        }
+
+        let actual = match self.field.as_str() {
+            "uploader" => info.uploader.as_deref(),
+            "channel" => info.channel.as_deref(),
+            "title" => info.title.as_deref(),
+            _ => None,
+        };
+        let Some(actual) = actual else {
+            return false;
+        };
+
+        match self.op {
+            Op::Eq => actual == self.value,
+            Op::Ne => actual != self.value,
+            Op::Contains => actual.to_lowercase().contains(&self.value.to_lowercase()),
+            _ => false,
+        }
+    }
+
+    /// 最初に一致したルールの振り分け先を取得
+    pub fn find_dir<'a>(rules: &'a [RouteRule], info: &VideoInfo) -> Option<&'a PathBuf> {
+        rules.iter().find(|rule| rule.matches(info)).map(|rule| &rule.dir)
+    }
+}
+
+/// 再生時間をパース（例: "90" -> 90秒, "60m" -> 3600秒, "1h" -> 3600秒）
+fn parse_duration(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last()? {
+        (i, 's') => (&value[..i], 1.0),
+        (i, 'm') => (&value[..i], 60.0),
+        (i, 'h') => (&value[..i], 3600.0),
+        _ => (value, 1.0),
+    };
+    number.trim().parse::<f64>().ok().map(|n| n * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(duration: Option<f64>, uploader: Option<&str>) -> VideoInfo {
+        VideoInfo::from_json_line(
+            &serde_json::json!({
+                "id": "x",
+                "duration": duration,
+                "uploader": uploader,
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = RouteRule::parse("duration > 60m -> Lectures/").unwrap();
+        assert_eq!(rule.field, "duration");
+        assert_eq!(rule.op, Op::Gt);
+        assert_eq!(rule.dir, PathBuf::from("Lectures/"));
+
+        let rule = RouteRule::parse("duration>=90 -> Long").unwrap();
+        assert_eq!(rule.op, Op::Ge);
+
+        assert!(RouteRule::parse("duration > 60m").is_err());
+        assert!(RouteRule::parse("views > 100 -> Popular/").is_err());
+        assert!(RouteRule::parse("uploader > X -> Music/").is_err());
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let long = RouteRule::parse("duration > 60m -> Lectures/").unwrap();
+        assert!(long.matches(&video(Some(4000.0), None)));
+        assert!(!long.matches(&video(Some(300.0), None)));
+        assert!(!long.matches(&video(None, None)));
+
+        let music = RouteRule::parse("uploader == Some Band -> Music/Some Band/").unwrap();
+        assert!(music.matches(&video(None, Some("Some Band"))));
+        assert!(!music.matches(&video(None, Some("Other"))));
+
+        let contains = RouteRule::parse("uploader ~= band -> Music/").unwrap();
+        assert!(contains.matches(&video(None, Some("Some Band"))));
+    }
+
+    #[test]
+    fn test_find_dir_first_match_wins() {
+        let rules = vec![
+            RouteRule::parse("duration > 60m -> Lectures/").unwrap(),
+            RouteRule::parse("duration > 10m -> Long/").unwrap(),
+        ];
+        let dir = RouteRule::find_dir(&rules, &video(Some(4000.0), None));
+        assert_eq!(dir, Some(&PathBuf::from("Lectures/")));
+        assert!(RouteRule::find_dir(&rules, &video(Some(60.0), None)).is_none());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Some(90.0));
+        assert_eq!(parse_duration("60m"), Some(3600.0));
+        assert_eq!(parse_duration("1.5h"), Some(5400.0));
+        assert_eq!(parse_duration("abc"), None);
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::error::{Result, YtdlError};
+use crate::pause;
+use crate::t;
+
+/// 親プロセスの生存を確認する間隔
+const PARENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 親プロセスの終了後、yt-dlpの終了を待つ時間（過ぎたら強制終了）
+const PARENT_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 終了要求（SIGTERM）を受け取ったか
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// 終了要求（SIGTERM）の受付を開始
+///
+/// systemdやDockerの停止時に送られるSIGTERMを受け取ると、新しいダウンロードを
+/// 開始せず、実行中のyt-dlpの完了を `grace` の間だけ待ちます。
+/// 時間内に終わらない場合はyt-dlpをffmpegなどの子プロセスも含めて終了させます（途中のファイルは次回やり直し、
+/// アーカイブには完了した動画だけが記録されます）。
+#[cfg(unix)]
+pub fn install_handler(grace: Duration) {
+    use signal_hook::consts::SIGTERM;
+    use signal_hook::iterator::Signals;
+
+    let mut signals = match Signals::new([SIGTERM]) {
+        Ok(signals) => signals,
+        Err(e) => {
+            eprintln!("{}", t!("警告: シグナルハンドラを登録できません: {}", e));
+            return;
+        }
+    };
+
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            if SHUTDOWN_REQUESTED.swap(true, Ordering::SeqCst) {
+                // 2回目の終了要求はすぐに終了させる
+                pause::terminate_children();
+                continue;
+            }
+            eprintln!(
+                "{}",
+                t!("\n🛑 終了要求を受け付けました。実行中のダウンロードの完了を待ちます（最大{}秒）", grace.as_secs())
+            );
+            std::thread::spawn(move || {
+                std::thread::sleep(grace);
+                if pause::has_active_children() {
+                    eprintln!("{}", t!("\n🛑 待機時間を過ぎたため、ダウンロードを中断します"));
+                    pause::terminate_children();
+                }
+            });
+        }
+    });
+}
+
+/// 終了要求の受付を開始（Unix以外では未対応）
+#[cfg(not(unix))]
+pub fn install_handler(_grace: Duration) {}
+
+/// 終了要求を受け取ったか
+pub fn is_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// 親プロセス（`--parent-pid`）の終了の監視を開始
+///
+/// 起動したGUIやスクリプトが終了した場合、yt-dlpやffmpegがバックグラウンドに残らないよう
+/// 実行中のyt-dlpを子プロセスも含めて終了させてから、このプロセスも終了します。
+/// yt-dlpは別のプロセスグループで動いているため、終了したことを確認してから終了します。
+pub fn watch_parent(pid: u32) -> Result<()> {
+    if !process_alive(pid) {
+        return Err(YtdlError::Other(t!(
+            "親プロセス(PID {})が見つかりません",
+            pid
+        )));
+    }
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(PARENT_POLL_INTERVAL);
+        if !process_alive(pid) {
+            eprintln!("{}", t!("\n🛑 親プロセス(PID {})が終了したため、ダウンロードを中止します", pid));
+            pause::terminate_children();
+            let deadline = std::time::Instant::now() + PARENT_EXIT_TIMEOUT;
+            while pause::has_active_children() && std::time::Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            pause::kill_children();
+            std::process::exit(1);
+        }
+    });
+    Ok(())
+}
+
+/// プロセスが存在するか
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: シグナル0は送信せずに存在確認のみ行う
+    let result = unsafe { libc::kill(pid, 0) };
+    // 権限がなくて送れない場合（EPERM）も、プロセスは存在する
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// プロセスが存在するか
+#[cfg(not(unix))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)))
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+        assert!(!process_alive(999_999_999));
+    }
+}
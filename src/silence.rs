@@ -0,0 +1,167 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// 無音とみなす音量
+const NOISE_THRESHOLD: &str = "-50dB";
+
+/// 無音とみなす最短の長さ（秒）。曲間などの短い無音は残す
+const MIN_SILENCE_SECS: f64 = 1.0;
+
+/// 先頭・末尾とみなす誤差（秒）
+const EDGE_TOLERANCE_SECS: f64 = 0.05;
+
+/// 音声ファイルの先頭・末尾の無音を削除（`--trim-silence`）
+///
+/// ffmpegのsilencedetectで無音の区間を調べ、先頭・末尾にある場合のみ切り取ります。
+/// 途中の無音はそのままです。タグ・カバー画像は引き継がれます。
+/// 削除した場合はtrueを返します。
+pub fn trim_silence(path: &Path) -> Result<bool> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostdin", "-i"])
+        .arg(path)
+        .arg("-af")
+        .arg(format!("silencedetect=noise={}:d={}", NOISE_THRESHOLD, MIN_SILENCE_SECS))
+        .args(["-f", "null", "-"])
+        .output()
+        .map_err(|e| YtdlError::ProcessError(t!("ffmpegの起動失敗: {}", e)))?;
+    if !output.status.success() {
+        return Err(YtdlError::ProcessError(t!(
+            "無音を検出できませんでした: {}",
+            path.display()
+        )));
+    }
+
+    let Some((start, end)) = trim_range(&String::from_utf8_lossy(&output.stderr)) else {
+        return Ok(false);
+    };
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".trimming-{}", file_name));
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-loglevel", "error", "-nostdin", "-i"])
+        .arg(path)
+        .arg("-ss")
+        .arg(format!("{:.3}", start));
+    if let Some(end) = end {
+        cmd.arg("-to").arg(format!("{:.3}", end));
+    }
+    let status = cmd
+        .args(["-map", "0", "-c", "copy"])
+        .arg(&temp)
+        .status()
+        .map_err(|e| YtdlError::ProcessError(t!("ffmpegの起動失敗: {}", e)))?;
+
+    if !status.success() {
+        let _ = std::fs::remove_file(&temp);
+        return Err(YtdlError::ProcessError(t!(
+            "無音を削除できませんでした: {}",
+            path.display()
+        )));
+    }
+    std::fs::rename(&temp, path)?;
+    Ok(true)
+}
+
+/// silencedetectの出力から残す範囲（開始秒, 終了秒）を求める
+///
+/// 先頭・末尾に無音がなければNone。終了がNoneならファイルの最後まで残します。
+fn trim_range(output: &str) -> Option<(f64, Option<f64>)> {
+    let duration = output.lines().find_map(parse_duration);
+
+    // (無音の開始, 無音の終了) の一覧（ファイルの最後まで無音なら終了はNone）
+    let mut silences: Vec<(f64, Option<f64>)> = Vec::new();
+    for line in output.lines() {
+        if let Some(start) = field(line, "silence_start:") {
+            silences.push((start, None));
+        } else if let Some(end) = field(line, "silence_end:") {
+            if let Some(last) = silences.last_mut() {
+                last.1 = Some(end);
+            }
+        }
+    }
+
+    let start = match silences.first() {
+        Some((start, Some(end))) if *start <= EDGE_TOLERANCE_SECS => *end,
+        _ => 0.0,
+    };
+    // すべて無音なら何もしない
+    if duration.is_some_and(|duration| start >= duration - EDGE_TOLERANCE_SECS) {
+        return None;
+    }
+    let end = silences.last().and_then(|(silence_start, silence_end)| {
+        let reaches_end = match (silence_end, duration) {
+            (None, _) => true,
+            (Some(end), Some(duration)) => *end >= duration - EDGE_TOLERANCE_SECS,
+            (Some(_), None) => false,
+        };
+        (reaches_end && *silence_start > start).then_some(*silence_start)
+    });
+
+    (start > 0.0 || end.is_some()).then_some((start, end))
+}
+
+/// "silence_end: 2.5 | silence_duration: 2.5" のような行から値を取り出す
+fn field(line: &str, name: &str) -> Option<f64> {
+    let (_, rest) = line.split_once(name)?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// "  Duration: 00:03:25.48, start: ..." の行から長さ（秒）を取り出す
+fn parse_duration(line: &str) -> Option<f64> {
+    let rest = line.trim_start().strip_prefix("Duration:")?;
+    let time = rest.split(',').next()?.trim();
+    let mut parts = time.split(':').map(|part| part.parse::<f64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_range() {
+        let output = "Input #0, mp3, from 'stream.mp3':
+  Duration: 00:01:40.00, start: 0.025057, bitrate: 128 kb/s
+[silencedetect @ 0x1] silence_start: 0
+[silencedetect @ 0x1] silence_end: 12.5 | silence_duration: 12.5
+[silencedetect @ 0x1] silence_start: 50.2
+[silencedetect @ 0x1] silence_end: 52.0 | silence_duration: 1.8
+[silencedetect @ 0x1] silence_start: 95.75
+[silencedetect @ 0x1] silence_end: 100 | silence_duration: 4.25
+";
+        assert_eq!(trim_range(output), Some((12.5, Some(95.75))));
+
+        // 末尾の無音の終了が出力されない（古いffmpeg）
+        let output = "  Duration: 00:01:40.00, start: 0.0
+[silencedetect @ 0x1] silence_start: 95.75
+";
+        assert_eq!(trim_range(output), Some((0.0, Some(95.75))));
+
+        // 途中の無音のみ
+        let output = "  Duration: 00:01:40.00, start: 0.0
+[silencedetect @ 0x1] silence_start: 50.2
+[silencedetect @ 0x1] silence_end: 52.0 | silence_duration: 1.8
+";
+        assert_eq!(trim_range(output), None);
+
+        let output = "  Duration: 00:00:10.00, start: 0.0
+[silencedetect @ 0x1] silence_start: 0
+[silencedetect @ 0x1] silence_end: 10 | silence_duration: 10
+";
+        assert_eq!(trim_range(output), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("  Duration: 01:02:03.50, start: 0.0"), Some(3723.5));
+        assert_eq!(parse_duration("  Duration: N/A, bitrate: N/A"), None);
+    }
+}
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::cli::SpecifiedArgs;
+
+/// サイトごとの設定（設定ファイルの `[site."twitch.tv"]`）
+///
+/// URLのホスト名が一致する（サブドメインを含む）場合に適用します。
+/// コマンドライン引数・環境変数で指定したものが優先され、`[defaults]` やプロファイルより優先されます。
+///
+/// ```toml
+/// [site."twitch.tv"]
+/// format = "best"
+/// output = "D:/Twitch"
+/// output_template = "%(uploader)s/%(upload_date)s %(title)s.%(ext)s"
+/// ytdlp_args = ["--live-from-start"]
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteConfig {
+    /// yt-dlpのフォーマット指定（品質プリセットのフォーマットの代わり）
+    pub format: Option<String>,
+    /// 出力先ディレクトリ（`--output`）
+    pub output: Option<PathBuf>,
+    /// ファイル名のテンプレート（`--output-template`）
+    pub output_template: Option<String>,
+    /// yt-dlpに追加する引数
+    #[serde(default)]
+    pub ytdlp_args: Vec<String>,
+}
+
+impl SiteConfig {
+    /// コマンドライン引数・環境変数で指定したオプションを除く
+    pub fn without_specified(&self, specified: &SpecifiedArgs) -> Self {
+        Self {
+            format: self.format.clone(),
+            output: self.output.clone().filter(|_| !specified.contains("output_dir")),
+            output_template: self
+                .output_template
+                .clone()
+                .filter(|_| !specified.contains("output_template")),
+            ytdlp_args: self.ytdlp_args.clone(),
+        }
+    }
+}
+
+/// URLに一致するサイトの設定の位置（最も長いホスト名で一致したもの）
+pub fn find(sites: &[(String, SiteConfig)], url: &str) -> Option<usize> {
+    let host = host(url)?;
+    sites
+        .iter()
+        .enumerate()
+        .filter(|(_, (site, _))| {
+            let site = normalize(site);
+            host == site || host.ends_with(&format!(".{}", site))
+        })
+        .max_by_key(|(_, (site, _))| site.len())
+        .map(|(index, _)| index)
+}
+
+/// URLのホスト名（小文字、先頭の "www." を除く）
+pub fn host(url: &str) -> Option<String> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then(|| normalize(host))
+}
+
+fn normalize(host: &str) -> String {
+    let host = host.trim().to_lowercase();
+    host.strip_prefix("www.").map(str::to_string).unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host() {
+        assert_eq!(host("https://www.twitch.tv/videos/123").as_deref(), Some("twitch.tv"));
+        assert_eq!(host("https://user@Example.com:8080/path").as_deref(), Some("example.com"));
+        assert_eq!(host("not a url"), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let sites = vec![
+            ("twitch.tv".to_string(), SiteConfig::default()),
+            ("youtube.com".to_string(), SiteConfig::default()),
+            ("music.youtube.com".to_string(), SiteConfig::default()),
+        ];
+        assert_eq!(find(&sites, "https://www.twitch.tv/videos/1"), Some(0));
+        assert_eq!(find(&sites, "https://m.youtube.com/watch?v=x"), Some(1));
+        assert_eq!(find(&sites, "https://music.youtube.com/watch?v=x"), Some(2));
+        assert_eq!(find(&sites, "https://youtu.be/x"), None);
+        assert_eq!(find(&sites, "https://nottwitch.tv/x"), None);
+    }
+
+    #[test]
+    fn test_config() {
+        let site: SiteConfig = toml::from_str(
+            r#"
+            format = "best"
+            output = "Twitch"
+            ytdlp_args = ["--live-from-start"]
+            "#,
+        )
+        .unwrap();
+        let mut specified = SpecifiedArgs::default();
+        specified.insert("output_dir");
+        let site = site.without_specified(&specified);
+        assert_eq!(site.format.as_deref(), Some("best"));
+        assert_eq!(site.output, None);
+        assert_eq!(site.ytdlp_args, vec!["--live-from-start"]);
+    }
+}
@@ -0,0 +1,158 @@
+/// ダウンロード中のスリープ防止
+///
+/// 保持している間はシステムのスリープ（macOSではApp Napも）を防ぎ、破棄すると解除します。
+/// 対応状況: macOS（IOKitの電源アサーション）、Linux（systemd-inhibit）、Windows（SetThreadExecutionState）
+pub struct SleepInhibitor {
+    #[cfg(target_os = "macos")]
+    assertions: Vec<macos::Assertion>,
+    /// スリープを防いでいる間だけ実行する systemd-inhibit（標準入力を閉じると終了する）
+    #[cfg(target_os = "linux")]
+    inhibit: std::process::Child,
+    /// 設定したスレッドで解除する必要がある
+    #[cfg(target_os = "windows")]
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl SleepInhibitor {
+    /// スリープを防ぐ（未対応のOS・失敗した場合はNone）
+    ///
+    /// `reason` はOSの電源管理の画面（macOSの `pmset -g assertions`、Linuxの `systemd-inhibit --list` など）に表示されます。
+    pub fn acquire(reason: &str) -> Option<Self> {
+        #[cfg(target_os = "macos")]
+        {
+            // アイドル時のスリープとApp Napを防ぎ、電源接続中は蓋を閉じてもスリープしない
+            let assertions: Vec<_> = [macos::PREVENT_IDLE_SLEEP, macos::PREVENT_SYSTEM_SLEEP]
+                .iter()
+                .filter_map(|kind| macos::Assertion::create(kind, reason))
+                .collect();
+            (!assertions.is_empty()).then_some(Self { assertions })
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let mut inhibit = std::process::Command::new("systemd-inhibit")
+                .args(["--what=sleep:idle", "--who=ytdl", "--mode=block"])
+                .arg(format!("--why={}", reason))
+                .arg("cat")
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .ok()?;
+            // logindに接続できない場合などはすぐに終了する
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            match inhibit.try_wait() {
+                Ok(None) => Some(Self { inhibit }),
+                _ => None,
+            }
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let _ = reason;
+            // SAFETY: 実行状態のフラグを設定するだけ（破棄時に同じスレッドで戻す）
+            let previous = unsafe {
+                windows::SetThreadExecutionState(windows::ES_CONTINUOUS | windows::ES_SYSTEM_REQUIRED)
+            };
+            (previous != 0).then_some(Self {
+                _not_send: std::marker::PhantomData,
+            })
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+        {
+            let _ = reason;
+            None
+        }
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        {
+            // 標準入力を閉じると cat が終了し、systemd-inhibit も終了する
+            self.inhibit.stdin.take();
+            let _ = self.inhibit.wait();
+        }
+        #[cfg(target_os = "windows")]
+        // SAFETY: acquire() と同じスレッドで、継続的な設定を解除する
+        unsafe {
+            windows::SetThreadExecutionState(windows::ES_CONTINUOUS);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    pub const ES_CONTINUOUS: u32 = 0x8000_0000;
+    pub const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn SetThreadExecutionState(flags: u32) -> u32;
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void, CString};
+
+    /// アイドル時のスリープを防ぐ（App Napも防がれる）
+    pub const PREVENT_IDLE_SLEEP: &str = "PreventUserIdleSystemSleep";
+    /// システムのスリープを防ぐ（電源接続中のみ有効）
+    pub const PREVENT_SYSTEM_SLEEP: &str = "PreventSystemSleep";
+
+    const ASSERTION_LEVEL_ON: u32 = 255;
+    const STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(allocator: *const c_void, string: *const c_char, encoding: u32) -> *const c_void;
+        fn CFRelease(object: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            kind: *const c_void,
+            level: u32,
+            name: *const c_void,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    /// 電源アサーション（破棄すると解除）
+    pub struct Assertion(u32);
+
+    impl Assertion {
+        pub fn create(kind: &str, reason: &str) -> Option<Self> {
+            let kind = CString::new(kind).ok()?;
+            let reason = CString::new(reason).ok()?;
+            // SAFETY: 作成したCFStringはアサーションの作成後に解放し、IDは破棄時に解除する
+            unsafe {
+                let kind = CFStringCreateWithCString(std::ptr::null(), kind.as_ptr(), STRING_ENCODING_UTF8);
+                let name = CFStringCreateWithCString(std::ptr::null(), reason.as_ptr(), STRING_ENCODING_UTF8);
+                let mut id = 0;
+                let result = if kind.is_null() || name.is_null() {
+                    -1
+                } else {
+                    IOPMAssertionCreateWithName(kind, ASSERTION_LEVEL_ON, name, &mut id)
+                };
+                for string in [kind, name] {
+                    if !string.is_null() {
+                        CFRelease(string);
+                    }
+                }
+                (result == 0).then_some(Self(id))
+            }
+        }
+    }
+
+    impl Drop for Assertion {
+        fn drop(&mut self) {
+            // SAFETY: create() で作成したアサーションを1回だけ解除する
+            unsafe {
+                IOPMAssertionRelease(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use serde::Serialize;
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{Result, YtdlError};
+use crate::progress_parser::ProgressInfo;
+use crate::t;
+
+/// 現在の処理状況（`--status-socket` に接続すると1行のJSONで返す）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunStatus {
+    /// 状態（idle / downloading / postprocessing / uploading / completed / failed）
+    pub state: String,
+    /// 処理中のURL
+    pub url: Option<String>,
+    /// 処理中の動画のタイトル
+    pub title: Option<String>,
+    /// プレイリスト内の位置
+    pub playlist_index: Option<u32>,
+    /// プレイリストの動画数
+    pub playlist_count: Option<u32>,
+    /// 進捗率（0.0 ~ 100.0）
+    pub percent: f64,
+    /// ダウンロード済みサイズ（バイト）
+    pub downloaded_bytes: Option<u64>,
+    /// 総サイズ（バイト）
+    pub total_bytes: Option<u64>,
+    /// ダウンロード速度（バイト/秒）
+    pub speed: Option<f64>,
+    /// 残り時間（秒）
+    pub eta: Option<u64>,
+    /// 直近のエラー
+    pub error: Option<String>,
+    /// 最終更新（UNIX時間）
+    pub updated_at: u64,
+}
+
+impl RunStatus {
+    /// 進捗を反映
+    pub fn set_progress(&mut self, progress: &ProgressInfo) {
+        self.percent = progress.percent;
+        self.downloaded_bytes = progress.downloaded_bytes;
+        self.total_bytes = progress.total_bytes;
+        self.speed = progress.speed;
+        self.eta = progress.eta;
+    }
+
+    /// 次の動画に移る（進捗をリセット）
+    pub fn start_item(&mut self, title: Option<String>, index: Option<u32>, count: Option<u32>) {
+        *self = Self {
+            state: "downloading".to_string(),
+            url: self.url.take(),
+            title,
+            playlist_index: index,
+            playlist_count: count,
+            ..Default::default()
+        };
+    }
+}
+
+/// 処理状況を問い合わせに応じて返すサーバー
+///
+/// Unixソケットのパス、または "127.0.0.1:PORT" のようなアドレス（TCP、Windowsなど）で待ち受けます。
+/// 接続するとその時点の [`RunStatus`] を1行のJSONで返して切断します。
+pub struct StatusServer {
+    status: Arc<Mutex<RunStatus>>,
+    /// 終了時に削除するソケットファイル
+    socket_path: Option<PathBuf>,
+}
+
+impl StatusServer {
+    /// 待ち受けを開始
+    pub fn start(target: &str) -> Result<Self> {
+        let status = Arc::new(Mutex::new(RunStatus {
+            state: "idle".to_string(),
+            ..Default::default()
+        }));
+
+        if let Ok(addr) = target.parse::<SocketAddr>() {
+            let listener = TcpListener::bind(addr).map_err(|e| bind_error(target, e))?;
+            let shared = Arc::clone(&status);
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let _ = stream.write_all(&snapshot(&shared));
+                }
+            });
+            return Ok(Self {
+                status,
+                socket_path: None,
+            });
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::net::{UnixListener, UnixStream};
+
+            let path = PathBuf::from(target);
+            // 前回の実行で残ったソケット（接続できない）は削除してよい
+            if path.exists() && UnixStream::connect(&path).is_err() {
+                let _ = std::fs::remove_file(&path);
+            }
+            let listener = UnixListener::bind(&path).map_err(|e| bind_error(target, e))?;
+            let shared = Arc::clone(&status);
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let _ = stream.write_all(&snapshot(&shared));
+                }
+            });
+            Ok(Self {
+                status,
+                socket_path: Some(path),
+            })
+        }
+        #[cfg(not(unix))]
+        {
+            Err(YtdlError::Other(t!(
+                "{}: このOSではソケットのパスは指定できません。\"127.0.0.1:ポート番号\" を指定してください",
+                target
+            )))
+        }
+    }
+
+    /// 処理状況を更新
+    pub fn update(&self, update: impl FnOnce(&mut RunStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            update(&mut status);
+            status.updated_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+        }
+    }
+}
+
+impl Drop for StatusServer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// 現在の状態をJSON（改行付き）にする
+fn snapshot(status: &Mutex<RunStatus>) -> Vec<u8> {
+    let status = status.lock().map(|status| status.clone()).unwrap_or_default();
+    let mut json = serde_json::to_vec(&status).unwrap_or_default();
+    json.push(b'\n');
+    json
+}
+
+fn bind_error(target: &str, e: std::io::Error) -> YtdlError {
+    YtdlError::Other(t!("状態取得用のソケット({})を作成できません: {}", target, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let status = Mutex::new(RunStatus {
+            url: Some("https://example.com".to_string()),
+            percent: 100.0,
+            ..Default::default()
+        });
+        status
+            .lock()
+            .unwrap()
+            .start_item(Some("Title".to_string()), Some(2), Some(5));
+
+        let json: serde_json::Value = serde_json::from_slice(&snapshot(&status)).unwrap();
+        assert_eq!(json["state"], "downloading");
+        assert_eq!(json["url"], "https://example.com");
+        assert_eq!(json["title"], "Title");
+        assert_eq!(json["playlist_index"], 2);
+        assert_eq!(json["percent"], 0.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket() {
+        use std::io::Read;
+
+        let path = std::env::temp_dir().join(format!("ytdl-test-status-{}.sock", std::process::id()));
+        let server = StatusServer::start(&path.to_string_lossy()).unwrap();
+        server.update(|status| status.url = Some("https://example.com".to_string()));
+
+        let mut response = String::new();
+        std::os::unix::net::UnixStream::connect(&path)
+            .unwrap()
+            .read_to_string(&mut response)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["state"], "idle");
+        assert_eq!(json["url"], "https://example.com");
+
+        drop(server);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Result, YtdlError};
+use crate::t;
+
+/// ストーリーボードのフォーマット（YouTubeでは "sb0" が最も高解像度）
+const FORMAT: &str = "sb0/sb1/sb2/sb3";
+
+/// 動画のストーリーボード（シーク時のプレビュー画像）を保存（`--write-storyboards`）
+///
+/// `cmd` はCookieなどを設定済みのyt-dlpのコマンド。動画と同じフォルダに
+/// "タイトル.storyboard.mhtml" として保存します（MHTMLにはタイル状に並んだJPEG画像が含まれます）。
+/// 保存したファイルを返します。ストーリーボードがない動画はNone。
+pub fn download(mut cmd: Command, url: &str, video: &Path) -> Result<Option<PathBuf>> {
+    let output = cmd
+        .args(["-f", FORMAT, "--no-playlist", "--quiet", "--no-warnings", "--no-simulate"])
+        .args(["--print", "after_move:filepath"])
+        .arg("-o")
+        .arg(output_template(video))
+        .arg(url)
+        .output()
+        .map_err(|e| YtdlError::ProcessError(t!("yt-dlpの起動失敗: {}", e)))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        if stderr.contains("Requested format is not available") {
+            return Ok(None);
+        }
+        return Err(YtdlError::DownloadFailed(t!(
+            "ストーリーボードを保存できませんでした: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(PathBuf::from))
+}
+
+/// 保存先のテンプレート（動画の拡張子を ".storyboard.%(ext)s" に置き換える）
+fn output_template(video: &Path) -> String {
+    let stem = video.with_extension("").to_string_lossy().replace('%', "%%");
+    format!("{}.storyboard.%(ext)s", stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_template() {
+        assert_eq!(
+            output_template(Path::new("/tmp/out/Lecture 100%.mp4")),
+            "/tmp/out/Lecture 100%%.storyboard.%(ext)s"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::metadata::VideoInfo;
+
+/// ダウンロードする字幕の言語（yt-dlpの `--sub-lang`）
+pub const SUB_LANGS: &str = "ja,en";
+
+/// 字幕の形式の優先順（yt-dlpの `--sub-format`）。どのコンテナにも埋め込みやすい形式を優先
+pub const SUB_FORMAT: &str = "vtt/srt/best";
+
+/// 埋め込めない字幕がある場合に使うコンテナ（すべての形式を埋め込める）
+pub const FALLBACK_CONTAINER: &str = "mkv";
+
+/// 字幕を埋め込む場合のコンテナを決める（`--embed-subs`）
+///
+/// ダウンロードされる字幕の形式が、指定（またはyt-dlpが選ぶ）コンテナに埋め込めない場合は
+/// MKVを返し、その理由（埋め込めない形式）も返します。そのまま埋め込める場合はNone。
+pub fn embed_container(info: &VideoInfo, container: Option<&str>) -> Option<(&'static str, String)> {
+    let container = container.or(info.ext.as_deref())?;
+    chosen_formats(info)
+        .into_iter()
+        .find(|format| !can_embed(container, format))
+        .map(|format| (FALLBACK_CONTAINER, format.to_string()))
+}
+
+/// コンテナに字幕の形式を埋め込めるか
+///
+/// MP4はテキストの字幕（mov_textに変換）、WebMはWebVTTのみ埋め込めます。
+pub fn can_embed(container: &str, format: &str) -> bool {
+    match container {
+        "mp4" | "m4v" | "mov" => matches!(format, "vtt" | "srt" | "ttml" | "srv1" | "srv2" | "srv3"),
+        "webm" => format == "vtt",
+        _ => true,
+    }
+}
+
+/// ダウンロードされる字幕の形式（言語ごと、`SUB_FORMAT` の優先順で選択）
+///
+/// 字幕がない言語は自動生成の字幕を使います。
+fn chosen_formats(info: &VideoInfo) -> Vec<&str> {
+    SUB_LANGS
+        .split(',')
+        .filter_map(|lang| {
+            let tracks = info
+                .subtitles
+                .get(lang)
+                .filter(|tracks| !tracks.is_empty())
+                .or_else(|| info.automatic_captions.get(lang))?;
+            let exts: Vec<&str> = tracks.iter().map(|track| track.ext.as_str()).collect();
+            SUB_FORMAT
+                .split('/')
+                .find_map(|preferred| match preferred {
+                    // "best" はyt-dlpと同じく最後の形式
+                    "best" => exts.last().copied(),
+                    _ => exts.iter().copied().find(|ext| *ext == preferred),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(ext: &str, subtitles: serde_json::Value) -> VideoInfo {
+        VideoInfo::from_json_line(
+            &serde_json::json!({ "id": "x", "ext": ext, "subtitles": subtitles }).to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_embed_container() {
+        // WebVTTがあればMP4・WebMにそのまま埋め込める
+        let info = video("mp4", serde_json::json!({ "ja": [{ "ext": "json3" }, { "ext": "vtt" }] }));
+        assert_eq!(embed_container(&info, None), None);
+        assert_eq!(embed_container(&info, Some("webm")), None);
+
+        // ASSはMP4に埋め込めない
+        let info = video("mp4", serde_json::json!({ "en": [{ "ext": "ass" }] }));
+        assert_eq!(embed_container(&info, None), Some(("mkv", "ass".to_string())));
+        assert_eq!(embed_container(&info, Some("mkv")), None);
+
+        // SRTはWebMに埋め込めない
+        let info = video("webm", serde_json::json!({ "ja": [{ "ext": "srt" }] }));
+        assert_eq!(embed_container(&info, None), Some(("mkv", "srt".to_string())));
+
+        // 字幕がなければ何もしない
+        let info = video("webm", serde_json::json!({}));
+        assert_eq!(embed_container(&info, None), None);
+    }
+}
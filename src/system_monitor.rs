@@ -0,0 +1,179 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crate::pause::{self, PauseReason};
+use crate::t;
+
+/// 電源・回線状態の確認間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// バッテリー駆動・従量制接続の間はダウンロードを一時停止する監視を開始
+///
+/// 状態が戻ると自動的に再開します。
+/// 対応状況: バッテリー（Windows/macOS/Linux）、従量制接続（Linux/NetworkManager）
+pub fn start_power_monitor() {
+    std::thread::spawn(|| {
+        let mut constrained = false;
+        loop {
+            let reason = constraint_reason();
+            match (&reason, constrained) {
+                (Some(reason), false) => {
+                    eprintln!("{}", t!("\n🔋 {}のため、ダウンロードを一時停止します", reason));
+                    pause::pause(PauseReason::Power);
+                    constrained = true;
+                }
+                (None, true) => {
+                    eprintln!("{}", t!("\n🔌 電源・回線の制約が解除されました"));
+                    pause::resume(PauseReason::Power);
+                    constrained = false;
+                }
+                _ => {}
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// 現在ダウンロードを控えるべき理由（なければNone）
+fn constraint_reason() -> Option<&'static str> {
+    if is_on_battery() {
+        Some(t!("バッテリー駆動中"))
+    } else if is_metered_connection() {
+        Some(t!("従量制接続"))
+    } else {
+        None
+    }
+}
+
+/// バッテリー駆動中か
+#[cfg(target_os = "linux")]
+fn is_on_battery() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+
+    let mut has_battery = false;
+    let mut on_mains = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Battery" => has_battery = true,
+            "Mains" | "USB" => {
+                let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+                if online.trim() == "1" {
+                    on_mains = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    has_battery && !on_mains
+}
+
+/// バッテリー駆動中か
+#[cfg(target_os = "macos")]
+fn is_on_battery() -> bool {
+    Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .map(|output| parse_pmset_on_battery(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(false)
+}
+
+/// バッテリー駆動中か
+#[cfg(target_os = "windows")]
+fn is_on_battery() -> bool {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: 初期化済みの構造体へのポインタを渡すだけ
+    let ok = unsafe { GetSystemPowerStatus(&mut status) } != 0;
+    // ACLineStatus: 0 = オフライン（バッテリー駆動）、128 = バッテリーなし
+    ok && status.ac_line_status == 0 && status.battery_flag != 128
+}
+
+/// バッテリー駆動中か（未対応のOSでは常にfalse）
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn is_on_battery() -> bool {
+    false
+}
+
+/// 従量制接続か（NetworkManagerの判定を使用）
+fn is_metered_connection() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    Command::new("nmcli")
+        .args(["-t", "-f", "GENERAL.STATE,GENERAL.METERED", "device", "show"])
+        .output()
+        .map(|output| parse_nmcli_metered(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(false)
+}
+
+/// `pmset -g batt` の出力からバッテリー駆動中か判定
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset_on_battery(output: &str) -> bool {
+    output
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains("'Battery Power'"))
+}
+
+/// `nmcli -t -f GENERAL.STATE,GENERAL.METERED device show` の出力から
+/// 接続中のデバイスに従量制のものがあるか判定
+fn parse_nmcli_metered(output: &str) -> bool {
+    let mut connected = false;
+    for line in output.lines() {
+        if let Some(state) = line.strip_prefix("GENERAL.STATE:") {
+            // 例: "100 (connected)"
+            connected = state.starts_with("100");
+        } else if let Some(metered) = line.strip_prefix("GENERAL.METERED:") {
+            // 例: "yes", "yes (guessed)", "no", "unknown"
+            if connected && metered.starts_with("yes") {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset_on_battery() {
+        assert!(parse_pmset_on_battery(
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging;"
+        ));
+        assert!(!parse_pmset_on_battery(
+            "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged;"
+        ));
+    }
+
+    #[test]
+    fn test_parse_nmcli_metered() {
+        let output = "GENERAL.STATE:100 (connected)\nGENERAL.METERED:yes (guessed)\n\n\
+                      GENERAL.STATE:10 (unmanaged)\nGENERAL.METERED:unknown\n";
+        assert!(parse_nmcli_metered(output));
+
+        let output = "GENERAL.STATE:100 (connected)\nGENERAL.METERED:no\n\n\
+                      GENERAL.STATE:30 (disconnected)\nGENERAL.METERED:yes\n";
+        assert!(!parse_nmcli_metered(output));
+    }
+}
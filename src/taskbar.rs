@@ -0,0 +1,278 @@
+use dialoguer::console::Term;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::pause;
+
+/// 一時停止の状態を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// タスクバーに表示する状態
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskbarState {
+    /// 進捗（0.0 ~ 100.0）
+    Progress(f64),
+    /// 失敗（赤で表示）
+    Error,
+    /// 表示しない
+    Clear,
+}
+
+/// タスクバーのアイコンへの進捗表示（Windows）
+///
+/// コンソールウィンドウのタスクバーのアイコンに進捗を表示し、一時停止中は黄色、失敗した場合は赤で表示します。
+/// Windows Terminalではエスケープシーケンス（OSC 9;4）でタブとタスクバーに表示します。
+/// 最小化していても状況がわかるよう、COMの呼び出しは専用のスレッドで行います。
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct TaskbarProgress {
+    sender: Option<Sender<TaskbarState>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl TaskbarProgress {
+    /// 表示を開始（Windowsで端末から実行している場合のみ）
+    pub fn start() -> Option<Self> {
+        if !cfg!(windows) || !Term::stderr().is_term() {
+            return None;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            let Some(mut backend) = Backend::open() else {
+                return;
+            };
+            let mut state = TaskbarState::Clear;
+            let mut shown = None;
+            loop {
+                match receiver.recv_timeout(POLL_INTERVAL) {
+                    Ok(next) => state = next,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                let current = (state, pause::is_paused());
+                if shown != Some(current) {
+                    backend.show(current.0, current.1);
+                    shown = Some(current);
+                }
+            }
+            backend.show(TaskbarState::Clear, false);
+        });
+        Some(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    /// 表示を更新
+    pub fn set(&self, state: TaskbarState) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(state);
+        }
+    }
+}
+
+impl Drop for TaskbarProgress {
+    /// 終了後に進捗が残らないよう、表示を消してから終了する
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// OSC 9;4 の状態（0: なし, 1: 通常, 2: エラー, 4: 一時停止）と進捗率
+#[cfg_attr(not(windows), allow(dead_code))]
+fn osc_progress(state: TaskbarState, paused: bool) -> String {
+    let (code, percent) = match state {
+        TaskbarState::Clear => (0, 0),
+        TaskbarState::Error => (2, 100),
+        TaskbarState::Progress(percent) => (if paused { 4 } else { 1 }, percent.clamp(0.0, 100.0) as u32),
+    };
+    format!("\x1b]9;4;{};{}\x07", code, percent)
+}
+
+/// 表示先
+#[cfg_attr(not(windows), allow(dead_code))]
+enum Backend {
+    /// コンソールウィンドウのタスクバーのアイコン（ITaskbarList3）
+    #[cfg(windows)]
+    Taskbar(windows::TaskbarList),
+    /// Windows Terminal（コンソールウィンドウが表示されないため、エスケープシーケンスで表示）
+    Terminal,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl Backend {
+    fn open() -> Option<Self> {
+        if std::env::var_os("WT_SESSION").is_some() {
+            return Some(Backend::Terminal);
+        }
+        #[cfg(windows)]
+        {
+            windows::TaskbarList::open().map(Backend::Taskbar)
+        }
+        #[cfg(not(windows))]
+        {
+            None
+        }
+    }
+
+    fn show(&mut self, state: TaskbarState, paused: bool) {
+        match self {
+            #[cfg(windows)]
+            Backend::Taskbar(list) => list.show(state, paused),
+            Backend::Terminal => {
+                use std::io::Write;
+                let mut stderr = std::io::stderr();
+                let _ = stderr.write_all(osc_progress(state, paused).as_bytes());
+                let _ = stderr.flush();
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+
+    use super::TaskbarState;
+
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+
+    const CLSID_TASKBAR_LIST: Guid = Guid(
+        0x56FD_F344,
+        0xFD6D,
+        0x11D0,
+        [0x95, 0x8A, 0x00, 0x60, 0x97, 0xC9, 0xA0, 0x90],
+    );
+    const IID_ITASKBAR_LIST3: Guid = Guid(
+        0xEA1A_FB91,
+        0x9E28,
+        0x4B86,
+        [0x90, 0xE9, 0x9E, 0x9F, 0x8A, 0x5E, 0xEF, 0xAF],
+    );
+    const COINIT_APARTMENTTHREADED: u32 = 0x2;
+    const CLSCTX_INPROC_SERVER: u32 = 0x1;
+
+    // TBPFLAG
+    const TBPF_NOPROGRESS: u32 = 0x0;
+    const TBPF_NORMAL: u32 = 0x2;
+    const TBPF_ERROR: u32 = 0x4;
+    const TBPF_PAUSED: u32 = 0x8;
+
+    /// ITaskbarList3 の関数テーブル（使用する関数まで）
+    #[repr(C)]
+    struct Vtbl {
+        _query_interface: usize,
+        _add_ref: usize,
+        release: unsafe extern "system" fn(*mut Object) -> u32,
+        hr_init: unsafe extern "system" fn(*mut Object) -> i32,
+        /// AddTab / DeleteTab / ActivateTab / SetActiveAlt / MarkFullscreenWindow
+        _unused: [usize; 5],
+        set_progress_value: unsafe extern "system" fn(*mut Object, isize, u64, u64) -> i32,
+        set_progress_state: unsafe extern "system" fn(*mut Object, isize, u32) -> i32,
+    }
+
+    #[repr(C)]
+    struct Object {
+        vtbl: *const Vtbl,
+    }
+
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoInitializeEx(reserved: *mut c_void, coinit: u32) -> i32;
+        fn CoCreateInstance(
+            clsid: *const Guid,
+            outer: *mut c_void,
+            context: u32,
+            iid: *const Guid,
+            object: *mut *mut c_void,
+        ) -> i32;
+        fn CoUninitialize();
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleWindow() -> isize;
+    }
+
+    /// コンソールウィンドウのタスクバーのアイコン（作成したスレッドでのみ使用する）
+    pub struct TaskbarList {
+        object: *mut Object,
+        window: isize,
+    }
+
+    impl TaskbarList {
+        pub fn open() -> Option<Self> {
+            // SAFETY: このスレッドでCOMを初期化し、取得したインターフェースはこのスレッドでのみ使う
+            unsafe {
+                let window = GetConsoleWindow();
+                if window == 0 || CoInitializeEx(std::ptr::null_mut(), COINIT_APARTMENTTHREADED) < 0 {
+                    return None;
+                }
+                let mut object: *mut c_void = std::ptr::null_mut();
+                let hr = CoCreateInstance(
+                    &CLSID_TASKBAR_LIST,
+                    std::ptr::null_mut(),
+                    CLSCTX_INPROC_SERVER,
+                    &IID_ITASKBAR_LIST3,
+                    &mut object,
+                );
+                let object = object as *mut Object;
+                if hr < 0 || object.is_null() || ((*(*object).vtbl).hr_init)(object) < 0 {
+                    if !object.is_null() {
+                        ((*(*object).vtbl).release)(object);
+                    }
+                    CoUninitialize();
+                    return None;
+                }
+                Some(Self { object, window })
+            }
+        }
+
+        pub fn show(&mut self, state: TaskbarState, paused: bool) {
+            let (flag, percent) = match state {
+                TaskbarState::Clear => (TBPF_NOPROGRESS, None),
+                TaskbarState::Error => (TBPF_ERROR, Some(100)),
+                TaskbarState::Progress(percent) => (
+                    if paused { TBPF_PAUSED } else { TBPF_NORMAL },
+                    Some(percent.clamp(0.0, 100.0) as u64),
+                ),
+            };
+            // SAFETY: open() で取得したインターフェースを、取得したスレッドで呼び出す
+            unsafe {
+                let vtbl = &*(*self.object).vtbl;
+                if let Some(percent) = percent {
+                    (vtbl.set_progress_value)(self.object, self.window, percent, 100);
+                }
+                (vtbl.set_progress_state)(self.object, self.window, flag);
+            }
+        }
+    }
+
+    impl Drop for TaskbarList {
+        fn drop(&mut self) {
+            // SAFETY: open() で取得したインターフェースを1回だけ解放する
+            unsafe {
+                ((*(*self.object).vtbl).release)(self.object);
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc_progress() {
+        assert_eq!(osc_progress(TaskbarState::Progress(42.7), false), "\x1b]9;4;1;42\x07");
+        assert_eq!(osc_progress(TaskbarState::Progress(42.7), true), "\x1b]9;4;4;42\x07");
+        assert_eq!(osc_progress(TaskbarState::Error, false), "\x1b]9;4;2;100\x07");
+        assert_eq!(osc_progress(TaskbarState::Clear, false), "\x1b]9;4;0;0\x07");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::filename::FilenamePolicy;
+use crate::metadata::VideoInfo;
+
+/// このツールで値を決めるテンプレートの変数（yt-dlpにはない変数）
+///
+/// - `%(sanitized_title)s`: ファイル名に使えない文字（"/" を含む）を置き換えたタイトル
+/// - `%(upload_year)s`: アップロードした年（例: 2024）
+/// - `%(channel_slug)s`: チャンネル名を小文字・英数字とハイフンにしたもの（例: "our-lab"）
+/// - `%(quality_preset)s`: 品質プリセットの名前（例: max-video）
+pub const CUSTOM_FIELDS: &[&str] = &["sanitized_title", "upload_year", "channel_slug", "quality_preset"];
+
+/// テンプレートにこのツールの変数が含まれるか
+pub fn uses_custom_fields(template: &str) -> bool {
+    CUSTOM_FIELDS.iter().any(|field| template.contains(&placeholder(field)))
+}
+
+/// 動画のメタデータからこのツールの変数を展開（yt-dlpの変数はそのまま残す）
+pub fn expand(template: &str, info: &VideoInfo, preset: &str) -> String {
+    replace_fields(template, |field| {
+        let value = match field {
+            "sanitized_title" => sanitize_title(info.display_title()),
+            "upload_year" => info.upload_date.as_deref().and_then(upload_year)?,
+            "channel_slug" => slug(info.channel.as_deref().or(info.uploader.as_deref())?),
+            "quality_preset" => preset.to_string(),
+            _ => return None,
+        };
+        // 値に含まれる "%" はテンプレートとして解釈させない
+        Some(value.replace('%', "%%"))
+    })
+}
+
+/// メタデータを取得しない場合の展開（yt-dlpの変数で近いものに置き換える）
+pub fn fallback(template: &str, preset: &str) -> String {
+    replace_fields(template, |field| {
+        Some(match field {
+            "sanitized_title" => "%(title)s".to_string(),
+            "upload_year" => "%(upload_date>%Y)s".to_string(),
+            "channel_slug" => "%(channel,uploader)s".to_string(),
+            "quality_preset" => preset.replace('%', "%%"),
+            _ => return None,
+        })
+    })
+}
+
+fn placeholder(field: &str) -> String {
+    format!("%({})s", field)
+}
+
+/// このツールの変数を置き換える（値がない場合は yt-dlp と同じく "NA"）
+fn replace_fields(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    CUSTOM_FIELDS.iter().fold(template.to_string(), |template, field| {
+        let placeholder = placeholder(field);
+        if template.contains(&placeholder) {
+            template.replace(&placeholder, &value(field).unwrap_or_else(|| "NA".to_string()))
+        } else {
+            template
+        }
+    })
+}
+
+/// ファイル名に使えない文字を置き換えたタイトル（フォルダの区切りにならないよう "/" も置き換える）
+fn sanitize_title(title: &str) -> String {
+    FilenamePolicy::WindowsSafe.apply(&title.replace(['/', '\\'], "_"))
+}
+
+/// "YYYYMMDD" の年
+fn upload_year(date: &str) -> Option<String> {
+    let year = date.get(0..4)?;
+    year.chars().all(|c| c.is_ascii_digit()).then(|| year.to_string())
+}
+
+/// 小文字の英数字とハイフンにする（日本語などはローマ字に変換）
+fn slug(name: &str) -> String {
+    let ascii = FilenamePolicy::Ascii.apply(&name.replace(['/', '\\'], " "));
+    let mut slug = String::new();
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "_".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let info = VideoInfo::from_json_line(
+            r#"{"id": "abc", "title": "AC/DC: 100% Live?", "upload_date": "20240102", "channel": "Our Lab Channel!"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            expand("%(channel_slug)s/%(upload_year)s/%(sanitized_title)s [%(quality_preset)s].%(ext)s", &info, "max-video"),
+            "our-lab-channel/2024/AC_DC_ 100%% Live_ [max-video].%(ext)s"
+        );
+
+        let info = VideoInfo::from_json_line(r#"{"id": "abc", "uploader": "ひらがな"}"#).unwrap();
+        assert_eq!(expand("%(channel_slug)s/%(upload_year)s", &info, "max-video"), "hiragana/NA");
+    }
+
+    #[test]
+    fn test_fallback() {
+        assert!(uses_custom_fields("%(upload_year)s/%(title)s.%(ext)s"));
+        assert!(!uses_custom_fields("%(title)s-%(id)s.%(ext)s"));
+        assert_eq!(
+            fallback("%(upload_year)s/%(sanitized_title)s [%(quality_preset)s].%(ext)s", "podcast"),
+            "%(upload_date>%Y)s/%(title)s [podcast].%(ext)s"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::cancel::CancelToken;
+use crate::error::{Result, YtdlError};
+use crate::pause::{self, PauseReason};
+use crate::t;
+
+/// 通知を更新する間隔（ボタンの操作もこの間隔で受け取る）
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 通知を表示するPowerShellのスクリプト
+///
+/// 標準入力から1行ずつ（タブ区切り）受け取って通知を更新し、押されたボタン（pause / resume / cancel）を標準出力に書きます。
+/// - "progress\t<タイトル>\t<0.0~1.0>\t<進捗率>\t<状況>": 進捗を表示（完了の通知の後なら表示し直す）
+/// - "finish\t<メッセージ>": 完了の通知に置き換える
+/// - "tick": ボタンの操作の確認のみ
+///
+/// 文言は環境変数で受け取ります（スクリプトはASCIIのみにするため）。
+const SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+[Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
+$app = '{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe'
+$notifier = [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($app)
+$values = @{ title = ''; value = '0'; percent = ''; status = '' }
+$sequence = 0
+
+function Escape([string]$text) { [Security.SecurityElement]::Escape($text) }
+
+function New-Data {
+    $data = New-Object Windows.UI.Notifications.NotificationData
+    foreach ($key in $values.Keys) { $data.Values[$key] = $values[$key] }
+    $script:sequence++
+    $data.SequenceNumber = $script:sequence
+    $data
+}
+
+function Show-Progress([bool]$paused) {
+    $toggle = if ($paused) { 'resume' } else { 'pause' }
+    $label = if ($paused) { $env:YTDL_TOAST_RESUME } else { $env:YTDL_TOAST_PAUSE }
+    $xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+    $xml.LoadXml(@"
+<toast scenario="reminder">
+  <visual><binding template="ToastGeneric">
+    <text>$(Escape $env:YTDL_TOAST_HEADER)</text>
+    <progress title="{title}" value="{value}" valueStringOverride="{percent}" status="{status}"/>
+  </binding></visual>
+  <actions>
+    <action content="$(Escape $label)" arguments="$toggle" activationType="foreground"/>
+    <action content="$(Escape $env:YTDL_TOAST_CANCEL)" arguments="cancel" activationType="foreground"/>
+  </actions>
+</toast>
+"@)
+    $toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+    $toast.Tag = 'progress'
+    $toast.Group = 'ytdl'
+    $toast.Data = New-Data
+    Unregister-Event -SourceIdentifier ytdl -ErrorAction SilentlyContinue
+    Register-ObjectEvent -InputObject $toast -EventName Activated -SourceIdentifier ytdl | Out-Null
+    $notifier.Show($toast)
+}
+
+function Show-Finished([string]$message) {
+    Unregister-Event -SourceIdentifier ytdl -ErrorAction SilentlyContinue
+    $xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+    $xml.LoadXml(@"
+<toast>
+  <visual><binding template="ToastGeneric">
+    <text>$(Escape $env:YTDL_TOAST_HEADER)</text>
+    <text>$(Escape $message)</text>
+  </binding></visual>
+</toast>
+"@)
+    $toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+    $toast.Tag = 'progress'
+    $toast.Group = 'ytdl'
+    $notifier.Show($toast)
+}
+
+$paused = $false
+$finished = $true
+while ($null -ne ($line = [Console]::In.ReadLine())) {
+    $fields = $line.Split("`t")
+    switch ($fields[0]) {
+        'progress' {
+            $values.title = $fields[1]
+            $values.value = $fields[2]
+            $values.percent = $fields[3]
+            $values.status = $fields[4]
+            if ($finished) {
+                $finished = $false
+                Show-Progress $paused
+            } else {
+                $notifier.Update((New-Data), 'progress', 'ytdl') | Out-Null
+            }
+        }
+        'finish' {
+            $finished = $true
+            Show-Finished $fields[1]
+        }
+    }
+    foreach ($event in @(Get-Event -SourceIdentifier ytdl -ErrorAction SilentlyContinue)) {
+        Remove-Event -EventIdentifier $event.EventIdentifier
+        $arguments = ([Windows.UI.Notifications.ToastActivatedEventArgs]$event.SourceArgs[1]).Arguments
+        [Console]::Out.WriteLine($arguments)
+        [Console]::Out.Flush()
+        if ($arguments -eq 'cancel') { continue }
+        $paused = $arguments -eq 'pause'
+        # ボタンを押すと通知が閉じるため、ボタンを切り替えて表示し直す
+        if (-not $finished) { Show-Progress $paused }
+    }
+}
+"#;
+
+/// 通知に表示する内容
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    Progress { title: String, percent: f64, status: String },
+    Finished(String),
+}
+
+impl Message {
+    /// スクリプトに渡す1行
+    fn to_line(&self) -> String {
+        let field = |text: &str| text.replace(['\t', '\r', '\n'], " ");
+        match self {
+            Message::Progress {
+                title,
+                percent,
+                status,
+            } => format!(
+                "progress\t{}\t{:.3}\t{:.0}%\t{}\n",
+                field(title),
+                percent.clamp(0.0, 100.0) / 100.0,
+                percent,
+                field(status)
+            ),
+            Message::Finished(message) => format!("finish\t{}\n", field(message)),
+        }
+    }
+}
+
+/// 未送信の内容
+#[derive(Debug, Default)]
+struct Pending {
+    title: String,
+    message: Option<Message>,
+    closed: bool,
+}
+
+/// 通知（トースト）への進捗表示（Windows、`--toast`）
+///
+/// 進捗バーと「一時停止／再開」「中止」のボタンがある通知を表示し、最小化していても状況の確認と操作ができます。
+/// 通知はPowerShellで表示し、更新は1秒ごとにまとめて送ります。
+pub struct ToastProgress {
+    pending: Arc<Mutex<Pending>>,
+    child: Child,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl ToastProgress {
+    /// 通知の表示を開始（ボタンで一時停止・中止する）
+    pub fn start(cancel: Arc<CancelToken>) -> Result<Self> {
+        if !cfg!(windows) {
+            return Err(YtdlError::Other(t!("通知での進捗表示（--toast）はWindowsのみ対応しています").to_string()));
+        }
+        let script = std::env::temp_dir().join("ytdl-toast.ps1");
+        std::fs::write(&script, SCRIPT)?;
+        let mut child = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-File"])
+            .arg(&script)
+            .env("YTDL_TOAST_HEADER", "YouTube Batch Downloader")
+            .env("YTDL_TOAST_PAUSE", t!("一時停止"))
+            .env("YTDL_TOAST_RESUME", t!("再開"))
+            .env("YTDL_TOAST_CANCEL", t!("中止"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| YtdlError::Other(t!("通知を表示できません: {}", e)))?;
+
+        // 押されたボタン
+        if let Some(stdout) = child.stdout.take() {
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                    match line.trim() {
+                        "pause" => pause::pause(PauseReason::User),
+                        "resume" => pause::resume(PauseReason::User),
+                        "cancel" => cancel.cancel(),
+                        _ => {}
+                    }
+                }
+            });
+        }
+
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        let writer = child.stdin.take().map(|stdin| {
+            let pending = Arc::clone(&pending);
+            std::thread::spawn(move || write_updates(stdin, &pending))
+        });
+        Ok(Self {
+            pending,
+            child,
+            writer,
+        })
+    }
+
+    /// 次の動画に移る
+    pub fn start_item(&self, title: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.title = title.to_string();
+        }
+    }
+
+    /// 進捗を表示
+    pub fn update(&self, percent: f64, status: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.message = Some(Message::Progress {
+                title: pending.title.clone(),
+                percent,
+                status: status.to_string(),
+            });
+        }
+    }
+
+    /// 完了・失敗の通知に置き換える
+    pub fn finish(&self, message: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.message = Some(Message::Finished(message.to_string()));
+        }
+    }
+}
+
+/// 1秒ごとに未送信の内容を送る（ボタンの操作を受け取るため、内容がなくても送る）
+fn write_updates(mut stdin: ChildStdin, pending: &Mutex<Pending>) {
+    loop {
+        std::thread::sleep(UPDATE_INTERVAL);
+        let (message, closed) = match pending.lock() {
+            Ok(mut pending) => (pending.message.take(), pending.closed),
+            Err(_) => return,
+        };
+        let line = message.map_or_else(|| "tick\n".to_string(), |message| message.to_line());
+        if stdin.write_all(line.as_bytes()).and_then(|_| stdin.flush()).is_err() || closed {
+            return;
+        }
+    }
+}
+
+impl Drop for ToastProgress {
+    /// 最後の内容を送ってからPowerShellを終了させる
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.closed = true;
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_line() {
+        let message = Message::Progress {
+            title: "Song\tTitle".to_string(),
+            percent: 42.5,
+            status: "5.0MiB/s".to_string(),
+        };
+        assert_eq!(message.to_line(), "progress\tSong Title\t0.425\t42%\t5.0MiB/s\n");
+        assert_eq!(
+            Message::Finished("完了".to_string()).to_line(),
+            "finish\t完了\n"
+        );
+    }
+}
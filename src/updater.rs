@@ -1,6 +1,7 @@
 use std::process::Command;
 
 use crate::error::{Result, YtdlError};
+use crate::t;
 
 /// yt-dlp更新機能
 pub struct Updater;
@@ -10,12 +11,12 @@ impl Updater {
     pub fn update_ytdlp() -> Result<()> {
         // pip経由でインストールされている場合はpip upgradeを試す
         let pip_update = Command::new("pip")
-            .args(&["install", "--upgrade", "yt-dlp"])
+            .args(["install", "--upgrade", "yt-dlp"])
             .output();
 
         if let Ok(output) = pip_update {
             if output.status.success() {
-                println!("✅ yt-dlpを最新版に更新しました（pip経由）");
+                println!("{}", t!("✅ yt-dlpを最新版に更新しました（pip経由）"));
                 return Ok(());
             }
         }
@@ -27,18 +28,17 @@ impl Updater {
 
         if let Ok(output) = ytdlp_update {
             if output.status.success() {
-                println!("✅ yt-dlpを最新版に更新しました");
+                println!("{}", t!("✅ yt-dlpを最新版に更新しました"));
                 return Ok(());
             }
         }
 
         // どちらも失敗した場合は警告のみ
-        eprintln!("⚠️ yt-dlpの自動更新をスキップしました（手動更新が必要な場合があります）");
+        eprintln!("{}", t!("⚠️ yt-dlpの自動更新をスキップしました（手動更新が必要な場合があります）"));
         Ok(())
     }
 
     /// yt-dlpのバージョンを表示
-    #[allow(dead_code)]
     pub fn show_version() -> Result<String> {
         let output = Command::new("yt-dlp")
             .arg("--version")
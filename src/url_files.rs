@@ -0,0 +1,155 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::error::{Result, YtdlError};
+use crate::i18n;
+use crate::t;
+
+/// .webloc（plist）内のURL
+/// 例: <key>URL</key><string>https://www.youtube.com/watch?v=abc</string>
+static WEBLOC_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<key>URL</key>\s*<string>(?P<url>[^<]+)</string>").expect("正規表現のコンパイルに失敗")
+});
+
+/// バイナリ形式のplistなどから取り出すURL
+static BARE_URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://[^\x00-\x20"'<>]+"#).expect("正規表現のコンパイルに失敗")
+});
+
+/// 引数のURLを展開（ファイルが指定された場合は中に書かれたURLを取り出す）
+///
+/// エクスプローラーでexeにドロップしたファイルや「送る」メニューから渡された
+/// ファイルのパスを受け取るために使用します。対応形式:
+/// - .url（Windowsのインターネットショートカット）
+/// - .webloc（macOSのWebロケーション）
+/// - テキストファイル（1行に1つのURL、# で始まる行はコメント）
+pub fn expand_inputs(inputs: &[String]) -> Result<Vec<String>> {
+    let mut urls = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if !path.is_file() {
+            urls.push(input.clone());
+            continue;
+        }
+
+        let found = read_url_file(path)?;
+        println!("{}", t!("📄 {} から{}件のURLを読み込みました", path.display(), i18n::number(found.len())));
+        urls.extend(found);
+    }
+    Ok(urls)
+}
+
+/// URLファイルからURLを取り出す（URLが1つもなければエラー）
+pub fn read_url_file(path: &Path) -> Result<Vec<String>> {
+    let bytes = std::fs::read(path)?;
+    let content = String::from_utf8_lossy(&bytes);
+    let found = match file_extension(path).as_str() {
+        "url" => parse_internet_shortcut(&content),
+        "webloc" => parse_webloc(&content),
+        _ => parse_url_list(&content),
+    };
+
+    if found.is_empty() {
+        return Err(YtdlError::Other(t!(
+            "ファイルにURLが見つかりません: {}",
+            path.display()
+        )));
+    }
+    Ok(found)
+}
+
+/// URLファイルとして扱う拡張子か
+pub fn is_url_file(path: &Path) -> bool {
+    matches!(file_extension(path).as_str(), "url" | "webloc" | "txt")
+}
+
+/// 小文字の拡張子
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// .url ファイル（[InternetShortcut] セクションの URL=...）をパース
+fn parse_internet_shortcut(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("URL="))
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .take(1)
+        .collect()
+}
+
+/// .webloc ファイルをパース（XML形式、バイナリ形式の両方）
+fn parse_webloc(content: &str) -> Vec<String> {
+    if let Some(caps) = WEBLOC_REGEX.captures(content) {
+        return vec![unescape_xml(caps["url"].trim())];
+    }
+    BARE_URL_REGEX
+        .find(content)
+        .map(|m| vec![m.as_str().to_string()])
+        .unwrap_or_default()
+}
+
+/// テキストファイル（1行に1つのURL）をパース
+fn parse_url_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| line.contains("://"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// XMLの文字参照を戻す（plistのURLで使われるもののみ）
+fn unescape_xml(text: &str) -> String {
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_internet_shortcut() {
+        let content = "[InternetShortcut]\r\nURL=https://www.youtube.com/watch?v=abc\r\nIconIndex=0\r\n";
+        assert_eq!(
+            parse_internet_shortcut(content),
+            vec!["https://www.youtube.com/watch?v=abc"]
+        );
+    }
+
+    #[test]
+    fn test_parse_webloc() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>URL</key>
+	<string>https://www.youtube.com/watch?v=abc&amp;list=PL1</string>
+</dict>
+</plist>"#;
+        assert_eq!(
+            parse_webloc(content),
+            vec!["https://www.youtube.com/watch?v=abc&list=PL1"]
+        );
+
+        let binary = "bplist00\u{1}\u{2}SURL_\u{10}\u{1c}https://youtu.be/abc\u{8}\u{b}";
+        assert_eq!(parse_webloc(binary), vec!["https://youtu.be/abc"]);
+    }
+
+    #[test]
+    fn test_parse_url_list() {
+        let content = "\u{feff}# あとで見る\nhttps://youtu.be/a\n\n  https://youtu.be/b  \nメモ\n";
+        assert_eq!(
+            parse_url_list(content),
+            vec!["https://youtu.be/a", "https://youtu.be/b"]
+        );
+    }
+}
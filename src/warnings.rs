@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use crate::t;
+
+/// yt-dlpの警告の分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningKind {
+    /// 署名（nsig）の抽出失敗
+    SignatureExtraction,
+    /// 速度制限（スロットリング）
+    Throttled,
+    /// 一部フォーマットの欠落
+    MissingFormats,
+    /// ffmpegが見つからない
+    MissingFfmpeg,
+    /// その他
+    Other,
+}
+
+impl WarningKind {
+    /// 警告の1行を分類（"WARNING:" で始まらない行はNone）
+    pub fn classify(line: &str) -> Option<Self> {
+        let message = line.trim_start().strip_prefix("WARNING:")?.to_lowercase();
+
+        let kind = if message.contains("nsig")
+            || message.contains("signature")
+            || message.contains("n challenge")
+            || message.contains("n function")
+        {
+            WarningKind::SignatureExtraction
+        } else if message.contains("throttl") {
+            WarningKind::Throttled
+        } else if message.contains("ffmpeg") {
+            WarningKind::MissingFfmpeg
+        } else if message.contains("formats have been skipped")
+            || message.contains("formats may be missing")
+        {
+            WarningKind::MissingFormats
+        } else {
+            WarningKind::Other
+        };
+        Some(kind)
+    }
+
+    /// 警告の説明
+    pub fn description(&self) -> &str {
+        match self {
+            WarningKind::SignatureExtraction => t!("署名の抽出に失敗"),
+            WarningKind::Throttled => t!("速度制限（スロットリング）"),
+            WarningKind::MissingFormats => t!("一部のフォーマットが取得できない"),
+            WarningKind::MissingFfmpeg => t!("ffmpegが見つからない"),
+            WarningKind::Other => t!("その他の警告"),
+        }
+    }
+
+    /// 推奨される対処
+    pub fn suggestion(&self) -> &str {
+        match self {
+            WarningKind::SignatureExtraction | WarningKind::MissingFormats => {
+                t!("yt-dlpを最新版に更新してください（yt-dlp -U）")
+            }
+            WarningKind::Throttled => t!("--limit-rate を指定するか、時間をおいて再実行してください"),
+            WarningKind::MissingFfmpeg => {
+                t!("ffmpegをインストールしてください（映像と音声の結合・音声変換に必要です）")
+            }
+            WarningKind::Other => t!("--verbose で詳細を確認してください"),
+        }
+    }
+}
+
+/// 実行中に発生した警告の集計
+#[derive(Debug, Default)]
+pub struct WarningCounter {
+    counts: BTreeMap<WarningKind, usize>,
+}
+
+impl WarningCounter {
+    /// yt-dlpの出力行を記録（警告でなければ無視）
+    pub fn record(&mut self, line: &str) {
+        if let Some(kind) = WarningKind::classify(line) {
+            *self.counts.entry(kind).or_insert(0) += 1;
+        }
+    }
+
+    /// 警告が1件もないか
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// 分類ごとの件数
+    pub fn counts(&self) -> impl Iterator<Item = (WarningKind, usize)> + '_ {
+        self.counts.iter().map(|(kind, count)| (*kind, *count))
+    }
+
+    /// 分類ごとのまとめを表示
+    pub fn print_summary(&self) {
+        if self.is_empty() {
+            return;
+        }
+        println!("{}", t!("\n⚠️  警告のまとめ:"));
+        for (kind, count) in self.counts() {
+            println!("  - {} ×{}", kind.description(), count);
+            println!("      → {}", kind.suggestion());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            WarningKind::classify("WARNING: [youtube] abc: nsig extraction failed: Some formats may be missing"),
+            Some(WarningKind::SignatureExtraction)
+        );
+        assert_eq!(
+            WarningKind::classify("WARNING: You have requested merging of multiple formats but ffmpeg is not installed."),
+            Some(WarningKind::MissingFfmpeg)
+        );
+        assert_eq!(
+            WarningKind::classify("WARNING: [youtube] abc: Some web client https formats have been skipped"),
+            Some(WarningKind::MissingFormats)
+        );
+        assert_eq!(
+            WarningKind::classify("WARNING: something unexpected"),
+            Some(WarningKind::Other)
+        );
+        assert_eq!(WarningKind::classify("ERROR: Video unavailable"), None);
+    }
+
+    #[test]
+    fn test_counter() {
+        let mut counter = WarningCounter::default();
+        assert!(counter.is_empty());
+
+        counter.record("WARNING: [youtube] a: nsig extraction failed");
+        counter.record("WARNING: [youtube] b: nsig extraction failed");
+        counter.record("[download] 10.0% of 1.00MiB");
+        counter.record("WARNING: ffmpeg not found");
+
+        let counts: Vec<_> = counter.counts().collect();
+        assert_eq!(
+            counts,
+            vec![
+                (WarningKind::SignatureExtraction, 2),
+                (WarningKind::MissingFfmpeg, 1)
+            ]
+        );
+    }
+}
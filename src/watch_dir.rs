@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::error::Result;
+use crate::shutdown;
+use crate::t;
+use crate::url_files;
+use crate::ytdlp_wrapper::YtdlpWrapper;
+
+/// フォルダの確認間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 処理済みのファイルを移動するフォルダ
+const DONE_DIR: &str = "done";
+
+/// ダウンロードに失敗したファイルを移動するフォルダ
+const FAILED_DIR: &str = "failed";
+
+/// フォルダを監視し、置かれたURLファイル（.txt / .url / .webloc）をダウンロード
+///
+/// 書き込み途中のファイルを読まないよう、サイズと更新日時が
+/// 前回の確認から変わっていないファイルだけを処理します。
+/// 処理したファイルは `done/`（失敗した場合は `failed/`）へ移動します。
+/// Ctrl+Cまたは終了要求（SIGTERM）を受け取るまで監視を続けます。
+pub fn watch(wrapper: &YtdlpWrapper, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir.join(DONE_DIR))?;
+    std::fs::create_dir_all(dir.join(FAILED_DIR))?;
+    println!("{}", t!("👀 {} を監視しています（Ctrl+Cで終了）", dir.display()));
+
+    // 前回確認時のファイルの状態（サイズ、更新日時）
+    let mut seen: HashMap<PathBuf, (u64, SystemTime)> = HashMap::new();
+    loop {
+        let mut current = HashMap::new();
+        for path in pending_files(dir)? {
+            if shutdown::is_requested() {
+                break;
+            }
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            let state = (
+                metadata.len(),
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            );
+            if seen.get(&path) == Some(&state) {
+                process_file(wrapper, dir, &path);
+            } else {
+                current.insert(path, state);
+            }
+        }
+        seen = current;
+
+        // 終了要求を受け取ったら、待機中のファイルはそのまま残して終了
+        if shutdown::is_requested() {
+            println!("{}", t!("👋 フォルダの監視を終了しました"));
+            return Ok(());
+        }
+        sleep_unless_shutdown(POLL_INTERVAL);
+    }
+}
+
+/// 指定時間待機（終了要求を受け取ったらすぐに戻る）
+pub fn sleep_unless_shutdown(duration: Duration) {
+    let step = Duration::from_millis(250);
+    let mut waited = Duration::ZERO;
+    while waited < duration && !shutdown::is_requested() {
+        std::thread::sleep(step);
+        waited += step;
+    }
+}
+
+/// 監視フォルダ直下のURLファイル（隠しファイル・一時ファイルを除く）
+fn pending_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && url_files::is_url_file(path))
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| !name.starts_with('.') && !name.starts_with('~'))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// 1つのURLファイルをダウンロードし、結果に応じたフォルダへ移動
+fn process_file(wrapper: &YtdlpWrapper, dir: &Path, path: &Path) {
+    println!("{}", t!("\n📄 {} を処理します", path.display()));
+    let result = url_files::read_url_file(path).and_then(|urls| wrapper.download_urls(&urls));
+
+    let target = match &result {
+        Ok(()) => DONE_DIR,
+        // 終了要求で中断した場合は次回の起動時にやり直す
+        Err(_) if shutdown::is_requested() => {
+            println!("{}", t!("⏸  {} は次回の起動時に処理します", path.display()));
+            return;
+        }
+        Err(e) => {
+            eprintln!("❌ {}: {}", path.display(), e);
+            FAILED_DIR
+        }
+    };
+    if let Err(e) = move_into(path, &dir.join(target)) {
+        eprintln!("{}", t!("警告: {} を移動できません: {}", path.display(), e));
+    }
+}
+
+/// ファイルをフォルダへ移動（同名のファイルがあれば日時を付けて回避）
+fn move_into(path: &Path, dir: &Path) -> std::io::Result<()> {
+    let Some(name) = path.file_name() else {
+        return Ok(());
+    };
+    let mut dest = dir.join(name);
+    if dest.exists() {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        dest = dir.join(format!("{}-{}", timestamp, name.to_string_lossy()));
+    }
+    std::fs::rename(path, dest)
+}
@@ -0,0 +1,123 @@
+/// 動画とプレイリストの両方を指すURL（例: watch?v=ID&list=PL...&index=3）
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedUrl {
+    /// 動画ID（v=）
+    pub video_id: String,
+    /// プレイリストID（list=）
+    pub list_id: String,
+    /// プレイリスト内の位置（index=）
+    pub index: Option<usize>,
+}
+
+impl MixedUrl {
+    /// URLを解析（動画・プレイリストの片方しか含まない場合はNone）
+    pub fn parse(url: &str) -> Option<Self> {
+        let (base, query) = url.split_once('?')?;
+        let host_and_path = base.split_once("://").map_or(base, |(_, rest)| rest);
+        let (host, path) = host_and_path.split_once('/').unwrap_or((host_and_path, ""));
+        let host = host.trim_start_matches("www.").trim_start_matches("m.");
+
+        let query = query.split('#').next().unwrap_or_default();
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let video_id = match host {
+            "youtu.be" => Some(path.to_string()).filter(|id| !id.is_empty()),
+            "youtube.com" | "music.youtube.com" if path == "watch" => param("v"),
+            _ => None,
+        }?;
+        Some(Self {
+            video_id,
+            list_id: param("list")?,
+            index: param("index").and_then(|index| index.parse().ok()),
+        })
+    }
+
+    /// この動画のみのURL
+    pub fn video_url(&self) -> String {
+        format!("https://www.youtube.com/watch?v={}", self.video_id)
+    }
+
+    /// プレイリスト全体のURL
+    pub fn playlist_url(&self) -> String {
+        format!("https://www.youtube.com/playlist?list={}", self.list_id)
+    }
+}
+
+/// 動画とプレイリストの両方を指すURLの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistChoice {
+    /// この動画のみ
+    ThisVideo,
+    /// プレイリスト全体
+    WholeList,
+}
+
+/// 動画とプレイリストの両方を指すURLを、選択に応じて一方だけを指すURLに置き換える
+///
+/// 同じ動画・同じプレイリストを指すURLが複数ある場合は1つにまとめます（順序は維持）。
+pub fn resolve_urls(urls: &[String], choice: PlaylistChoice) -> Vec<String> {
+    let mut resolved: Vec<String> = Vec::new();
+    for url in urls {
+        let url = match (MixedUrl::parse(url), choice) {
+            (Some(mixed), PlaylistChoice::ThisVideo) => mixed.video_url(),
+            (Some(mixed), PlaylistChoice::WholeList) => mixed.playlist_url(),
+            (None, _) => url.clone(),
+        };
+        if !resolved.contains(&url) {
+            resolved.push(url);
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mixed_url() {
+        let mixed = MixedUrl::parse("https://www.youtube.com/watch?v=abc&list=PL123&index=3").unwrap();
+        assert_eq!(mixed.video_id, "abc");
+        assert_eq!(mixed.list_id, "PL123");
+        assert_eq!(mixed.index, Some(3));
+
+        let mixed = MixedUrl::parse("https://youtu.be/abc?list=PL123&si=x").unwrap();
+        assert_eq!(mixed.video_url(), "https://www.youtube.com/watch?v=abc");
+        assert_eq!(mixed.playlist_url(), "https://www.youtube.com/playlist?list=PL123");
+
+        assert_eq!(MixedUrl::parse("https://www.youtube.com/watch?v=abc"), None);
+        assert_eq!(MixedUrl::parse("https://www.youtube.com/playlist?list=PL123"), None);
+        assert_eq!(MixedUrl::parse("https://example.com/watch?v=abc&list=PL123"), None);
+    }
+
+    #[test]
+    fn test_resolve_urls_dedupes() {
+        let urls = vec![
+            "https://www.youtube.com/watch?v=a&list=PL1&index=1".to_string(),
+            "https://www.youtube.com/watch?v=b&list=PL1&index=2".to_string(),
+            "https://www.youtube.com/playlist?list=PL2".to_string(),
+        ];
+        assert_eq!(
+            resolve_urls(&urls, PlaylistChoice::WholeList),
+            vec![
+                "https://www.youtube.com/playlist?list=PL1",
+                "https://www.youtube.com/playlist?list=PL2"
+            ]
+        );
+        assert_eq!(
+            resolve_urls(&urls, PlaylistChoice::ThisVideo),
+            vec![
+                "https://www.youtube.com/watch?v=a",
+                "https://www.youtube.com/watch?v=b",
+                "https://www.youtube.com/playlist?list=PL2"
+            ]
+        );
+    }
+}
@@ -1,29 +1,357 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::album;
+use crate::archive;
+use crate::auto_tune::{self, AutoTuner, Outcome};
+use crate::cancel::{CancelToken, DownloadHandle};
+use crate::chunks::{self, Checkpoints};
 use crate::cli::Cli;
-use crate::cookie_detector::CookieDetector;
+use crate::commands::format_length;
+use crate::collision::{self, CollisionPolicy};
+use crate::cookie_detector::{CookieDetector, CookieSnapshot};
+use crate::crash_report::{self, OutputBuffer};
+use crate::dedupe::HashHistory;
+use crate::dock_badge::DockBadge;
+use crate::encode::{EncodeQueue, VideoEncoder};
 use crate::error::{Result, YtdlError};
-use crate::progress_parser::ProgressParser;
+use crate::events::{EventLog, ItemInfo, YtdlpEvent};
+use crate::filename::FilenamePolicy;
+use crate::heatmap;
+use crate::history::{History, HistoryEntry};
+use crate::i18n;
+use crate::hooks::{HookStage, Hooks};
+use crate::interactive::InteractiveMode;
+use crate::item_logs::ItemLogs;
+use crate::metadata::{self, VideoInfo};
+use crate::mqtt::MqttPublisher;
+use crate::pause;
+use crate::placeholder::{self, UnavailablePlaceholder};
+use crate::progress_parser::{destination, format_bytes, postprocessor_name, ProgressInfo, ProgressParser};
+use crate::progress_style::{ProgressFormat, PLAIN_INTERVAL};
+use crate::quality::{AudioFormat, Container, QualityProfile, VideoCodec};
+use crate::report::RunReport;
+use crate::rclone::RcloneUploader;
+use crate::redact;
+use crate::resume;
+use crate::retry_policy::{ErrorClass, RetryPolicy};
+use crate::routing::RouteRule;
+use crate::shutdown;
+use crate::silence;
+use crate::site::{self, SiteConfig};
+use crate::sleep_inhibitor::SleepInhibitor;
+use crate::status_socket::{RunStatus, StatusServer};
+use crate::storyboard;
+use crate::subtitles;
+use crate::taskbar::{TaskbarProgress, TaskbarState};
+use crate::template;
+use crate::toast::ToastProgress;
+use crate::warnings::WarningCounter;
+use crate::t;
 
 /// yt-dlpラッパー
 ///
 /// yt-dlpプロセスを管理し、ダウンロードを実行します。
 pub struct YtdlpWrapper {
     cli: Cli,
+    profile: QualityProfile,
     progress_parser: ProgressParser,
+    /// 動画ごとの進捗バーと全体の進捗バー
+    multi: MultiProgress,
+    mqtt: Option<MqttPublisher>,
+    cookie_snapshot: Option<CookieSnapshot>,
+    uploader: Option<RcloneUploader>,
+    warnings: Mutex<WarningCounter>,
+    /// ダウンロードしたファイル（インタラクティブモードでの再生用）
+    downloaded: Mutex<Vec<PathBuf>>,
+    /// 既存のファイルがあるためスキップしたファイル（実行ごとに集計）
+    skipped: Mutex<Vec<PathBuf>>,
+    /// アーカイブ済みとしてyt-dlpがスキップした動画のID（URLごとに確認）
+    archived: Mutex<Vec<String>>,
+    /// プレイリスト全体のダウンロードで失敗した動画のIDとURL（最後にやり直す）
+    failed_items: Mutex<Vec<(String, String)>>,
+    /// 動画ごとの最後のエラー（やり直す回数をエラーの分類で決める）
+    item_errors: Mutex<HashMap<String, String>>,
+    /// 失敗した動画をやり直す回数（設定ファイルの `[retry]`）
+    retry_policy: RetryPolicy,
+    /// 断片・動画の同時ダウンロード数の自動調整（`--auto-tune`）
+    auto_tune: Option<AutoTuner>,
+    /// ダウンロード中のURLに一致したサイトの設定（`cli.sites` の位置）
+    current_site: Mutex<Option<usize>>,
+    /// 大きなプレイリストを分割してダウンロードしている区切り（1から数える位置、`--playlist-chunk`）
+    chunk: Mutex<Option<(usize, usize)>>,
+    hooks: Hooks,
+    status: Option<StatusServer>,
+    /// タスクバーのアイコンへの進捗表示（Windows）
+    taskbar: Option<TaskbarProgress>,
+    /// 通知への進捗表示（`--toast`）
+    toast: Option<ToastProgress>,
+    /// Dockのアイコンのバッジへの進捗表示（`--dock-badge`）
+    dock_badge: Option<DockBadge>,
+    encode_queue: Option<EncodeQueue>,
+    /// 先頭・末尾の無音を削除する（音声のみのダウンロードの場合）
+    trim_silence: bool,
+    /// 字幕を埋め込む（映像付きのダウンロードの場合）
+    embed_subs: bool,
+    /// ストーリーボードを保存する（映像付きのダウンロードの場合）
+    write_storyboards: bool,
+    /// 途中までダウンロードしたファイルの続きから再開する（最初のダウンロード前に決める）
+    resume: OnceLock<bool>,
+    progress_callback: Option<ProgressCallback>,
+    cancel: Arc<CancelToken>,
 }
 
+/// 進捗の通知先（ライブラリとして使う場合）
+pub type ProgressCallback = Arc<dyn Fn(&ProgressInfo) + Send + Sync>;
+
+/// 動画ごとに決める設定（`download_each` で使用）
+#[derive(Debug, Default)]
+struct ItemOptions<'a> {
+    /// フォーマット（プリセットのフォーマットより優先）
+    format: Option<&'a str>,
+    /// 保存先（拡張子を除く）。同名のファイルを避ける場合に指定
+    output_stem: Option<PathBuf>,
+    /// 既存のファイルを上書き
+    force_overwrites: bool,
+    /// 結合後のコンテナ（字幕を埋め込めない場合など、プリセットより優先）
+    container: Option<&'a str>,
+    /// 全体の進捗バーへの反映（複数の動画をダウンロードする場合）
+    item: Option<&'a ItemProgress>,
+    /// 進捗バーに表示するタイトル（並列ダウンロードの場合）
+    title: Option<&'a str>,
+    /// YouTubeのプレイヤークライアント（403でやり直す場合）
+    player_client: Option<&'a str>,
+    /// ファイル名のテンプレート（このツールの変数を動画のメタデータで展開したもの）
+    output_template: Option<String>,
+}
+
+/// 1本の動画の進捗を全体の進捗バー（1本を100とする）に反映する
+#[derive(Debug)]
+struct ItemProgress {
+    overall: ProgressBar,
+    /// 反映済みの進捗率
+    reported: Cell<u64>,
+}
+
+impl ItemProgress {
+    fn new(overall: &ProgressBar) -> Self {
+        Self {
+            overall: overall.clone(),
+            reported: Cell::new(0),
+        }
+    }
+
+    /// 進捗率を反映（戻った場合は無視。映像と音声を別々に取得する場合など）
+    fn update(&self, percent: u64) {
+        let percent = percent.min(100);
+        let reported = self.reported.get();
+        if percent > reported {
+            self.overall.inc(percent - reported);
+            self.reported.set(percent);
+        }
+    }
+
+    /// 全体の進捗率
+    fn overall_percent(&self) -> f64 {
+        match self.overall.length() {
+            Some(length) if length > 0 => self.overall.position() as f64 * 100.0 / length as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// 完了（失敗・スキップした場合も含む）
+    fn finish(&self) {
+        self.update(100);
+    }
+}
+
+/// 音声抽出時に元の動画も残す場合のフォーマット
+const KEEP_VIDEO_FORMAT: &str = "bestvideo+bestaudio/best";
+
+/// 音声のみでないプリセットでアルバムに分割する場合のフォーマット
+const ALBUM_FORMAT: &str = "bestaudio/best";
+
 impl YtdlpWrapper {
     /// 新しいyt-dlpラッパーを作成
-    pub fn new(cli: Cli) -> Self {
+    pub fn new(cli: Cli, profile: QualityProfile, hooks: Hooks) -> Self {
+        // MQTT接続に失敗してもダウンロードは続行
+        let mqtt = cli.mqtt_broker.as_ref().and_then(|broker| {
+            MqttPublisher::connect(broker, &cli.mqtt_topic)
+                .map_err(|e| eprintln!("{}", t!("警告: {}", e)))
+                .ok()
+        });
+
+        // Cookieスナップショット（失敗した場合はブラウザから直接読む）
+        let cookie_snapshot = match (&cli.cookie_browser, cli.cookie_snapshot) {
+            (Some(browser), true) => CookieDetector::from_str(browser)
+                .and_then(|detector| detector.snapshot())
+                .inspect(|_| println!("{}", t!("🍪 Cookieのスナップショットを作成しました")))
+                .map_err(|e| {
+                    eprintln!("{}", t!("警告: Cookieのスナップショット作成に失敗しました: {}", e));
+                    eprintln!("{}", t!("ブラウザのCookieを直接使用します"));
+                })
+                .ok(),
+            _ => None,
+        };
+
+        // 状態ソケットを作成できなくてもダウンロードは続行
+        let status = cli.status_socket.as_ref().and_then(|target| {
+            StatusServer::start(target)
+                .map_err(|e| eprintln!("{}", t!("警告: {}", e)))
+                .ok()
+        });
+
+        // 変換したコピーは映像のあるファイルのみ作成できる
+        let encode_queue = match cli.also_encode {
+            Some(_) if profile.extract_audio || cli.split_album => {
+                eprintln!("{}", t!("警告: 音声のみのダウンロードでは --also-encode は無視されます"));
+                None
+            }
+            Some(preset) => {
+                let encoder = if cli.sw_encode {
+                    VideoEncoder::Software
+                } else {
+                    VideoEncoder::detect(preset.codec())
+                };
+                if encoder != VideoEncoder::Software {
+                    println!("{}", t!("🎞  変換に{}を使用します（無効にする場合は --sw-encode）", encoder.description()));
+                }
+                Some(EncodeQueue::new(cli.encode_jobs, move |source: &Path| {
+                    preset.encode(source, encoder)
+                }))
+            }
+            None => None,
+        };
+
+        // 通知のボタンで中止できるよう、中止要求を共有する
+        let cancel = Arc::new(CancelToken::default());
+        let taskbar = if cli.no_taskbar_progress {
+            None
+        } else {
+            TaskbarProgress::start()
+        };
+        let toast = cli.toast.then(|| {
+            ToastProgress::start(Arc::clone(&cancel))
+                .map_err(|e| eprintln!("{}", t!("警告: {}", e)))
+                .ok()
+        });
+
+        let dock_badge = if cli.dock_badge { DockBadge::new() } else { None };
+
+        let auto_tune = cli.auto_tune.then(|| {
+            AutoTuner::new(if cli.parallel > 1 { cli.parallel } else { auto_tune::DEFAULT_MAX_PARALLEL })
+        });
+
+        // 進捗をJSON・1行ずつで出力する場合（または出力しない場合）はバーを表示しない
+        let multi = if cli.progress_format.shows_bars() {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+
+        let audio_only = profile.extract_audio || cli.split_album;
+        if cli.trim_silence && !audio_only {
+            eprintln!("{}", t!("警告: 映像付きのダウンロードでは --trim-silence は無視されます"));
+        }
+        let trim_silence = cli.trim_silence && audio_only;
+        if cli.resolution.is_some() && audio_only {
+            eprintln!("{}", t!("警告: 音声のみのダウンロードでは --resolution は無視されます"));
+        }
+        if cli.format_string.is_some() && (cli.resolution.is_some() || cli.vcodec.is_some()) {
+            eprintln!("{}", t!("警告: --format-string を指定したため --resolution / --vcodec は無視されます"));
+        }
+        if cli.audio_format.is_some() && !audio_only {
+            eprintln!("{}", t!("警告: 映像付きのダウンロードでは --audio-format は無視されます"));
+        }
+        if cli.vcodec.is_some() && audio_only {
+            eprintln!("{}", t!("警告: 音声のみのダウンロードでは --vcodec は無視されます"));
+        }
+        if cli.container.is_some() && audio_only {
+            eprintln!("{}", t!("警告: 音声のみのダウンロードでは --container は無視されます"));
+        }
+        if cli.container == Some(Container::Webm) && cli.vcodec == Some(VideoCodec::H264) {
+            eprintln!("{}", t!("警告: WebMにはH.264の映像を格納できないため、変換に失敗する場合があります（--vcodec vp9 / av1 を推奨）"));
+        }
+        if cli.embed_subs && audio_only {
+            eprintln!("{}", t!("警告: 音声のみのダウンロードでは --embed-subs は無視されます"));
+        }
+        let embed_subs = cli.embed_subs && !audio_only;
+        if cli.write_storyboards && audio_only {
+            eprintln!("{}", t!("警告: 音声のみのダウンロードでは --write-storyboards は無視されます"));
+        }
+        let write_storyboards = cli.write_storyboards && !audio_only;
+
+        // 転送先は validate() で検証済み
+        let uploader = cli
+            .upload_dest()
+            .and_then(|dest| dest.ok())
+            .map(|dest| RcloneUploader::new(&dest, cli.rclone_move, cli.progress_style));
+
         Self {
             cli,
+            profile,
             progress_parser: ProgressParser::new(),
+            multi,
+            mqtt,
+            cookie_snapshot,
+            uploader,
+            warnings: Mutex::new(WarningCounter::default()),
+            downloaded: Mutex::new(Vec::new()),
+            skipped: Mutex::new(Vec::new()),
+            archived: Mutex::new(Vec::new()),
+            failed_items: Mutex::new(Vec::new()),
+            item_errors: Mutex::new(HashMap::new()),
+            retry_policy: RetryPolicy::default(),
+            auto_tune,
+            current_site: Mutex::new(None),
+            chunk: Mutex::new(None),
+            hooks,
+            status,
+            taskbar,
+            toast: toast.flatten(),
+            dock_badge,
+            encode_queue,
+            trim_silence,
+            embed_subs,
+            write_storyboards,
+            resume: OnceLock::new(),
+            progress_callback: None,
+            cancel,
         }
     }
 
+    /// 進捗を受け取る関数を設定（進捗バーの表示とは別に呼ばれる）
+    pub fn with_progress_callback(mut self, callback: ProgressCallback) -> Self {
+        self.progress_callback = Some(callback);
+        self
+    }
+
+    /// 失敗した動画をやり直す回数をエラーの分類ごとに設定（設定ファイルの `[retry]`）
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// これまでにダウンロードしたファイル（変換したコピーは含まない）
+    pub fn downloaded_files(&self) -> Vec<PathBuf> {
+        self.downloaded
+            .lock()
+            .map(|files| files.clone())
+            .unwrap_or_default()
+    }
+
+    /// ドライランモードか
+    pub fn is_dry_run(&self) -> bool {
+        self.cli.dry_run
+    }
+
     /// yt-dlpが利用可能かチェック
     pub fn check_ytdlp_available() -> Result<()> {
         let output = Command::new("yt-dlp")
@@ -33,7 +361,7 @@ impl YtdlpWrapper {
 
         if output.status.success() {
             let version = String::from_utf8_lossy(&output.stdout);
-            println!("yt-dlp バージョン: {}", version.trim());
+            println!("{}", t!("yt-dlp バージョン: {}", version.trim()));
             Ok(())
         } else {
             Err(YtdlError::YtdlpNotFound)
@@ -42,213 +370,1728 @@ impl YtdlpWrapper {
 
     /// ダウンロードを実行
     pub fn download(&self) -> Result<()> {
+        self.download_urls(self.urls()?)
+    }
+
+    /// バックグラウンドでダウンロードを開始（[`DownloadHandle::cancel`] で中止できる）
+    pub fn spawn(self) -> DownloadHandle {
+        let token = Arc::clone(&self.cancel);
+        DownloadHandle::new(std::thread::spawn(move || self.download()), token)
+    }
+
+    /// 終了要求・中止要求を受け取ったか
+    fn stop_requested(&self) -> bool {
+        shutdown::is_requested() || self.cancel.is_cancelled()
+    }
+
+    /// 指定したURLをダウンロード（監視フォルダなど、引数以外のURL用）
+    pub fn download_urls(&self, urls: &[String]) -> Result<()> {
+        // ダウンロード中はスリープを防ぐ（macOSではApp Napも防ぐため常に有効）
+        let _awake = (cfg!(target_os = "macos") || self.cli.keep_awake).then(|| {
+            let inhibitor = SleepInhibitor::acquire("YouTube Batch Downloader: downloading");
+            if inhibitor.is_none() && self.cli.keep_awake {
+                eprintln!("{}", t!("警告: スリープを防止できません（--keep-awake）"));
+            }
+            inhibitor
+        });
+        self.publish_status(urls, "downloading", None);
+        self.update_status(|status| {
+            status.state = "downloading".to_string();
+            status.error = None;
+        });
+        let started = SystemTime::now();
+        let downloaded_before = self.downloaded_files().len();
+        let mut failures = Vec::new();
+        let result = self.run_download(urls, &mut failures);
+        match &result {
+            Ok(()) => self.publish_status(urls, "completed", None),
+            Err(e) => self.publish_status(urls, "failed", Some(&e.to_string())),
+        }
+        self.update_status(|status| {
+            status.state = if result.is_ok() { "completed" } else { "failed" }.to_string();
+            status.error = result.as_ref().err().map(|e| e.to_string());
+        });
+        self.finish_desktop_progress(&result);
+        // 警告は実行ごとに集計
+        if let Ok(mut warnings) = self.warnings.lock() {
+            std::mem::take(&mut *warnings).print_summary();
+        }
+        let skipped = self
+            .skipped
+            .lock()
+            .map(|mut skipped| std::mem::take(&mut *skipped))
+            .unwrap_or_default();
+        print_skipped(&skipped);
+        self.finish_encodes();
+
+        // 実行結果のレポート（スクリプト向け）
+        if let Some(path) = &self.cli.report {
+            let report = RunReport::new(
+                urls,
+                started
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+                started.elapsed().unwrap_or_default(),
+                &self.downloaded_files()[downloaded_before..],
+                &skipped,
+                &failures,
+            );
+            match report.write(path) {
+                Ok(()) => println!("{}", t!("📝 レポートを保存しました: {}", path.display())),
+                Err(e) => eprintln!("{}", t!("警告: レポートを保存できません: {}", e)),
+            }
+        }
+
+        let hook_result = self.run_hook(
+            HookStage::PostRun,
+            vec![
+                ("YTDL_URLS", urls.join("\n")),
+                ("YTDL_STATUS", if result.is_ok() { "completed" } else { "failed" }.to_string()),
+                (
+                    "YTDL_ERROR",
+                    result.as_ref().err().map(|e| e.to_string()).unwrap_or_default(),
+                ),
+            ],
+        );
+        result.and(hook_result)
+    }
+
+    /// タスクバー・通知に進捗を表示
+    fn show_desktop_progress(&self, percent: f64, status: &str) {
+        if let Some(taskbar) = &self.taskbar {
+            taskbar.set(TaskbarState::Progress(percent));
+        }
+        if let Some(toast) = &self.toast {
+            toast.update(percent, status);
+        }
+        if let Some(badge) = &self.dock_badge {
+            badge.set_progress(percent);
+        }
+    }
+
+    /// タスクバー・通知の表示を完了・失敗にする
+    fn finish_desktop_progress(&self, result: &Result<()>) {
+        if let Some(taskbar) = &self.taskbar {
+            taskbar.set(if result.is_ok() { TaskbarState::Clear } else { TaskbarState::Error });
+        }
+        if let Some(badge) = &self.dock_badge {
+            if result.is_ok() {
+                badge.clear();
+            } else {
+                badge.set_error();
+            }
+        }
+        if let Some(toast) = &self.toast {
+            match result {
+                Ok(()) => toast.finish(t!("ダウンロードが完了しました")),
+                Err(e) => toast.finish(&t!("ダウンロードに失敗しました: {}", e)),
+            }
+        }
+    }
+
+    /// 状態ソケットで返す処理状況を更新
+    fn update_status(&self, update: impl FnOnce(&mut RunStatus)) {
+        if let Some(status) = &self.status {
+            status.update(update);
+        }
+    }
+
+    /// フックを実行（出力先ディレクトリは常に渡す）
+    fn run_hook(&self, stage: HookStage, mut env: Vec<(&str, String)>) -> Result<()> {
+        if let Some(dir) = self.output_dir() {
+            env.push(("YTDL_OUTPUT_DIR", dir.to_string_lossy().to_string()));
+        }
+        self.hooks.run(stage, &env)
+    }
+
+    /// 状態のサマリーをMQTTに配信（retain）
+    fn publish_status(&self, urls: &[String], state: &str, error: Option<&str>) {
+        if let Some(mqtt) = &self.mqtt {
+            let payload = serde_json::json!({
+                "state": state,
+                "url": urls,
+                "error": error,
+            });
+            if let Err(e) = mqtt.publish("status", &payload.to_string(), true) {
+                eprintln!("{}", t!("警告: MQTT配信に失敗しました: {}", e));
+            }
+        }
+    }
+
+    /// 動画ごとの状態・進捗をMQTTに配信（"<トピック>/<動画ID>/..."）
+    fn publish_item(&self, id: Option<&str>, topic: &str, payload: serde_json::Value, retain: bool) {
+        if let (Some(mqtt), Some(id)) = (&self.mqtt, id) {
+            if let Err(e) = mqtt.publish_item(id, topic, &payload.to_string(), retain) {
+                tracing::debug!("MQTT配信に失敗: {}", e);
+            }
+        }
+    }
+
+    /// 指定されたURLを順にダウンロード
+    ///
+    /// 失敗したURLとその理由を `failures` に追加します（`--report` 用）。
+    fn run_download(&self, urls: &[String], failures: &mut Vec<(String, String)>) -> Result<()> {
+        // 再開するか確認してから進捗バーを表示する
+        self.resumes();
+
+        if let [url] = urls {
+            let result = self.download_url(url);
+            if let Err(e) = &result {
+                failures.push((url.clone(), e.to_string()));
+            }
+            return result;
+        }
+
+        let mut failed = Vec::new();
+        // URLごとの（URL, ダウンロードしたファイル数, 成功したか）
+        let mut results = Vec::new();
+        for (i, url) in urls.iter().enumerate() {
+            if self.stop_requested() {
+                print_url_results(&results);
+                print_failed_urls(&failed);
+                return Err(self.interrupted_error(urls.len() - i));
+            }
+            println!("\n📥 [{}/{}] {}", i + 1, urls.len(), url);
+            let downloaded_before = self.downloaded_files().len();
+            let result = self.download_url(url);
+            let files = self.downloaded_files().len().saturating_sub(downloaded_before);
+            results.push((url.as_str(), files, result.is_ok()));
+            if let Err(e) = result {
+                eprintln!("❌ {}: {}", url, e);
+                failures.push((url.clone(), e.to_string()));
+                failed.push((url.as_str(), e));
+            }
+        }
+
+        print_url_results(&results);
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            print_failed_urls(&failed);
+            Err(YtdlError::DownloadFailed(t!(
+                "{}件中{}件のURLでダウンロードに失敗しました",
+                i18n::number(urls.len()),
+                i18n::number(failed.len())
+            )))
+        }
+    }
+
+    /// 途中までダウンロードしたファイル（.part）の続きから再開するか
+    ///
+    /// `--resume` / `--no-resume` の指定がなければ、出力先に .part ファイルがある場合のみ確認します。
+    fn resumes(&self) -> bool {
+        *self.resume.get_or_init(|| {
+            if self.cli.resume {
+                return true;
+            }
+            if self.cli.no_resume {
+                return false;
+            }
+            let dir = self.cli.output_dir.as_deref().unwrap_or(Path::new("."));
+            let files = resume::find_partial_files(dir);
+            if files.is_empty() {
+                return false;
+            }
+            let size = format_bytes(resume::total_size(&files));
+            if self.cli.non_interactive {
+                println!("{}", t!("🔁 途中までダウンロードしたファイル（{}件、{}）の続きから再開します", i18n::number(files.len()), size));
+                true
+            } else {
+                InteractiveMode::ask_resume(files.len(), &size)
+            }
+        })
+    }
+
+    /// 動画ごとの処理（振り分け・サイズ上限・同名ファイルの確認）の有無に応じてダウンロード
+    fn download_url(&self, url: &str) -> Result<()> {
+        let site = site::find(&self.cli.sites, url);
+        if let Ok(mut current) = self.current_site.lock() {
+            *current = site;
+        }
+        if let Some((host, _)) = site.and_then(|index| self.cli.sites.get(index)) {
+            println!("{}", t!("🌐 サイトごとの設定を使用します: {}", host));
+        }
+        self.run_hook(HookStage::PreDownload, vec![("YTDL_URL", url.to_string())])?;
+        self.update_status(|status| status.url = Some(url.to_string()));
+
+        let chunked = (self.cli.playlist && self.cli.playlist_chunk > 0)
+            .then(|| self.playlist_count(url))
+            .flatten()
+            .filter(|count| *count > self.cli.playlist_chunk);
+        let result = match chunked {
+            Some(count) => self.download_chunks(url, count),
+            None => self.download_range(url),
+        };
+        let redownload = self.redownload_missing(url);
+        result.and(redownload)
+    }
+
+    /// 履歴ではダウンロード済みでもファイルが削除されている動画を、アーカイブから外してダウンロードし直す
+    ///
+    /// アーカイブ済みの動画は何も表示されずにスキップされ、保存先を整理した後に戸惑うため、
+    /// 該当する動画を表示し、`--redownload-missing` を指定した場合・確認で選んだ場合はURLをもう一度ダウンロードします。
+    fn redownload_missing(&self, url: &str) -> Result<()> {
+        let archived = self.take_archived();
+        let Some(archive_path) = &self.cli.download_archive else {
+            return Ok(());
+        };
+        // 転送後に削除する場合（--rclone-move）は保存先にファイルが残らない
+        if archived.is_empty() || self.cli.rclone_move || self.stop_requested() {
+            return Ok(());
+        }
+        let missing = match History::open().and_then(|history| history.missing_files(&archived)) {
+            Ok(missing) => missing,
+            Err(e) => {
+                self.report_error(t!("警告: ダウンロード履歴を確認できません: {}", e));
+                return Ok(());
+            }
+        };
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        println!("{}", t!("\n🗑 履歴ではダウンロード済みですが、ファイルが削除されている動画: {}件", i18n::number(missing.len())));
+        for entry in &missing {
+            println!("  - {}", entry.title.as_deref().unwrap_or(&entry.path.to_string_lossy()));
+        }
+        let redownload = self.cli.redownload_missing
+            || (!self.cli.non_interactive && InteractiveMode::ask_redownload_missing(missing.len()));
+        if !redownload {
+            println!("{}", t!("💡 アーカイブに記録済みのためスキップしました（ダウンロードし直す場合は --redownload-missing）"));
+            return Ok(());
+        }
+
+        let ids: Vec<String> = missing.into_iter().filter_map(|entry| entry.video_id).collect();
+        archive::remove_ids(archive_path, &ids)?;
+        println!("{}", t!("🔁 アーカイブから外してダウンロードし直します"));
+        let result = self.download_range(url);
+        self.take_archived();
+        result
+    }
+
+    /// アーカイブ済みとしてスキップした動画のIDを取り出す
+    fn take_archived(&self) -> Vec<String> {
+        self.archived
+            .lock()
+            .map(|mut archived| std::mem::take(&mut *archived))
+            .unwrap_or_default()
+    }
+
+    /// 大きなプレイリストを `--playlist-chunk` 本ずつ分割してダウンロード
+    ///
+    /// 完了した区切りの次の位置を記録し、前回中断した場合はその位置から再開します。
+    /// 失敗した区切りがあっても残りの区切りは続けますが、記録する位置はそれより先に進めません。
+    fn download_chunks(&self, url: &str, count: usize) -> Result<()> {
+        let first = self.cli.playlist_start.unwrap_or(1);
+        let last = self.cli.playlist_end.unwrap_or(count).min(count);
+        let mut checkpoints = Checkpoints::open()
+            .inspect_err(|e| eprintln!("{}", t!("警告: {}", e)))
+            .ok();
+        let resume_from = checkpoints
+            .as_ref()
+            .and_then(|checkpoints| checkpoints.get(url))
+            .filter(|next| (first + 1..=last).contains(next));
+        if let Some(next) = resume_from {
+            println!("{}", t!("🔖 前回の続き（{}番目）から再開します", next));
+        }
+        let ranges = chunks::ranges(resume_from.unwrap_or(first), last, self.cli.playlist_chunk);
+        println!(
+            "{}",
+            t!("📚 {}本のプレイリストを{}本ずつ{}回に分けてダウンロードします", i18n::number(count), self.cli.playlist_chunk, ranges.len())
+        );
+
+        let mut failed = 0;
+        for (i, &(start, end)) in ranges.iter().enumerate() {
+            if self.stop_requested() {
+                return Err(self.interrupted_error(last + 1 - start));
+            }
+            println!("{}", t!("\n📦 [{}/{}] {}〜{}番目", i + 1, ranges.len(), start, end));
+            if let Ok(mut chunk) = self.chunk.lock() {
+                *chunk = Some((start, end));
+            }
+            let result = self.download_range(url);
+            if let Ok(mut chunk) = self.chunk.lock() {
+                *chunk = None;
+            }
+            if self.stop_requested() {
+                return result.and_then(|_| Err(self.interrupted_error(last - end)));
+            }
+            match result {
+                Ok(()) if failed == 0 => {
+                    if let Some(checkpoints) = &mut checkpoints {
+                        let saved = if end == last { checkpoints.remove(url) } else { checkpoints.set(url, end + 1) };
+                        if let Err(e) = saved {
+                            eprintln!("{}", t!("警告: {}", e));
+                        }
+                    }
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    eprintln!("❌ {}", t!("{}〜{}番目: {}", start, end, e));
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed == 0 {
+            Ok(())
+        } else {
+            Err(YtdlError::DownloadFailed(t!(
+                "{}回中{}回の区切りでダウンロードに失敗しました（次回は失敗した区切りから再開します）",
+                ranges.len(),
+                failed
+            )))
+        }
+    }
+
+    /// プレイリストの動画の数（プレイリストでない場合・取得できない場合はNone）
+    fn playlist_count(&self, url: &str) -> Option<usize> {
+        let mut cmd = self.ytdlp_command();
+        cmd.args(["--flat-playlist", "--playlist-items", "1", "--print", "playlist_count"]);
+        self.apply_cookies(&mut cmd).ok()?;
+        cmd.arg(url);
+        let mut count = None;
+        self.cancel
+            .run_probe(&mut cmd, |line| {
+                count = count.or_else(|| line.trim().parse().ok());
+            })
+            .ok()?;
+        count
+    }
+
+    /// 動画をダウンロードできるか確認（できない場合はyt-dlpのエラーメッセージ）
+    ///
+    /// プレミア公開前・処理中・地域制限などのほか、品質の指定に合うフォーマットがまだない場合もできないものとします。
+    pub fn check_available(&self, url: &str) -> Result<Option<String>> {
+        let mut cmd = self.ytdlp_command();
+        cmd.args(["--simulate", "--no-playlist", "--no-warnings"]);
+        cmd.arg("-f").arg(self.download_format());
+        self.apply_cookies(&mut cmd)?;
+        cmd.arg(url);
+        let output = self.cancel.run_probe(&mut cmd, |_| {})?;
+        if output.status.success() {
+            return Ok(None);
+        }
+        let reason = output
+            .stderr
+            .lines()
+            .rev()
+            .find_map(|line| line.trim().strip_prefix("ERROR:"))
+            .map(|message| message.trim().to_string())
+            .unwrap_or_else(|| t!("yt-dlpがエラーコード{}で終了しました", output.status.code().unwrap_or(-1)));
+        Ok(Some(reason))
+    }
+
+    /// URL（分割している場合はその区切り）をダウンロード
+    fn download_range(&self, url: &str) -> Result<()> {
+        if self.checks_each_video() {
+            self.download_each(url)
+        } else {
+            let result = self.execute(
+                url,
+                self.output_dir(),
+                self.cli.playlist,
+                &ItemOptions::default(),
+            );
+            self.retry_failed_items(result)
+        }
+    }
+
+    /// ダウンロード前に動画ごとのメタデータを確認する必要があるか（並列ダウンロードでは動画ごとのURLを取得）
+    fn checks_each_video(&self) -> bool {
+        !self.cli.routes.is_empty()
+            || self.cli.target_size.is_some()
+            || !self.cli.own_channels.is_empty()
+            || self.cli.only_creative_commons
+            || self.filename_policy().is_some()
+            || (self.max_parallel() > 1 && self.cli.playlist)
+            || self.embed_subs
+            || self.checks_collisions()
+            || template::uses_custom_fields(&self.template_source())
+    }
+
+    /// 動画ごとにメタデータを取得し、出力先とフォーマットを決めてダウンロード
+    fn download_each(&self, url: &str) -> Result<()> {
+        println!("{}", t!("🔍 動画情報を取得中..."));
+        let videos = self.probe_videos(url)?;
+        if videos.is_empty() {
+            println!("{}", t!("新しくダウンロードされる動画はありません（アーカイブ済み）"));
+            return Ok(());
+        }
+
+        // 複数の動画をダウンロードする場合は全体の進捗バーを表示
+        let overall = (videos.len() > 1).then(|| self.overall_bar(videos.len()));
+        let failed = match &overall {
+            Some(overall) if self.max_parallel() > 1 => self.download_parallel(&videos, overall)?,
+            _ => {
+                let mut failed = Vec::new();
+                for (i, info) in videos.iter().enumerate() {
+                    if self.stop_requested() {
+                        return Err(self.interrupted_error(videos.len() - i));
+                    }
+                    let label = format!("\n[{}/{}] {}", i + 1, videos.len(), info.display_title());
+                    let item = overall.as_ref().map(ItemProgress::new);
+                    if let Err(e) = self.download_item(info, &label, item.as_ref(), None) {
+                        self.report_error(format!("❌ {}: {}", info.display_title(), e));
+                        failed.push((info, self.failure_message(&info.id, &e)));
+                    }
+                    if let (Some(overall), Some(item)) = (&overall, item) {
+                        item.finish();
+                        overall.set_message(t!("{}/{}本完了", i + 1, videos.len()));
+                    }
+                }
+                failed
+            }
+        };
+        if let Some(overall) = overall {
+            overall.finish();
+        }
+
+        let failed = self.retry_failed_videos(failed);
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            let titles: Vec<&str> = failed.iter().map(|info| info.display_title()).collect();
+            Err(YtdlError::DownloadFailed(t!(
+                "{}本中{}本のダウンロードに失敗しました: {}",
+                i18n::number(videos.len()),
+                i18n::number(failed.len()),
+                titles.join(", ")
+            )))
+        }
+    }
+
+    /// `--progress-format json` の場合、進捗を1行のJSONで出力
+    fn emit_progress(&self, json: serde_json::Value) {
+        if self.cli.progress_format == ProgressFormat::Json {
+            println!("{}", json);
+        }
+    }
+
+    /// 失敗した動画を1本ずつやり直す（最後まで失敗した動画を返す）
+    fn retry_failed_videos<'a>(&self, failed: Vec<(&'a VideoInfo, String)>) -> Vec<&'a VideoInfo> {
+        self.retry_by_class(
+            failed,
+            |info| info.display_title().to_string(),
+            |info, player_client| {
+                let label = t!("\n[やり直し] {}", info.display_title());
+                self.download_item(info, &label, None, player_client).map_err(|e| {
+                    self.report_error(format!("❌ {}: {}", info.display_title(), e));
+                    self.failure_message(&info.id, &e)
+                })
+            },
+        )
+    }
+
+    /// プレイリスト全体を1回のyt-dlpでダウンロードした後、失敗した動画をやり直す
+    ///
+    /// `result` は最初のダウンロードの結果。すべてやり直せた場合は成功とします。
+    fn retry_failed_items(&self, result: Result<()>) -> Result<()> {
+        let failed = self
+            .failed_items
+            .lock()
+            .map(|mut items| std::mem::take(&mut *items))
+            .unwrap_or_default();
+        if result.is_ok() || failed.is_empty() {
+            return result;
+        }
+        let failed = failed
+            .into_iter()
+            .map(|(id, url)| {
+                let message = self.take_item_error(&id).unwrap_or_default();
+                ((id, url), message)
+            })
+            .collect();
+        let failed = self.retry_by_class(
+            failed,
+            |(_, url)| url.clone(),
+            |(id, url), player_client| {
+                println!("{}", t!("\n[やり直し] {}", url));
+                let options = ItemOptions {
+                    player_client,
+                    ..Default::default()
+                };
+                self.execute(url, self.output_dir(), false, &options)
+                    .map_err(|e| {
+                        eprintln!("❌ {}: {}", url, e);
+                        self.failure_message(id, &e)
+                    })
+            },
+        );
+        if failed.is_empty() {
+            println!("{}", t!("\n✓ 失敗した動画をすべてやり直しました"));
+            Ok(())
+        } else {
+            result
+        }
+    }
+
+    /// 失敗したものを、エラーの分類ごとの回数（設定ファイルの `[retry]`、未設定なら `--retry-failed`）まで
+    /// やり直す（最後まで失敗したものを返す）
+    ///
+    /// `failed` は失敗したものと理由。`retry` は1回やり直し、失敗した場合は新しい理由を返します。
+    /// 理由が変われば、その分類の回数でやり直します。
+    fn retry_by_class<T>(
+        &self,
+        failed: Vec<(T, String)>,
+        label: impl Fn(&T) -> String,
+        retry: impl Fn(&T, Option<&str>) -> std::result::Result<(), String>,
+    ) -> Vec<T> {
+        // （やり直すもの, 分類, やり直した回数）
+        let mut pending: Vec<(T, ErrorClass, usize)> = failed
+            .into_iter()
+            .map(|(item, message)| (item, ErrorClass::classify(&message), 0))
+            .collect();
+        let mut given_up = Vec::new();
+        let mut pass = 0;
+        while !pending.is_empty() && !self.stop_requested() {
+            let (retrying, exhausted): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut pending).into_iter().partition(|(_, class, attempts)| {
+                    *attempts < self.retry_policy.rule(*class, self.cli.retry_failed).attempts
+                });
+            for (item, class, attempts) in exhausted {
+                if attempts == 0 && self.cli.retry_failed > 0 {
+                    println!("{}", t!("⏭ {}: {}のためやり直しません", label(&item), class.description()));
+                }
+                given_up.push(item);
+            }
+            if retrying.is_empty() {
+                break;
+            }
+
+            pass += 1;
+            println!("{}", t!("\n🔁 失敗した{}本をやり直します（{}回目）", retrying.len(), pass));
+            for (item, class, attempts) in retrying {
+                if self.stop_requested() {
+                    given_up.push(item);
+                    continue;
+                }
+                let player_client = self
+                    .retry_policy
+                    .rule(class, self.cli.retry_failed)
+                    .player_client(attempts);
+                if let Some(client) = player_client {
+                    println!("{}", t!("   プレイヤークライアントを {} に切り替えます", client));
+                }
+                if let Err(message) = retry(&item, player_client) {
+                    pending.push((item, ErrorClass::classify(&message), attempts + 1));
+                }
+            }
+        }
+        given_up.extend(pending.into_iter().map(|(item, ..)| item));
+        given_up
+    }
+
+    /// 動画の失敗の理由（yt-dlpが出した動画ごとのエラー、なければダウンロードのエラー）
+    fn failure_message(&self, id: &str, error: &YtdlError) -> String {
+        self.take_item_error(id).unwrap_or_else(|| error.to_string())
+    }
+
+    /// yt-dlpが出した動画ごとのエラーを記録（やり直すときに分類する）
+    fn record_item_errors(&self, events: &[YtdlpEvent]) {
+        let Ok(mut errors) = self.item_errors.lock() else {
+            return;
+        };
+        for event in events {
+            if let YtdlpEvent::Error { id: Some(id), message } = event {
+                errors.insert(id.clone(), message.clone());
+            }
+        }
+    }
+
+    /// 視聴できない動画の代わりのファイルを作成し、ダウンロードできた動画の分は削除
+    fn update_placeholders(&self, events: &[YtdlpEvent], dir: &Path) {
+        for info in finished_items(events) {
+            if let Some(id) = &info.id {
+                if placeholder::remove(dir, id) {
+                    self.report(t!("   視聴できない動画の代わりのファイルを削除しました: {}", id));
+                }
+            }
+        }
+        for event in events {
+            let YtdlpEvent::Error { id: Some(id), message } = event else {
+                continue;
+            };
+            if ErrorClass::classify(message) != ErrorClass::Unavailable {
+                continue;
+            }
+            let started = events.iter().find_map(|event| match event {
+                YtdlpEvent::Started { info } if info.id.as_deref() == Some(id.as_str()) => Some(info),
+                _ => None,
+            });
+            let placeholder = UnavailablePlaceholder::new(
+                id,
+                started.and_then(|info| info.title.as_deref()),
+                started.and_then(|info| info.webpage_url.as_deref()),
+                message,
+            );
+            match placeholder.write(dir) {
+                Ok(path) => self.report(t!("   📄 視聴できない動画の代わりのファイルを作成しました: {}", path.display())),
+                Err(e) => self.report_error(t!("⚠️  視聴できない動画の代わりのファイルを作成できません: {}", e)),
+            }
+        }
+    }
+
+    /// 動画ごとの最後のエラーを取り出す
+    fn take_item_error(&self, id: &str) -> Option<String> {
+        self.item_errors.lock().ok()?.remove(id)
+    }
+
+    /// 動画の同時ダウンロード本数の上限（`--auto-tune` では調整する範囲の上限）
+    fn max_parallel(&self) -> usize {
+        match &self.auto_tune {
+            Some(tuner) => tuner.max_parallel(),
+            None => self.cli.parallel,
+        }
+    }
+
+    /// 動画を最大 `--parallel` 本ずつ同時にダウンロード（失敗した動画と理由を返す）
+    ///
+    /// ダウンロード中の動画ごとの進捗バーを、全体の進捗バーの下に表示します。
+    /// `--auto-tune` では、調整した本数を超える分のスレッドは待機します。
+    fn download_parallel<'a>(
+        &self,
+        videos: &'a [VideoInfo],
+        overall: &ProgressBar,
+    ) -> Result<Vec<(&'a VideoInfo, String)>> {
+        let workers = self.max_parallel().min(videos.len());
+        match &self.auto_tune {
+            Some(_) => println!("{}", t!("⏩ 最大{}本まで同時ダウンロードの本数を自動調整します", workers)),
+            None => println!("{}", t!("⏩ {}本ずつ同時にダウンロードします", workers)),
+        }
+
+        let next = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+        let failed = Mutex::new(Vec::new());
+        std::thread::scope(|scope| {
+            for worker in 0..workers {
+                let next = &next;
+                let finished = &finished;
+                let failed = &failed;
+                scope.spawn(move || {
+                    while !self.stop_requested() {
+                        if self.auto_tune.as_ref().is_some_and(|tuner| worker >= tuner.parallel()) {
+                            if next.load(Ordering::SeqCst) >= videos.len() {
+                                break;
+                            }
+                            std::thread::sleep(Duration::from_millis(500));
+                            continue;
+                        }
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(info) = videos.get(i) else {
+                            break;
+                        };
+                        let label = format!("[{}/{}] {}", i + 1, videos.len(), info.display_title());
+                        let item = ItemProgress::new(overall);
+                        if let Err(e) = self.download_item(info, &label, Some(&item), None) {
+                            self.report_error(format!("❌ {}: {}", info.display_title(), e));
+                            let message = self.failure_message(&info.id, &e);
+                            if let Ok(mut failed) = failed.lock() {
+                                failed.push((info, message));
+                            }
+                        }
+                        item.finish();
+                        let finished = finished.fetch_add(1, Ordering::SeqCst) + 1;
+                        overall.set_message(t!("{}/{}本完了", finished, videos.len()));
+                    }
+                });
+            }
+        });
+
+        let started = next.load(Ordering::SeqCst).min(videos.len());
+        if self.stop_requested() && started < videos.len() {
+            return Err(self.interrupted_error(videos.len() - started));
+        }
+        Ok(failed.into_inner().unwrap_or_default())
+    }
+
+    /// 全体の進捗バーを作成（1本を100として、完了した本数と全体の残り時間を表示）
+    fn overall_bar(&self, count: usize) -> ProgressBar {
+        let overall = self.multi.insert(0, ProgressBar::new(count as u64 * 100));
+        overall.set_style(self.cli.progress_style.overall_style());
+        overall.set_message(t!("0/{}本完了", count));
+        overall
+    }
+
+    /// メッセージを表示（進捗バーの上に表示。端末でない場合はそのまま出力）
+    fn report(&self, message: impl AsRef<str>) {
+        if self.multi.is_hidden() {
+            println!("{}", message.as_ref());
+        } else {
+            let _ = self.multi.println(message);
+        }
+    }
+
+    /// エラーメッセージを表示（端末でない場合は標準エラー出力へ）
+    fn report_error(&self, message: impl AsRef<str>) {
+        if self.multi.is_hidden() {
+            eprintln!("{}", message.as_ref());
+        } else {
+            let _ = self.multi.println(message);
+        }
+    }
+
+    /// 1本の動画をダウンロード（許可されない動画・サイズ上限に収まらない動画はエラー）
+    ///
+    /// `item` は全体の進捗バーへの反映です（複数の動画をダウンロードする場合）。
+    /// `player_client` はやり直す場合に切り替えるYouTubeのプレイヤークライアントです。
+    fn download_item(
+        &self,
+        info: &VideoInfo,
+        label: &str,
+        item: Option<&ItemProgress>,
+        player_client: Option<&str>,
+    ) -> Result<()> {
+        let dir = self.route_dir(info);
+        if self.cli.routes.is_empty() {
+            self.report(label);
+        } else {
+            self.report(format!("{} → {}", label, dir.display()));
+        }
+
+        if !self.is_allowed_channel(info) {
+            return Err(YtdlError::DownloadFailed(t!(
+                "許可されたチャンネル（--own-channel）の動画ではないためダウンロードしません（{}）",
+                info.uploader_id.as_deref().or(info.channel.as_deref()).unwrap_or(t!("チャンネル不明"))
+            )));
+        }
+
+        if self.cli.only_creative_commons && !info.is_creative_commons() {
+            self.report(t!(
+                    "   ⏭ クリエイティブ・コモンズではないためスキップ（{}）",
+                    info.license.as_deref().unwrap_or(t!("ライセンス不明"))
+                ),
+            );
+            return Ok(());
+        }
+
+        // サイズ上限に収まるフォーマットを選択
+        let format = match self.cli.target_size {
+            Some(max_bytes) => {
+                let Some((format, size)) = info.best_format_under(max_bytes, self.profile.extract_audio)
+                else {
+                    return Err(YtdlError::DownloadFailed(t!(
+                        "{}以下に収まるフォーマットがありません",
+                        format_bytes(max_bytes)
+                    )));
+                };
+                self.report(t!("   フォーマット: {} (約 {})", format, format_bytes(size)));
+                Some(format)
+            }
+            None => None,
+        };
+        let mut options = ItemOptions {
+            format: format.as_deref(),
+            item,
+            title: (self.max_parallel() > 1).then(|| info.display_title()),
+            player_client,
+            output_template: self.item_output_template(info),
+            ..Default::default()
+        };
+
+        // 字幕を埋め込めないコンテナならMKVにする
+        if self.embed_subs {
+            if let Some((container, format)) =
+                subtitles::embed_container(info, self.profile.container.as_deref())
+            {
+                self.report(t!(
+                    "   字幕（{}）をそのまま埋め込めないため、{}で保存します",
+                    format,
+                    container.to_uppercase()
+                ));
+                options.container = Some(container);
+            }
+        }
+
+        // ファイル名の文字を制限する（タグのタイトルは元のまま）
+        if self.filename_policy().is_some() {
+            if let Some(name) = self.target_name(info) {
+                let target = dir.join(name);
+                self.report(t!("   ファイル名: {}", target.file_name().unwrap_or_default().to_string_lossy()),
+                );
+                options.output_stem = Some(target.with_extension(""));
+            }
+        }
+
+        // 保存先に同じ名前のファイルがある場合
+        if let Some((target, existing)) = self.find_collision(info, &dir) {
+            match self.collision_policy(&existing) {
+                CollisionPolicy::Skip => {
+                    self.report(t!("   ⏭ 同名のファイルがあるためスキップ: {}", existing.display()));
+                    self.record_skipped(existing);
+                    return Ok(());
+                }
+                CollisionPolicy::Overwrite => {
+                    self.report(t!("   上書きします: {}", existing.display()));
+                    options.force_overwrites = true;
+                }
+                CollisionPolicy::Rename => {
+                    let stem = collision::free_stem(&target);
+                    self.report(t!("   同名のファイルがあるため別名で保存: {}", stem.display()));
+                    options.output_stem = Some(stem);
+                }
+            }
+        }
+
+        let item_url = info.webpage_url.as_deref().unwrap_or(&info.id);
+        self.execute(item_url, Some(&dir), false, &options)
+    }
+
+    /// 既存のファイルがあるためスキップしたことを記録
+    fn record_skipped(&self, file: PathBuf) {
+        if let Ok(mut skipped) = self.skipped.lock() {
+            skipped.push(file);
+        }
+    }
+
+    /// ダウンロードを許可するチャンネルの動画か（`--own-channel` 未指定ならすべて許可）
+    fn is_allowed_channel(&self, info: &VideoInfo) -> bool {
+        self.cli.own_channels.is_empty()
+            || self.cli.own_channels.iter().any(|channel| info.is_from_channel(channel))
+    }
+
+    /// 同名ファイルを確認するか（ファイル名に動画IDを含まない場合、または `--on-conflict` 指定時）
+    fn checks_collisions(&self) -> bool {
+        !self.cli.split_album
+            && (self.cli.on_collision.is_some() || !self.output_template().contains("%(id)"))
+    }
+
+    /// 保存先と、同じ名前の既存ファイル
+    fn find_collision(&self, info: &VideoInfo, dir: &Path) -> Option<(PathBuf, PathBuf)> {
+        if !self.checks_collisions() {
+            return None;
+        }
+        let target = dir.join(self.target_name(info)?);
+        let existing = collision::find_existing(&target)?;
+        Some((target, existing))
+    }
+
+    /// ファイル名に使う文字の制限（`--transliterate` は ascii と同じ）
+    fn filename_policy(&self) -> Option<FilenamePolicy> {
+        self.cli
+            .filename_policy
+            .or(self.cli.transliterate.then_some(FilenamePolicy::Ascii))
+    }
+
+    /// 保存先のファイル名（出力先からの相対パス。文字の制限を適用したもの）
+    fn target_name(&self, info: &VideoInfo) -> Option<String> {
+        let name = info.filename.as_deref()?;
+        Some(match self.filename_policy() {
+            Some(policy) => policy.apply(name),
+            None => name.to_string(),
+        })
+    }
+
+    /// 同名ファイルの扱い（未指定なら確認し、非対話モード・並列ダウンロードでは番号を付ける）
+    fn collision_policy(&self, existing: &Path) -> CollisionPolicy {
+        match self.cli.on_collision {
+            Some(policy) => policy,
+            None if self.cli.non_interactive || self.max_parallel() > 1 => CollisionPolicy::Rename,
+            None => InteractiveMode::ask_collision(existing),
+        }
+    }
+
+    /// 振り分けルールに従った出力先を決定（一致しない場合は通常の出力先）
+    fn route_dir(&self, info: &VideoInfo) -> PathBuf {
+        let base = self
+            .output_dir()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        match RouteRule::find_dir(&self.cli.routes, info) {
+            Some(dir) => base.join(dir),
+            None => base,
+        }
+    }
+
+    /// 指定URLを1回のyt-dlpプロセスでダウンロード
+    fn execute(
+        &self,
+        url: &str,
+        output_dir: Option<&Path>,
+        whole_playlist: bool,
+        options: &ItemOptions,
+    ) -> Result<()> {
+        let _span = tracing::info_span!("download", url).entered();
+        let started_at = Instant::now();
+
         // 出力ディレクトリを作成
-        if let Some(output_dir) = &self.cli.output_dir {
+        if let Some(output_dir) = output_dir {
             if !output_dir.exists() {
                 std::fs::create_dir_all(output_dir)?;
             }
         }
 
         // yt-dlpコマンドを構築
-        let mut cmd = self.build_command()?;
+        let mut cmd = self.build_command(url, output_dir, whole_playlist, options)?;
 
-        if self.cli.verbose {
-            println!("\n実行コマンド: {:?}\n", cmd);
+        // 動画ごとの開始・完了をイベントとして記録させる
+        let mut event_log = EventLog::new();
+        event_log.apply_args(&mut cmd);
+        let mut events = Vec::new();
+
+        // 動画ごとのログ（作成できなくてもダウンロードは続行）
+        let mut item_logs = ItemLogs::open()
+            .map_err(|e| tracing::warn!("動画ごとのログを保存できません: {}", e))
+            .ok();
+
+        let command_line = redact::command_line(&cmd);
+        tracing::debug!("実行コマンド: {}", command_line);
+        if self.cli.verbose > 0 || self.cli.print_command {
+            println!("{}", t!("\n実行コマンド: {}\n", command_line));
         }
 
         // プロセスを起動
+        pause::prepare_child(&mut cmd);
         let mut child = cmd
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .map_err(|e| YtdlError::ProcessError(format!("プロセス起動失敗: {}", e)))?;
-
-        // 進捗バーを作成
-        let pb = ProgressBar::new(100);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{bar:40.cyan/blue}] {percent}% | {msg}")
-                .expect("Progress template invalid")
-                .progress_chars("#>-"),
-        );
+            .map_err(|e| YtdlError::ProcessError(t!("プロセス起動失敗: {}", e)))?;
+        pause::register_child(child.id());
+        self.cancel.add_child(child.id());
+
+        // stderrは別スレッドで読み取る（パイプが詰まってyt-dlpが停止するのを防ぐ）
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            std::thread::spawn(move || {
+                BufReader::new(stderr)
+                    .lines()
+                    .map_while(std::result::Result::ok)
+                    .collect::<Vec<String>>()
+            })
+        });
+
+        // 進捗バーを作成（全体の進捗バーがある場合はその下に表示）
+        let pb = self.multi.add(ProgressBar::new(100));
+        pb.set_style(self.cli.progress_style.bar_style("cyan/blue"));
+        // 1回のyt-dlpでプレイリスト全体をダウンロードする場合の全体の進捗
+        let mut playlist_item: Option<ItemProgress> = None;
+
+        // 後処理（結合・変換・埋め込み）の区間
+        let mut postprocess_span = None;
+        // 予期しない失敗の不具合報告に含める最後の出力
+        let mut stdout_tail = OutputBuffer::new(crash_report::BUFFER_LINES);
+        // ダウンロード中のファイル（`--progress-format json` 用）
+        let mut filename: Option<String> = None;
+        // ダウンロード中の動画のID（MQTTの動画ごとのトピック用）
+        let mut current_id: Option<String> = None;
+        // 最後に進捗を1行で出力した時刻（`--progress plain` 用）
+        let mut last_plain: Option<Instant> = None;
 
         // 標準出力を読み取り
         if let Some(stdout) = child.stdout.take() {
             let mut reader = BufReader::new(stdout);
             let mut buffer = Vec::new();
 
-            // UTF-8でない可能性があるため、バイト単位で読み取り
-            loop {
-                buffer.clear();
-                match reader.read_until(b'\n', &mut buffer) {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        // lossy変換でUTF-8に変換（不正なバイトは置換）
-                        let line = String::from_utf8_lossy(&buffer).to_string();
-                        let line = line.trim_end();
+            // UTF-8でない可能性があるため、バイト単位で読み取り
+            loop {
+                buffer.clear();
+                match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) => break, // EOF
+                    Ok(_) => {
+                        // lossy変換でUTF-8に変換（不正なバイトは置換）
+                        let line = String::from_utf8_lossy(&buffer).to_string();
+                        let line = line.trim_end();
+
+                        if self.cli.verbose > 0 {
+                            println!("{}", redact::redact_line(line));
+                        }
+
+                        for event in event_log.read_new() {
+                            if let YtdlpEvent::Started { info } = &event {
+                                self.report(format!("▶ {}", item_label(info)));
+                                current_id = info.id.clone();
+                                self.publish_item(
+                                    info.id.as_deref(),
+                                    "state",
+                                    serde_json::json!({
+                                        "state": "downloading",
+                                        "title": info.title,
+                                        "url": info.webpage_url,
+                                    }),
+                                    true,
+                                );
+                                if let (Some(logs), Some(id)) = (&mut item_logs, &info.id) {
+                                    logs.start(id);
+                                }
+                                if options.item.is_none() {
+                                    self.start_playlist_item(&mut playlist_item, info);
+                                    pb.reset();
+                                }
+                                if let Some(toast) = &self.toast {
+                                    toast.start_item(&item_label(info));
+                                }
+                                self.update_status(|status| {
+                                    status.start_item(
+                                        info.title.clone(),
+                                        info.playlist_index,
+                                        info.playlist_count,
+                                    )
+                                });
+                            }
+                            events.push(event);
+                        }
+                        if let Some(logs) = &mut item_logs {
+                            logs.line(line);
+                        }
+                        stdout_tail.push(line);
+                        if let Some(destination) = destination(line) {
+                            filename = Some(destination.to_string());
+                        }
+
+                        // 進捗情報をパース（進捗以外の行はログファイルに記録）
+                        let progress = self.progress_parser.parse(line);
+                        if !matches!(progress, Ok(Some(_))) {
+                            tracing::trace!(stream = "stdout", "{}", redact::redact_line(line));
+                        }
+                        if let Ok(Some(progress)) = progress {
+                            // プレイリストの次の動画のダウンロードが始まった
+                            postprocess_span = None;
+                            // MQTTには1%単位の変化時のみ配信
+                            if progress.percent as u64 != pb.position() {
+                                self.publish_item(current_id.as_deref(), "progress", progress.to_json(), false);
+                            }
+                            let mut json = progress.to_json();
+                            json["phase"] = "downloading".into();
+                            json["filename"] = filename.as_deref().into();
+                            self.emit_progress(json);
+                            self.update_status(|status| {
+                                status.state = "downloading".to_string();
+                                status.set_progress(&progress);
+                            });
+                            if let Some(callback) = &self.progress_callback {
+                                callback(&progress);
+                            }
+                            pb.set_position(progress.percent as u64);
+                            let message = self.cli.progress_style.message(&progress);
+                            let message = match options.title {
+                                Some(title) => format!("{} | {}", title, message),
+                                None => message,
+                            };
+                            if self.cli.progress_format == ProgressFormat::Plain
+                                && last_plain.is_none_or(|last| last.elapsed() >= PLAIN_INTERVAL)
+                            {
+                                println!("{:>5.1}% | {}", progress.percent, message);
+                                last_plain = Some(Instant::now());
+                            }
+                            // 複数の動画をダウンロードする場合は全体の進捗を表示
+                            let overall = match options.item.or(playlist_item.as_ref()) {
+                                Some(item) => {
+                                    item.update(progress.percent as u64);
+                                    item.overall_percent()
+                                }
+                                None => progress.percent,
+                            };
+                            self.show_desktop_progress(overall, &message);
+                            pb.set_message(message);
+                        } else if let Some(name) = postprocessor_name(line) {
+                            if postprocess_span.is_none() {
+                                postprocess_span = Some(tracing::info_span!("postprocess").entered());
+                                self.update_status(|status| status.state = "postprocessing".to_string());
+                                self.emit_progress(serde_json::json!({
+                                    "phase": "postprocessing",
+                                    "postprocessor": name,
+                                    "filename": filename,
+                                }));
+                            }
+                            tracing::debug!(postprocessor = name, "{}", line);
+                        } else if let Some(file) = collision::already_downloaded(line) {
+                            let _ = self.multi.println(line);
+                            self.record_skipped(file);
+                        } else if let Some(id) = archive::already_recorded(line) {
+                            let _ = self.multi.println(line);
+                            if let Ok(mut archived) = self.archived.lock() {
+                                archived.push(id);
+                            }
+                        } else if line.contains("[download]") {
+                            // その他のダウンロード情報も表示
+                            let _ = self.multi.println(line);
+                        }
+                    }
+                    Err(e) => {
+                        // 読み取りエラー（通常は発生しない）
+                        eprintln!("{}", t!("警告: 出力読み取りエラー: {}", e));
+                        break;
+                    }
+                }
+            }
+        }
+
+        // 複数の動画の場合、完了した動画のバーは消す（全体の進捗バーに反映済み）
+        if options.item.is_some() {
+            pb.finish_and_clear();
+        } else {
+            pb.finish_with_message(t!("完了"));
+        }
+        if let Some(item) = playlist_item {
+            item.finish();
+            item.overall.finish();
+        }
+        drop(postprocess_span);
+
+        // stderr（警告・エラーメッセージ）を回収
+        let stderr_lines = stderr_reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        if let Ok(mut warnings) = self.warnings.lock() {
+            for line in &stderr_lines {
+                warnings.record(line);
+            }
+        }
+        // 出力の終了後に書き出されたイベントも動画ごとのログに反映
+        events.extend(event_log.read_new());
+        if let Some(logs) = &mut item_logs {
+            if !logs.has_started() {
+                let started = events.iter().find_map(|event| match event {
+                    YtdlpEvent::Started { info } => info.id.as_deref(),
+                    _ => None,
+                });
+                if let Some(id) = started {
+                    logs.start(id);
+                }
+            }
+            for line in &stderr_lines {
+                match YtdlpEvent::from_error_line(line) {
+                    Some(YtdlpEvent::Error { id: Some(id), .. }) => logs.line_for(&id, line),
+                    _ => logs.line(line),
+                }
+            }
+        }
+        drop(item_logs);
+        for line in &stderr_lines {
+            tracing::trace!(stream = "stderr", "{}", redact::redact_line(line));
+        }
+        let stderr_content = stderr_lines.join("\n");
+
+        // プロセスの終了を待つ
+        let status = child.wait();
+        pause::unregister_child(child.id());
+        self.cancel.remove_child(child.id());
+        let status = status.map_err(|e| YtdlError::ProcessError(e.to_string()))?;
+        if !status.success() && self.cancel.is_cancelled() {
+            return Err(YtdlError::Cancelled(0));
+        }
+
+        events.extend(event_log.read_new());
+        events.extend(stderr_lines.iter().filter_map(|line| YtdlpEvent::from_error_line(line)));
+        print_item_summary(&events);
+        for info in finished_items(&events) {
+            self.emit_progress(serde_json::json!({
+                "phase": "finished",
+                "percent": 100.0,
+                "id": info.id,
+                "filename": info.filepath,
+            }));
+            self.publish_item(
+                info.id.as_deref(),
+                "state",
+                serde_json::json!({ "state": "finished", "filename": info.filepath }),
+                true,
+            );
+        }
+        for event in &events {
+            if let YtdlpEvent::Error { id: Some(id), message } = event {
+                self.publish_item(Some(id), "state", serde_json::json!({ "state": "failed", "error": message }), true);
+            }
+        }
+        self.record_item_errors(&events);
+        if self.cli.unavailable_placeholders {
+            self.update_placeholders(&events, output_dir.unwrap_or(Path::new(".")));
+        }
+        if let Some(tuner) = &self.auto_tune {
+            let outcome = if auto_tune::is_rate_limited(&stderr_lines) {
+                Outcome::RateLimited
+            } else if status.success() {
+                let bytes = finished_items(&events)
+                    .iter()
+                    .filter_map(|info| info.filepath.as_ref()?.metadata().ok())
+                    .map(|metadata| metadata.len())
+                    .sum();
+                Outcome::Finished {
+                    bytes,
+                    elapsed: started_at.elapsed(),
+                }
+            } else {
+                Outcome::Failed
+            };
+            if let Some(change) = tuner.record(outcome) {
+                self.report(t!("⚙ 自動調整: {}", change));
+            }
+        }
+        if whole_playlist {
+            if let Ok(mut failed) = self.failed_items.lock() {
+                failed.extend(failed_items(&events));
+            }
+        }
+
+        // アルバムとして分割した場合は曲ごとにタグを付ける
+        let mut files = Vec::new();
+        let mut items = Vec::new();
+        for info in finished_items(&events) {
+            let Some(file) = &info.filepath else {
+                continue;
+            };
+            if self.cli.split_album {
+                files.extend(album::tag_tracks(file, self.cli.keep_video)?);
+                // フックにはアルバムフォルダを渡す
+                items.push((info, file.parent().unwrap_or(file).to_path_buf()));
+            } else {
+                files.push(file.clone());
+                items.push((info, file.clone()));
+            }
+        }
 
-                if self.cli.verbose {
-                    println!("{}", line);
+        // 先頭・末尾の無音を削除（失敗した場合は元のまま）
+        if self.trim_silence {
+            for file in &files {
+                match silence::trim_silence(file) {
+                    Ok(true) => println!("{}", t!("🔇 無音を削除しました: {}", file.display())),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("{}", t!("警告: {}", e)),
                 }
+            }
+        }
 
-                        // 進捗情報をパース
-                        if let Ok(Some(progress)) = self.progress_parser.parse(&line) {
-                            pb.set_position(progress.percent as u64);
-                            pb.set_message(format!(
-                                "{} / {} | {} | ETA {}",
-                                progress.downloaded_size_str(),
-                                progress.total_size_str(),
-                                progress.speed_str(),
-                                progress.eta_str()
-                            ));
-                        } else if line.contains("[download]") {
-                            // その他のダウンロード情報も表示
-                            pb.println(&line);
-                        }
-                    }
-                    Err(e) => {
-                        // 読み取りエラー（通常は発生しない）
-                        eprintln!("警告: 出力読み取りエラー: {}", e);
-                        break;
-                    }
+        // ストーリーボードを保存（失敗しても動画はそのまま）
+        if self.write_storyboards {
+            for (info, file) in &items {
+                match self.write_storyboard(info, file) {
+                    Ok(Some(path)) => self.report(t!("🖼  ストーリーボードを保存しました: {}", path.display())),
+                    Ok(None) => self.report(t!("   ストーリーボードはありません: {}", file.display())),
+                    Err(e) => self.report_error(t!("警告: {}", e)),
                 }
             }
         }
 
-        pb.finish_with_message("完了");
+        // ダウンロード履歴に記録（ytdl history）
+        if !self.cli.no_history {
+            self.record_history(&items);
+        }
 
-        // stderrも読み取り（エラーメッセージ用）
-        let stderr_content = if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
-            lines.join("\n")
-        } else {
-            String::new()
-        };
+        // 「最も再生された部分」をサイドカーに保存
+        if self.cli.write_heatmap {
+            for (info, file) in &items {
+                self.write_heatmap(info, file);
+            }
+        }
 
-        // プロセスの終了を待つ
-        let status = child
-            .wait()
-            .map_err(|e| YtdlError::ProcessError(e.to_string()))?;
+        if let Ok(mut downloaded) = self.downloaded.lock() {
+            downloaded.extend(files.iter().cloned());
+        }
+
+        // 重複検出用にハッシュを記録（クラウドへ移動する前に）
+        if self.cli.record_hashes {
+            self.record_hashes(&files);
+        }
+
+        // 変換したコピーを作成（転送する場合は転送前に完了を待つ）
+        if let Some(queue) = &self.encode_queue {
+            for file in &files {
+                queue.submit(file.clone());
+            }
+            if self.uploader.is_some() {
+                files.extend(self.finish_encodes());
+            }
+        }
+
+        for (info, file) in items {
+            self.run_hook(
+                HookStage::PostItem,
+                vec![
+                    ("YTDL_ID", info.id.clone().unwrap_or_default()),
+                    ("YTDL_TITLE", info.title.clone().unwrap_or_default()),
+                    ("YTDL_URL", info.webpage_url.clone().unwrap_or_default()),
+                    ("YTDL_FILE", file.to_string_lossy().to_string()),
+                ],
+            )?;
+        }
+
+        // 完了したファイルをクラウドへ転送（一部の動画が失敗しても完了分は転送）
+        let upload_result = self.upload_completed(&files, output_dir);
 
         if status.success() {
-            println!("\n✓ ダウンロードが正常に完了しました");
-            Ok(())
+            self.report(t!("\n✓ ダウンロードが正常に完了しました"));
+            upload_result
         } else {
             // Bot検出エラーの特別処理
             if stderr_content.contains("Sign in to confirm you're not a bot") {
-                eprintln!("\n❌ YouTubeのBot対策により、ブラウザのCookie認証が必要です\n");
-                eprintln!("📝 解決方法:");
-                eprintln!("  1. Chromeを開いてYouTubeにログインしてください");
-                eprintln!("  2. ログイン後、このツールを再度実行してください");
-                eprintln!("  3. デフォルトでChromeのCookieを使用します\n");
-                eprintln!("別のブラウザを使用する場合:");
-                eprintln!("  --cookies firefox  (Firefoxの場合)");
-                eprintln!("  --cookies edge     (Edgeの場合)\n");
+                eprintln!("{}", t!("\n❌ YouTubeのBot対策により、ブラウザのCookie認証が必要です\n"));
+                eprintln!("{}", t!("📝 解決方法:"));
+                eprintln!("{}", t!("  1. Chromeを開いてYouTubeにログインしてください"));
+                eprintln!("{}", t!("  2. ログイン後、このツールを再度実行してください"));
+                eprintln!("{}", t!("  3. デフォルトでChromeのCookieを使用します\n"));
+                eprintln!("{}", t!("別のブラウザを使用する場合:"));
+                eprintln!("{}", t!("  --cookies firefox  (Firefoxの場合)"));
+                eprintln!("{}", t!("  --cookies edge     (Edgeの場合)\n"));
 
                 return Err(YtdlError::DownloadFailed(
-                    "YouTube認証エラー: ブラウザでログインしてください".to_string()
+                    t!("YouTube認証エラー: ブラウザでログインしてください").to_string()
                 ));
             }
 
             // Cookie コピーエラーの特別処理
             if stderr_content.contains("Could not copy Chrome cookie database") {
-                eprintln!("\n❌ ChromeのCookieデータベースをコピーできませんでした\n");
-                eprintln!("📝 解決方法（以下のいずれかを試してください）:");
-                eprintln!("  1. Chromeを完全に終了してから、再度このツールを実行");
-                eprintln!("  2. タスクマネージャーでChrome関連プロセスを全て終了");
-                eprintln!("  3. Firefoxを使用: ytdl.exe --cookies firefox <URL>");
-                eprintln!("  4. Edgeを使用: ytdl.exe --cookies edge <URL>\n");
-                eprintln!("💡 ヒント: Chromeが起動中だとCookieファイルがロックされます\n");
+                eprintln!("{}", t!("\n❌ ChromeのCookieデータベースをコピーできませんでした\n"));
+                eprintln!("{}", t!("📝 解決方法（以下のいずれかを試してください）:"));
+                eprintln!("{}", t!("  1. Chromeを完全に終了してから、再度このツールを実行"));
+                eprintln!("{}", t!("  2. タスクマネージャーでChrome関連プロセスを全て終了"));
+                eprintln!("{}", t!("  3. Firefoxを使用: ytdl.exe --cookies firefox <URL>"));
+                eprintln!("{}", t!("  4. Edgeを使用: ytdl.exe --cookies edge <URL>\n"));
+                eprintln!("{}", t!("💡 ヒント: Chromeが起動中だとCookieファイルがロックされます\n"));
 
                 return Err(YtdlError::DownloadFailed(
-                    "Cookie読み込みエラー: Chromeを終了してください".to_string()
+                    t!("Cookie読み込みエラー: Chromeを終了してください").to_string()
                 ));
             }
 
             // その他のエラー詳細を表示
-            eprintln!("\n❌ yt-dlpエラー詳細:");
+            eprintln!("{}", t!("\n❌ yt-dlpエラー詳細:"));
             if !stderr_content.is_empty() {
                 eprintln!("{}", stderr_content);
             }
-            Err(YtdlError::DownloadFailed(format!(
+
+            // 動画ごとの理由なしに失敗した場合（yt-dlpの異常終了など）は不具合報告用の情報を保存
+            let explained = events
+                .iter()
+                .any(|event| matches!(event, YtdlpEvent::Error { id: Some(_), .. }));
+            if !explained {
+                let mut stderr_tail = OutputBuffer::new(crash_report::BUFFER_LINES);
+                for line in &stderr_lines {
+                    stderr_tail.push(line);
+                }
+                match crash_report::write(&cmd, &status.to_string(), &stdout_tail, &stderr_tail) {
+                    Ok(path) => eprintln!("{}", t!("🧾 不具合報告用の情報を保存しました: {}", path.display())),
+                    Err(e) => tracing::warn!("不具合報告を保存できません: {}", e),
+                }
+            }
+            Err(YtdlError::DownloadFailed(t!(
                 "yt-dlpがエラーコード{}で終了しました",
                 status.code().unwrap_or(-1)
             )))
         }
     }
 
-    /// yt-dlpコマンドを構築
-    fn build_command(&self) -> Result<Command> {
+    /// プレイリストの次の動画を開始（2本目以降は前の動画を完了として全体の進捗に反映）
+    fn start_playlist_item(&self, current: &mut Option<ItemProgress>, info: &ItemInfo) {
+        let (Some(index), Some(count)) = (info.playlist_index, info.playlist_count) else {
+            return;
+        };
+        if count <= 1 {
+            return;
+        }
+        let overall = match current.take() {
+            Some(previous) => {
+                previous.finish();
+                previous.overall
+            }
+            None => {
+                // --from で途中から始めた場合も、それより前の動画は完了とみなす
+                let overall = self.overall_bar(count as usize);
+                overall.set_position((index.saturating_sub(1) * 100) as u64);
+                overall
+            }
+        };
+        overall.set_message(t!("{}/{}本完了", index.saturating_sub(1), count));
+        *current = Some(ItemProgress::new(&overall));
+    }
+
+    /// 動画のストーリーボードを動画と同じフォルダに保存
+    fn write_storyboard(&self, info: &ItemInfo, file: &Path) -> Result<Option<PathBuf>> {
+        let Some(url) = info.webpage_url.as_deref().or(info.id.as_deref()) else {
+            return Ok(None);
+        };
+        let mut cmd = self.ytdlp_command();
+        self.apply_cookies(&mut cmd)?;
+        storyboard::download(cmd, url, file)
+    }
+
+    /// 完了した動画をダウンロード履歴に記録（失敗してもダウンロードは続ける）
+    fn record_history(&self, items: &[(&ItemInfo, PathBuf)]) {
+        if items.is_empty() {
+            return;
+        }
+        let result = History::open().and_then(|history| {
+            for (info, file) in items {
+                history.record(&HistoryEntry {
+                    video_id: info.id.clone(),
+                    title: info.title.clone(),
+                    url: info.webpage_url.clone(),
+                    path: file.clone(),
+                    size: file.metadata().map(|meta| meta.len()).unwrap_or(0),
+                    quality: self.profile.name.clone(),
+                    ..Default::default()
+                })?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            self.report_error(t!("警告: ダウンロード履歴に記録できません: {}", e));
+        }
+    }
+
+    /// ヒートマップを動画と同じフォルダに保存し、最も再生された区間を表示
+    fn write_heatmap(&self, info: &ItemInfo, file: &Path) {
+        match heatmap::write_sidecar(info, file) {
+            Ok(Some(path)) => {
+                self.report(t!("🔥 ヒートマップを保存しました: {}", path.display()));
+                if let Some(peak) = info.heatmap.as_deref().and_then(heatmap::most_replayed) {
+                    self.report(t!(
+                        "   最も再生された部分: {}〜{}",
+                        format_length(peak.start_time as u64),
+                        format_length(peak.end_time as u64)
+                    ));
+                }
+            }
+            Ok(None) => self.report(t!("   ヒートマップはありません: {}", file.display())),
+            Err(e) => self.report_error(t!("警告: {}", e)),
+        }
+    }
+
+    /// 変換の完了を待ち、結果を表示（作成したファイルを返す）
+    fn finish_encodes(&self) -> Vec<PathBuf> {
+        let Some(queue) = &self.encode_queue else {
+            return Vec::new();
+        };
+        let results = queue.wait();
+        for output in &results.created {
+            println!("{}", t!("🎞  変換しました: {}", output.display()));
+        }
+        for (source, e) in &results.failed {
+            eprintln!("❌ {}: {}", source.display(), e);
+        }
+        results.created
+    }
+
+    /// 終了要求・中止要求によって残りのダウンロードを中止した場合のエラー
+    fn interrupted_error(&self, remaining: usize) -> YtdlError {
+        if self.cancel.is_cancelled() {
+            YtdlError::Cancelled(remaining)
+        } else {
+            YtdlError::DownloadFailed(t!(
+                "終了要求により中断しました（未処理: {}件）",
+                i18n::number(remaining)
+            ))
+        }
+    }
+
+    /// ダウンロードしたファイルのハッシュを記録（失敗しても警告のみ）
+    fn record_hashes(&self, files: &[PathBuf]) {
+        let history = match HashHistory::open() {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("{}", t!("警告: ハッシュを記録できません: {}", e));
+                return;
+            }
+        };
+        for file in files {
+            if let Err(e) = history.record(file) {
+                eprintln!("{}", t!("警告: ハッシュを記録できません: {}: {}", file.display(), e));
+            }
+        }
+    }
+
+    /// ダウンロードが完了したファイルをrcloneで転送
+    fn upload_completed(&self, files: &[PathBuf], output_dir: Option<&Path>) -> Result<()> {
+        let Some(uploader) = &self.uploader else {
+            return Ok(());
+        };
+
+        // 転送先でのパスは出力先ディレクトリ（振り分け前）からの相対パス
+        let base = self.output_dir().or(output_dir);
+        self.update_status(|status| status.state = "uploading".to_string());
+        let mut failed = Vec::new();
+        for file in files {
+            let relative = base
+                .and_then(|base| file.strip_prefix(base).ok())
+                .or_else(|| file.file_name().map(Path::new))
+                .unwrap_or(file);
+            let _span = tracing::info_span!("upload", file = %file.display()).entered();
+            if let Err(e) = uploader.upload(file, relative) {
+                eprintln!("❌ {}", e);
+                failed.push(file.display().to_string());
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(YtdlError::UploadFailed(t!(
+                "{}件のファイルを転送できませんでした",
+                i18n::number(failed.len())
+            )))
+        }
+    }
+
+    /// yt-dlpに指定するフォーマット（音声抽出で元の動画を残す場合は映像も取得する）
+    fn download_format(&self) -> &str {
+        if self.profile.extract_audio && self.cli.keep_video {
+            KEEP_VIDEO_FORMAT
+        } else if self.cli.split_album && !self.profile.extract_audio {
+            ALBUM_FORMAT
+        } else if let Some(format) = &self.cli.format_string {
+            format
+        } else if let Some(format) = self.site().and_then(|site| site.format.as_deref()) {
+            format
+        } else {
+            &self.profile.format
+        }
+    }
+
+    /// ダウンロード中のURLに一致したサイトの設定（`[site.*]`）
+    fn site(&self) -> Option<&SiteConfig> {
+        let index = (*self.current_site.lock().ok()?)?;
+        self.cli.sites.get(index).map(|(_, site)| site)
+    }
+
+    /// 出力先ディレクトリ（サイトの設定があればそちらを使う）
+    fn output_dir(&self) -> Option<&Path> {
+        self.site()
+            .and_then(|site| site.output.as_deref())
+            .or(self.cli.output_dir.as_deref())
+    }
+
+    /// ファイル名のテンプレート（このツールの変数はyt-dlpの変数で近いものに置き換える）
+    fn output_template(&self) -> String {
+        template::fallback(&self.template_source(), &self.profile.name)
+    }
+
+    /// 動画ごとのファイル名のテンプレート（このツールの変数を使う場合のみ）
+    fn item_output_template(&self, info: &VideoInfo) -> Option<String> {
+        let source = self.template_source();
+        template::uses_custom_fields(&source).then(|| template::expand(&source, info, &self.profile.name))
+    }
+
+    /// 指定されたファイル名のテンプレート
+    fn template_source(&self) -> String {
+        if self.cli.split_album {
+            album::ALBUM_TEMPLATE.to_string()
+        } else if let Some(template) = self.site().and_then(|site| site.output_template.as_ref()) {
+            template.clone()
+        } else if let Some(template) = &self.cli.output_template {
+            template.clone()
+        } else {
+            "%(title)s-%(id)s.%(ext)s".to_string()
+        }
+    }
+
+    /// 対象URL（未指定ならエラー）
+    fn urls(&self) -> Result<&[String]> {
+        if self.cli.url.is_empty() {
+            return Err(YtdlError::Other(t!("URLが指定されていません").to_string()));
+        }
+        Ok(&self.cli.url)
+    }
+
+    /// yt-dlpのコマンド（`--ytdlp-env` と設定ファイルの env の環境変数を適用）
+    ///
+    /// 値が空の環境変数は引き継がずに削除します。
+    fn ytdlp_command(&self) -> Command {
         let mut cmd = Command::new("yt-dlp");
+        for (key, value) in &self.cli.ytdlp_env {
+            if value.is_empty() {
+                cmd.env_remove(key);
+            } else {
+                cmd.env(key, value);
+            }
+        }
+        cmd
+    }
+
+    /// yt-dlpコマンドを構築
+    fn build_command(
+        &self,
+        url: &str,
+        output_dir: Option<&Path>,
+        whole_playlist: bool,
+        options: &ItemOptions,
+    ) -> Result<Command> {
+        let mut cmd = self.ytdlp_command();
 
         // 基本オプション
         cmd.arg("--newline"); // 進捗を毎行出力
         cmd.arg("--progress"); // 進捗表示を有効化
 
         // 品質設定
-        let format_str = self.cli.quality.to_ytdlp_format();
-        cmd.arg("-f").arg(&format_str);
+        cmd.arg("-f").arg(options.format.unwrap_or(self.download_format()));
 
         // 音声抽出が必要な場合
-        if self.cli.quality.needs_audio_extraction() {
+        if self.profile.extract_audio || self.cli.split_album {
             cmd.arg("-x"); // 音声抽出
-            cmd.arg("--audio-format").arg("mp3"); // MP3形式に変換
-            cmd.arg("--audio-quality").arg("0"); // 最高品質
+            cmd.arg("--audio-format").arg(self.profile.audio_format());
+            // 元の形式のままなら再エンコードしないため、音質の指定は不要
+            if self.profile.audio_format() != AudioFormat::Best.to_ytdlp_arg() {
+                cmd.arg("--audio-quality")
+                    .arg(self.cli.audio_quality.to_ytdlp_arg());
+            }
+        } else if let Some(container) = options.container.or(self.profile.container.as_deref()) {
+            // 結合したファイルは結合時に、単体のファイルは再格納で同じ形式にする
+            cmd.arg("--merge-output-format").arg(container);
+            cmd.arg("--remux-video").arg(container);
         }
 
-        // Cookie設定
-        if let Some(browser) = &self.cli.cookie_browser {
-            let detector = CookieDetector::from_str(browser)?;
-            let browser_arg = detector.get_ytdlp_browser_arg();
-            cmd.arg("--cookies-from-browser").arg(browser_arg);
-
-            if self.cli.verbose {
-                println!("🍪 {}ブラウザのCookieを使用します", browser);
-            }
+        // プリセットの後処理
+        for step in &self.profile.postprocess {
+            cmd.args(step.ytdlp_args());
+        }
 
-            // Cookie検出を試みる（警告のみ）
-            if let Err(e) = detector.detect_cookie_path() {
-                eprintln!("警告: Cookieパスの検出に失敗しました: {}", e);
-                eprintln!("ヒント: {}でYouTubeにログインしていることを確認してください", browser);
-            }
-        } else if self.cli.verbose {
-            println!("⚠️  Cookieを使用しません（Bot判定される可能性があります）");
+        // 中間ファイルを残す
+        if self.cli.keep_video {
+            cmd.arg("--keep-video");
+        }
+        if self.cli.keep_fragments {
+            cmd.arg("--keep-fragments");
         }
 
-        // 出力先設定
-        let output_template = if let Some(template) = &self.cli.output_template {
-            template.clone()
-        } else {
-            "%(title)s-%(id)s.%(ext)s".to_string()
-        };
+        // Cookie設定
+        self.apply_cookies(&mut cmd)?;
+        self.report_cookie_source();
 
-        let output_path = if let Some(output_dir) = &self.cli.output_dir {
-            output_dir.join(output_template).to_string_lossy().to_string()
+        // 出力先設定
+        let output_path = if let Some(stem) = &options.output_stem {
+            // 別名はそのまま使う（"%"はテンプレートとして解釈されないようにする）
+            format!("{}.%(ext)s", stem.to_string_lossy().replace('%', "%%"))
         } else {
-            output_template
+            let template = options.output_template.clone().unwrap_or_else(|| self.output_template());
+            match output_dir {
+                Some(output_dir) => output_dir.join(template).to_string_lossy().to_string(),
+                None => template,
+            }
         };
         cmd.arg("-o").arg(output_path);
+        if options.force_overwrites {
+            cmd.arg("--force-overwrites");
+        } else if let Some(arg) = self.cli.on_collision.and_then(|policy| policy.ytdlp_arg()) {
+            cmd.arg(arg);
+        }
 
-        // プレイリスト設定
-        if self.cli.playlist {
-            // プレイリスト範囲
-            if let Some(start) = self.cli.playlist_start {
-                cmd.arg("--playlist-start").arg(start.to_string());
-            }
-            if let Some(end) = self.cli.playlist_end {
-                cmd.arg("--playlist-end").arg(end.to_string());
-            }
-        } else {
-            // 単一動画のみダウンロード
-            cmd.arg("--no-playlist");
+        // アルバムとしてチャプターごとに分割（アルバム名は動画タイトル）
+        if self.cli.split_album {
+            let track_path = match output_dir {
+                Some(output_dir) => output_dir.join(album::TRACK_TEMPLATE),
+                None => PathBuf::from(album::TRACK_TEMPLATE),
+            };
+            cmd.arg("-o").arg(format!("chapter:{}", track_path.to_string_lossy()));
+            cmd.arg("--split-chapters");
+            cmd.arg("--parse-metadata").arg("title:%(album)s");
+            cmd.arg("--parse-metadata").arg("%(artist,uploader)s:%(artist)s");
+            cmd.arg("--embed-metadata");
         }
 
+        // 翻訳されたタイトル・説明文（ファイル名とタグに反映される）と、やり直す場合のプレイヤークライアント
+        self.apply_extractor_args(&mut cmd, options.player_client);
+
+        // プレイリスト設定
+        self.apply_playlist_args(&mut cmd, whole_playlist);
+
         // 字幕設定
-        if self.cli.download_subtitle {
+        if self.cli.download_subtitle || self.embed_subs {
             cmd.arg("--write-subs"); // 字幕をダウンロード
             cmd.arg("--write-auto-subs"); // 自動生成字幕もダウンロード
-            cmd.arg("--sub-lang").arg("ja,en"); // 日本語と英語
+            cmd.arg("--sub-lang").arg(subtitles::SUB_LANGS); // 日本語と英語
+        }
+        if self.embed_subs {
+            cmd.arg("--sub-format").arg(subtitles::SUB_FORMAT);
+            cmd.arg("--embed-subs");
         }
 
         // メタデータ設定
@@ -263,6 +2106,15 @@ impl YtdlpWrapper {
             cmd.arg("--limit-rate").arg(rate);
         }
 
+        // 断片（DASH/HLS）の同時ダウンロード（`--auto-tune` では調整した数）
+        let fragments = match &self.auto_tune {
+            Some(tuner) => Some(tuner.fragments()),
+            None => self.cli.fragments,
+        };
+        if let Some(fragments) = fragments {
+            cmd.arg("--concurrent-fragments").arg(fragments.to_string());
+        }
+
         // リトライ設定
         cmd.arg("--retries").arg(self.cli.retry_count.to_string());
 
@@ -273,10 +2125,10 @@ impl YtdlpWrapper {
         }
 
         // その他の推奨オプション
-        cmd.arg("--no-warnings"); // 警告を抑制
         // --no-call-home は非推奨になったため削除
         cmd.arg("--ignore-errors"); // エラーが出ても続行
-        cmd.arg("--no-continue"); // 部分ダウンロードファイルを再利用しない
+        // 途中までダウンロードしたファイル（.part）を再利用するか
+        cmd.arg(if self.resumes() { "--continue" } else { "--no-continue" });
 
         // エンコーディング設定（Windows用）
         #[cfg(target_os = "windows")]
@@ -284,50 +2136,341 @@ impl YtdlpWrapper {
             cmd.arg("--encoding").arg("utf-8");
         }
 
-        // URL
-        if let Some(url) = &self.cli.url {
-            cmd.arg(url);
-        } else {
-            return Err(YtdlError::Other("URLが指定されていません".to_string()));
+        // サイトごとの設定で追加する引数
+        if let Some(site) = self.site() {
+            cmd.args(&site.ytdlp_args);
         }
 
+        // URL
+        cmd.arg(url);
+
         Ok(cmd)
     }
 
-    /// ドライラン（実際にはダウンロードせず、情報のみ取得）
-    #[allow(dead_code)]
-    pub fn dry_run(&self) -> Result<()> {
-        let mut cmd = Command::new("yt-dlp");
-        cmd.arg("--dump-json");
-        cmd.arg("--flat-playlist");
+    /// Cookieの引数を追加（cookies.txt、ブラウザのCookieのスナップショット、ブラウザの順）
+    fn apply_cookies(&self, cmd: &mut Command) -> Result<()> {
+        if let Some(file) = &self.cli.cookies_file {
+            cmd.arg("--cookies").arg(file);
+        } else if let Some(snapshot) = &self.cookie_snapshot {
+            cmd.arg("--cookies-from-browser")
+                .arg(snapshot.get_ytdlp_browser_arg());
+        } else if let Some(browser) = &self.cli.cookie_browser {
+            let detector = CookieDetector::from_str(browser)?;
+            cmd.arg("--cookies-from-browser").arg(detector.get_ytdlp_browser_arg());
+        }
+        Ok(())
+    }
+
+    /// ダウンロードに使うCookieを表示し、ブラウザのCookieが見つからない場合は警告
+    ///
+    /// Cookieの引数は [`apply_cookies`](Self::apply_cookies) で追加します（cookies.txt・スナップショットでは確認しない）。
+    fn report_cookie_source(&self) {
+        if self.cli.cookies_file.is_some() || self.cookie_snapshot.is_some() {
+            return;
+        }
+        let Some(browser) = &self.cli.cookie_browser else {
+            if self.cli.verbose > 0 {
+                println!("{}", t!("⚠️  Cookieを使用しません（Bot判定される可能性があります）"));
+            }
+            return;
+        };
+        if self.cli.verbose > 0 {
+            println!("{}", t!("🍪 {}ブラウザのCookieを使用します", browser));
+        }
+        // Cookie検出を試みる（警告のみ）
+        if let Ok(detector) = CookieDetector::from_str(browser) {
+            if let Err(e) = detector.detect_cookie_path() {
+                eprintln!("{}", t!("警告: Cookieパスの検出に失敗しました: {}", e));
+                eprintln!("{}", t!("ヒント: {}でYouTubeにログインしていることを確認してください", browser));
+            }
+        }
+    }
+
+    /// メタデータの言語・プレイヤークライアントの引数を追加（1つの `--extractor-args` にまとめる）
+    fn apply_extractor_args(&self, cmd: &mut Command, player_client: Option<&str>) {
+        let arg = match (&self.cli.metadata_lang, player_client) {
+            (Some(lang), Some(client)) => {
+                format!("{};player_client={}", metadata::metadata_lang_arg(lang), client)
+            }
+            (Some(lang), None) => metadata::metadata_lang_arg(lang),
+            (None, Some(client)) => format!("youtube:player_client={}", client),
+            (None, None) => return,
+        };
+        cmd.arg("--extractor-args").arg(arg);
+    }
 
-        if let Some(url) = &self.cli.url {
-            cmd.arg(url);
+    /// プレイリスト関連の引数を追加
+    fn apply_playlist_args(&self, cmd: &mut Command, whole_playlist: bool) {
+        let chunk = self.chunk.lock().ok().and_then(|chunk| *chunk);
+        if let (true, Some((start, end))) = (whole_playlist, chunk) {
+            // 分割してダウンロードしている区切り
+            cmd.arg("--playlist-items").arg(format!("{}:{}", start, end));
+        } else if whole_playlist {
+            // プレイリスト範囲
+            if let Some(start) = self.cli.playlist_start {
+                cmd.arg("--playlist-start").arg(start.to_string());
+            }
+            if let Some(end) = self.cli.playlist_end {
+                cmd.arg("--playlist-end").arg(end.to_string());
+            }
         } else {
-            return Err(YtdlError::Other("URLが指定されていません".to_string()));
+            // 単一動画のみダウンロード
+            cmd.arg("--no-playlist");
         }
+    }
 
-        if let Some(browser) = &self.cli.cookie_browser {
-            let detector = CookieDetector::from_str(browser)?;
-            let browser_arg = detector.get_ytdlp_browser_arg();
-            cmd.arg("--cookies-from-browser").arg(browser_arg);
+    /// ドライラン（実際にはダウンロードせず、ダウンロード予定の動画一覧のみ表示）
+    ///
+    /// アーカイブ済みの動画はyt-dlp側で除外されるため、
+    /// 表示されるのは実際に新しくダウンロードされる動画のみです。
+    pub fn dry_run(&self) -> Result<()> {
+        println!("{}", t!("🔍 ダウンロード予定の動画を確認中...\n"));
+        let mut videos = Vec::new();
+        for url in self.urls()? {
+            videos.extend(self.probe_videos(url)?);
         }
 
-        let output = cmd
-            .output()
-            .map_err(|e| YtdlError::ProcessError(format!("ドライラン実行失敗: {}", e)))?;
+        println!("{}", t!("=== ダウンロード予定の動画 ==="));
+        let mut total_bytes = 0;
+        let mut unknown_size = 0;
+        let mut over_limit = 0;
+        for (i, info) in videos.iter().enumerate() {
+            let estimate = match self.cli.target_size {
+                Some(max_bytes) => info
+                    .best_format_under(max_bytes, self.profile.extract_audio)
+                    .map(|(_, bytes)| bytes),
+                None => info.size_estimate(),
+            };
+            let size = match estimate {
+                Some(bytes) => {
+                    total_bytes += bytes;
+                    t!("約 {}", format_bytes(bytes))
+                }
+                None if self.cli.target_size.is_some() => {
+                    over_limit += 1;
+                    t!("上限に収まるフォーマットなし").to_string()
+                }
+                None => {
+                    unknown_size += 1;
+                    t!("サイズ不明").to_string()
+                }
+            };
+            println!(
+                "{:>4}. [{}] {} ({}) - {}",
+                i + 1,
+                info.upload_date_str().map_or_else(|| t!("日付不明").to_string(), |date| i18n::localize_date(&date)),
+                info.display_title(),
+                info.id,
+                size
+            );
+            if !self.cli.routes.is_empty() {
+                println!("        → {}", self.route_dir(info).display());
+            }
+            if self.cli.only_creative_commons && !info.is_creative_commons() {
+                println!("{}", t!("        ⏭ クリエイティブ・コモンズではないためスキップ"));
+            }
+            if !self.is_allowed_channel(info) {
+                println!("{}", t!("        ⛔ 許可されたチャンネルの動画ではないためダウンロードしません"));
+            }
+            if self.embed_subs {
+                if let Some((container, format)) =
+                    subtitles::embed_container(info, self.profile.container.as_deref())
+                {
+                    println!("{}", t!("        字幕（{}）を埋め込むため{}で保存", format, container.to_uppercase()));
+                }
+            }
+            if let Some((_, existing)) = self.find_collision(info, &self.route_dir(info)) {
+                println!("{}", t!("        ⚠ 同名のファイルがあります: {}", existing.display()));
+            }
+        }
+        println!("==============================");
 
-        if output.status.success() {
-            let json_output = String::from_utf8_lossy(&output.stdout);
-            println!("=== 動画情報 ===");
-            println!("{}", json_output);
-            Ok(())
+        if videos.is_empty() {
+            println!("{}", t!("新しくダウンロードされる動画はありません（アーカイブ済み）"));
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(YtdlError::DownloadFailed(format!(
-                "情報取得失敗: {}",
-                error
-            )))
+            print!("{}", t!("合計: {}本 / 約 {}", i18n::number(videos.len()), format_bytes(total_bytes)));
+            if unknown_size > 0 {
+                print!("{}", t!("（サイズ不明 {}本を除く）", i18n::number(unknown_size)));
+            }
+            println!();
+            if over_limit > 0 {
+                println!("{}", t!("サイズ上限に収まらずスキップ: {}本", i18n::number(over_limit)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// ダウンロード対象の動画情報を取得（アーカイブ済みの動画は除外）
+    fn probe_videos(&self, url: &str) -> Result<Vec<VideoInfo>> {
+        let _span = tracing::info_span!("metadata_probe", url).entered();
+        let mut cmd = self.ytdlp_command();
+        cmd.arg("--dump-json"); // 1動画につき1行のJSONを出力
+        cmd.arg("--ignore-errors");
+
+        // サイズ推定のため、実際のダウンロードと同じフォーマットを選択
+        cmd.arg("-f").arg(self.download_format());
+        // 同名ファイルの確認用に、保存先のファイル名（出力先からの相対パス）も出力させる
+        cmd.arg("-o").arg(self.output_template());
+
+        self.apply_cookies(&mut cmd)?;
+        self.apply_extractor_args(&mut cmd, None);
+        self.apply_playlist_args(&mut cmd, self.cli.playlist);
+
+        if let Some(archive) = &self.cli.download_archive {
+            cmd.arg("--download-archive")
+                .arg(archive.to_string_lossy().to_string());
+        }
+
+        cmd.arg(url);
+
+        if self.cli.verbose > 0 || self.cli.print_command {
+            println!("{}", t!("\n実行コマンド: {}\n", redact::command_line(&cmd)));
+        }
+
+        // 動画ごとに1行ずつ届くため、プレイリスト全体の取得中でも中止できる
+        let mut videos = Vec::new();
+        let output = self.cancel.run_probe(&mut cmd, |line| {
+            if line.trim().is_empty() {
+                return;
+            }
+            match VideoInfo::from_json_line(line) {
+                Ok(info) => videos.push(info),
+                Err(e) => eprintln!("{}", t!("警告: {}", e)),
+            }
+        })?;
+
+        if videos.is_empty() && !output.status.success() {
+            return Err(YtdlError::DownloadFailed(t!("情報取得失敗: {}", output.stderr)));
         }
+
+        Ok(videos)
+    }
+}
+
+/// 複数のURLをダウンロードした場合のURLごとの結果（URL, ダウンロードしたファイル数, 成功したか）
+fn print_url_results(results: &[(&str, usize, bool)]) {
+    if results.is_empty() {
+        return;
+    }
+    println!("{}", t!("\n=== URLごとの結果（{}件） ===", i18n::number(results.len())));
+    for (url, files, succeeded) in results {
+        let mark = if *succeeded { "✓" } else { "❌" };
+        println!("{} {}{}", mark, url, t!("（{}ファイル）", files));
+    }
+    let succeeded = results.iter().filter(|(_, _, succeeded)| *succeeded).count();
+    let files: usize = results.iter().map(|(_, files, _)| files).sum();
+    println!(
+        "{}",
+        t!(
+            "成功 {}件 / 失敗 {}件、合計 {}ファイル",
+            i18n::number(succeeded),
+            i18n::number(results.len() - succeeded),
+            i18n::number(files)
+        )
+    );
+}
+
+/// 失敗したURLの一覧を表示（再実行しやすいよう、URLは1行ずつ）
+fn print_failed_urls(failed: &[(&str, YtdlError)]) {
+    if failed.is_empty() {
+        return;
+    }
+    eprintln!("{}", t!("\n=== 失敗したURL（{}件） ===", i18n::number(failed.len())));
+    for (url, e) in failed {
+        eprintln!("{}\n    {}", url, e);
+    }
+    eprintln!("==========================");
+}
+
+/// 動画の表示名（プレイリストなら "[2/5] タイトル"）
+fn item_label(info: &ItemInfo) -> String {
+    let title = info
+        .title
+        .as_deref()
+        .or(info.id.as_deref())
+        .unwrap_or(t!("（タイトル不明）"));
+    match (info.playlist_index, info.playlist_count) {
+        (Some(index), Some(count)) => format!("[{}/{}] {}", index, count, title),
+        _ => title.to_string(),
+    }
+}
+
+/// 既存のファイルがあるためスキップした件数を表示
+fn print_skipped(files: &[PathBuf]) {
+    if files.is_empty() {
+        return;
+    }
+    println!("{}", t!("\n⏭ 既存のファイルがあるためスキップ: {}件", i18n::number(files.len())));
+    for file in files {
+        println!("  - {}", file.display());
+    }
+}
+
+/// 失敗した動画のIDとURL（開始イベントのURL、なければ動画ID）
+fn failed_items(events: &[YtdlpEvent]) -> Vec<(String, String)> {
+    let mut items: Vec<(String, String)> = Vec::new();
+    for event in events {
+        let YtdlpEvent::Error { id: Some(id), .. } = event else {
+            continue;
+        };
+        let url = events
+            .iter()
+            .find_map(|event| match event {
+                YtdlpEvent::Started { info } if info.id.as_deref() == Some(id.as_str()) => {
+                    info.webpage_url.clone()
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| id.clone());
+        if !items.iter().any(|(_, existing)| *existing == url) {
+            items.push((id.clone(), url));
+        }
+    }
+    items
+}
+
+/// 完了した動画の情報
+fn finished_items(events: &[YtdlpEvent]) -> Vec<&ItemInfo> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            YtdlpEvent::Finished { info } => Some(info),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 複数の動画を処理した場合、または失敗があった場合に結果をまとめて表示
+fn print_item_summary(events: &[YtdlpEvent]) {
+    let started = events
+        .iter()
+        .filter(|event| matches!(event, YtdlpEvent::Started { .. }))
+        .count();
+    let finished = finished_items(events).len();
+    let errors: Vec<(Option<&str>, &str)> = events
+        .iter()
+        .filter_map(|event| match event {
+            YtdlpEvent::Error { id, message } => Some((id.as_deref(), message.as_str())),
+            _ => None,
+        })
+        .collect();
+    if started <= 1 && errors.is_empty() {
+        return;
+    }
+
+    println!("{}", t!("\n📊 完了: {}本 / 失敗: {}件", i18n::number(finished), i18n::number(errors.len())));
+    let mut failed_id = None;
+    for (id, message) in errors {
+        match id {
+            Some(id) => {
+                println!("  ✗ {}: {}", id, message);
+                failed_id = Some(id);
+            }
+            None => println!("  ✗ {}", message),
+        }
+    }
+    if let Some(id) = failed_id {
+        println!("{}", t!("💡 動画ごとの詳しい出力: ytdl logs {}", id));
     }
 }